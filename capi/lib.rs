@@ -0,0 +1,13 @@
+/* Copyright 2018 Torbjørn Birch Moltu
+ *
+ * Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+ * http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+ * http://opensource.org/licenses/MIT>, at your option. This file may not be
+ * copied, modified, or distributed except according to those terms.
+ */
+
+//! The functions in `encode_unicode::capi`, built as a library for C
+//! with `cargo build --release` in this directory.
+
+extern crate encode_unicode_rs;
+pub use encode_unicode_rs::capi::*;
@@ -0,0 +1,71 @@
+/* Copyright 2018 Torbjørn Birch Moltu
+ *
+ * Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+ * http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+ * http://opensource.org/licenses/MIT>, at your option. This file may not be
+ * copied, modified, or distributed except according to those terms.
+ */
+
+#ifndef ENCODE_UNICODE_H
+#define ENCODE_UNICODE_H
+
+/* Generated from src/capi.rs by cbindgen with cbindgen.toml; don't edit it by hand.
+ * The functions are documented there, and are in the library built by
+ * `cargo build --release --manifest-path capi/Cargo.toml`. */
+
+#include <stddef.h>
+#include <stdint.h>
+
+typedef enum eu_status {
+    EU_STATUS_OK = 0,
+    EU_STATUS_TOO_LONG_SEQUENCE = 1,
+    EU_STATUS_CONTINUATION_BYTE = 2,
+    EU_STATUS_NOT_A_CONTINUATION_BYTE = 3,
+    EU_STATUS_OVER_LONG = 4,
+    EU_STATUS_UTF16_RESERVED = 5,
+    EU_STATUS_TOO_HIGH = 6,
+    EU_STATUS_TOO_SHORT = 7,
+    EU_STATUS_FIRST_LOW_SURROGATE = 8,
+    EU_STATUS_MISSING_SECOND = 9,
+    EU_STATUS_SECOND_NOT_LOW_SURROGATE = 10,
+    EU_STATUS_BUFFER_TOO_SMALL = 11,
+    EU_STATUS_NULL_POINTER = 12,
+} eu_status;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+enum eu_status eu_utf8_decode(const uint8_t *src,
+                              size_t len,
+                              uint32_t *codepoint,
+                              size_t *consumed);
+
+enum eu_status eu_utf16_decode(const uint16_t *src,
+                               size_t len,
+                               uint32_t *codepoint,
+                               size_t *consumed);
+
+enum eu_status eu_utf8_validate(const uint8_t *src, size_t len, size_t *valid_up_to);
+
+enum eu_status eu_utf16_validate(const uint16_t *src, size_t len, size_t *valid_up_to);
+
+enum eu_status eu_utf8_to_utf16(const uint8_t *src,
+                                size_t src_len,
+                                uint16_t *dst,
+                                size_t dst_len,
+                                size_t *read,
+                                size_t *written);
+
+enum eu_status eu_utf16_to_utf8(const uint16_t *src,
+                                size_t src_len,
+                                uint8_t *dst,
+                                size_t dst_len,
+                                size_t *read,
+                                size_t *written);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* ENCODE_UNICODE_H */
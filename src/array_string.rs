@@ -0,0 +1,407 @@
+/* Copyright 2018 Torbjørn Birch Moltu
+ *
+ * Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+ * http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+ * http://opensource.org/licenses/MIT>, at your option. This file may not be
+ * copied, modified, or distributed except according to those terms.
+ */
+
+//! Strings stored in fixed-size arrays, which never split a character.
+
+use utf8_char::Utf8Char;
+use utf16_char::Utf16Char;
+use errors::CapacityError;
+extern crate core;
+use self::core::{cmp, fmt, hash, str};
+use self::core::borrow::Borrow;
+use self::core::iter::{FromIterator, from_fn};
+use self::core::ops::Deref;
+
+
+/// A UTF-8 string of up to `N` bytes, stored inline.
+///
+/// Characters are only added if all of their bytes fit, so the contents are
+/// always valid UTF-8 and can be borrowed as a `str`.
+///
+/// `Extend` and `FromIterator` panic when the capacity is exceeded;
+/// use `try_extend()` and `try_from_iter()` to get an error instead.
+///
+/// # Examples
+///
+#[cfg_attr(feature="std", doc=" ```")]
+#[cfg_attr(not(feature="std"), doc=" ```no_compile")]
+/// use encode_unicode::{ArrayUtf8String, Utf8Char};
+/// use std::fmt::Write;
+///
+/// let mut s = ArrayUtf8String::<6>::new();
+/// s.push(Utf8Char::from('æ')).unwrap();
+/// write!(s, "{}", 1+2).unwrap();
+/// assert_eq!(&*s, "æ3");
+/// assert!(s.push_str("💣").is_err(), "doesn't fit");
+/// s.push(Utf8Char::from('ø')).unwrap();
+/// assert_eq!(s.len(), 5);
+/// assert_eq!(s.remaining_capacity(), 1);
+/// ```
+#[derive(Clone,Copy)]
+pub struct ArrayUtf8String<const N: usize> {
+    bytes: [u8; N],
+    len: usize,
+}
+impl<const N: usize> ArrayUtf8String<N> {
+    /// Create an empty string.
+    pub const fn new() -> Self {
+        ArrayUtf8String{ bytes: [0; N],  len: 0 }
+    }
+    /// The number of bytes it can store.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+    /// The number of bytes that are free.
+    pub fn remaining_capacity(&self) -> usize {
+        N - self.len
+    }
+    /// Append a character, or return an error if it doesn't fit.
+    pub fn push(&mut self,  u8c: Utf8Char) -> Result<(),CapacityError> {
+        self.push_str(u8c.as_str())
+    }
+    /// Append a string if all of it fits, and otherwise leave `self` unchanged.
+    pub fn push_str(&mut self,  s: &str) -> Result<(),CapacityError> {
+        if s.len() > self.remaining_capacity() {
+            return Err(CapacityError);
+        }
+        self.bytes[self.len..self.len+s.len()].copy_from_slice(s.as_bytes());
+        self.len += s.len();
+        Ok(())
+    }
+    /// Remove and return the last character.
+    pub fn pop(&mut self) -> Option<Utf8Char> {
+        let c = self.as_str().chars().next_back()?;
+        self.len -= c.len_utf8();
+        Some(Utf8Char::from(c))
+    }
+    /// Remove everything.
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+    /// Borrow the contents as a `str`.
+    pub fn as_str(&self) -> &str {
+        unsafe{ str::from_utf8_unchecked(&self.bytes[..self.len]) }
+    }
+    /// Append characters until one doesn't fit.
+    ///
+    /// The characters before the one that didn't fit are kept.
+    pub fn try_extend<U:Borrow<Utf8Char>, I:IntoIterator<Item=U>>
+    (&mut self,  iter: I) -> Result<(),CapacityError> {
+        for u8c in iter {
+            self.push(*u8c.borrow())?;
+        }
+        Ok(())
+    }
+    /// Collect characters, or return an error if they don't fit.
+    pub fn try_from_iter<U:Borrow<Utf8Char>, I:IntoIterator<Item=U>>
+    (iter: I) -> Result<Self,CapacityError> {
+        let mut s = Self::new();
+        s.try_extend(iter)?;
+        Ok(s)
+    }
+}
+impl<const N: usize> Default for ArrayUtf8String<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<const N: usize> str::FromStr for ArrayUtf8String<N> {
+    type Err = CapacityError;
+    fn from_str(s: &str) -> Result<Self,CapacityError> {
+        let mut array = Self::new();
+        array.push_str(s)?;
+        Ok(array)
+    }
+}
+impl<const N: usize> Deref for ArrayUtf8String<N> {
+    type Target = str;
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+impl<const N: usize> AsRef<str> for ArrayUtf8String<N> {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+impl<const N: usize> AsRef<[u8]> for ArrayUtf8String<N> {
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+impl<const N: usize> Borrow<str> for ArrayUtf8String<N> {
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+impl<const N: usize> fmt::Write for ArrayUtf8String<N> {
+    fn write_str(&mut self,  s: &str) -> fmt::Result {
+        self.push_str(s).map_err(|_| fmt::Error )
+    }
+    fn write_char(&mut self,  c: char) -> fmt::Result {
+        self.push(Utf8Char::from(c)).map_err(|_| fmt::Error )
+    }
+}
+impl<const N: usize> Extend<Utf8Char> for ArrayUtf8String<N> {
+    fn extend<I:IntoIterator<Item=Utf8Char>>(&mut self,  iter: I) {
+        self.try_extend(iter).expect("ArrayUtf8String is full")
+    }
+}
+impl<'a, const N: usize> Extend<&'a Utf8Char> for ArrayUtf8String<N> {
+    fn extend<I:IntoIterator<Item=&'a Utf8Char>>(&mut self,  iter: I) {
+        self.try_extend(iter).expect("ArrayUtf8String is full")
+    }
+}
+impl<const N: usize> FromIterator<Utf8Char> for ArrayUtf8String<N> {
+    fn from_iter<I:IntoIterator<Item=Utf8Char>>(iter: I) -> Self {
+        Self::try_from_iter(iter).expect("ArrayUtf8String is full")
+    }
+}
+impl<'a, const N: usize> FromIterator<&'a Utf8Char> for ArrayUtf8String<N> {
+    fn from_iter<I:IntoIterator<Item=&'a Utf8Char>>(iter: I) -> Self {
+        Self::try_from_iter(iter).expect("ArrayUtf8String is full")
+    }
+}
+impl<const N: usize, const M: usize> PartialEq<ArrayUtf8String<M>> for ArrayUtf8String<N> {
+    fn eq(&self,  rhs: &ArrayUtf8String<M>) -> bool {
+        self.as_str() == rhs.as_str()
+    }
+}
+impl<const N: usize> PartialEq<str> for ArrayUtf8String<N> {
+    fn eq(&self,  rhs: &str) -> bool {
+        self.as_str() == rhs
+    }
+}
+impl<'a, const N: usize> PartialEq<&'a str> for ArrayUtf8String<N> {
+    fn eq(&self,  rhs: &&'a str) -> bool {
+        self.as_str() == *rhs
+    }
+}
+impl<const N: usize> Eq for ArrayUtf8String<N> {}
+impl<const N: usize> cmp::PartialOrd for ArrayUtf8String<N> {
+    fn partial_cmp(&self,  rhs: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(rhs))
+    }
+}
+impl<const N: usize> cmp::Ord for ArrayUtf8String<N> {
+    fn cmp(&self,  rhs: &Self) -> cmp::Ordering {
+        self.as_str().cmp(rhs.as_str())
+    }
+}
+impl<const N: usize> hash::Hash for ArrayUtf8String<N> {
+    fn hash<H : hash::Hasher>(&self,  state: &mut H) {
+        self.as_str().hash(state);
+    }
+}
+impl<const N: usize> fmt::Debug for ArrayUtf8String<N> {
+    fn fmt(&self,  fmtr: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), fmtr)
+    }
+}
+impl<const N: usize> fmt::Display for ArrayUtf8String<N> {
+    fn fmt(&self,  fmtr: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), fmtr)
+    }
+}
+
+
+/// A UTF-16 string of up to `N` units, stored inline.
+///
+/// Surrogate pairs are only added if both units fit, so the contents are
+/// always valid UTF-16.
+///
+/// `Extend` and `FromIterator` panic when the capacity is exceeded;
+/// use `try_extend()` and `try_from_iter()` to get an error instead.
+///
+/// # Examples
+///
+#[cfg_attr(feature="std", doc=" ```")]
+#[cfg_attr(not(feature="std"), doc=" ```no_compile")]
+/// use encode_unicode::{ArrayUtf16String, Utf16Char};
+///
+/// let mut s: ArrayUtf16String<3> = "æ".parse().unwrap();
+/// s.push(Utf16Char::from('A')).unwrap();
+/// assert!(s.push(Utf16Char::from('💣')).is_err(), "needs two units");
+/// assert_eq!(&*s, &[0xe6, 0x41]);
+/// assert_eq!(s.to_string(), "æA");
+/// assert_eq!(s.pop(), Some(Utf16Char::from('A')));
+/// ```
+#[derive(Clone,Copy)]
+pub struct ArrayUtf16String<const N: usize> {
+    units: [u16; N],
+    len: usize,
+}
+impl<const N: usize> ArrayUtf16String<N> {
+    /// Create an empty string.
+    pub const fn new() -> Self {
+        ArrayUtf16String{ units: [0; N],  len: 0 }
+    }
+    /// The number of units it can store.
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+    /// The number of units that are free.
+    pub fn remaining_capacity(&self) -> usize {
+        N - self.len
+    }
+    /// Append a character, or return an error if it doesn't fit.
+    pub fn push(&mut self,  u16c: Utf16Char) -> Result<(),CapacityError> {
+        if u16c.len() > self.remaining_capacity() {
+            return Err(CapacityError);
+        }
+        self.len += u16c.to_slice(&mut self.units[self.len..]);
+        Ok(())
+    }
+    /// Append a string encoded as UTF-16 if all of it fits,
+    /// and otherwise leave `self` unchanged.
+    pub fn push_str(&mut self,  s: &str) -> Result<(),CapacityError> {
+        let old_len = self.len;
+        for c in s.chars() {
+            if let Err(e) = self.push(Utf16Char::from(c)) {
+                self.len = old_len;
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+    /// Remove and return the last character.
+    pub fn pop(&mut self) -> Option<Utf16Char> {
+        let last = *self.as_slice().last()?;
+        let start = match last & 0xfc00 {
+            0xdc00 => self.len - 2,
+            _ => self.len - 1,
+        };
+        let (u16c, _) = Utf16Char::from_slice_start(&self.units[start..self.len]).ok()?;
+        self.len = start;
+        Some(u16c)
+    }
+    /// Remove everything.
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+    /// Borrow the units.
+    pub fn as_slice(&self) -> &[u16] {
+        &self.units[..self.len]
+    }
+    /// Append characters until one doesn't fit.
+    ///
+    /// The characters before the one that didn't fit are kept.
+    pub fn try_extend<U:Borrow<Utf16Char>, I:IntoIterator<Item=U>>
+    (&mut self,  iter: I) -> Result<(),CapacityError> {
+        for u16c in iter {
+            self.push(*u16c.borrow())?;
+        }
+        Ok(())
+    }
+    /// Collect characters, or return an error if they don't fit.
+    pub fn try_from_iter<U:Borrow<Utf16Char>, I:IntoIterator<Item=U>>
+    (iter: I) -> Result<Self,CapacityError> {
+        let mut s = Self::new();
+        s.try_extend(iter)?;
+        Ok(s)
+    }
+    /// Iterate over the characters.
+    fn chars(&self) -> impl Iterator<Item=char> + '_ {
+        let mut units = self.as_slice();
+        from_fn(move || {
+            let (u16c, len) = Utf16Char::from_slice_start(units).ok()?;
+            units = &units[len..];
+            Some(u16c.to_char())
+        })
+    }
+}
+impl<const N: usize> Default for ArrayUtf16String<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<const N: usize> str::FromStr for ArrayUtf16String<N> {
+    type Err = CapacityError;
+    fn from_str(s: &str) -> Result<Self,CapacityError> {
+        let mut array = Self::new();
+        array.push_str(s)?;
+        Ok(array)
+    }
+}
+impl<const N: usize> Deref for ArrayUtf16String<N> {
+    type Target = [u16];
+    fn deref(&self) -> &[u16] {
+        self.as_slice()
+    }
+}
+impl<const N: usize> AsRef<[u16]> for ArrayUtf16String<N> {
+    fn as_ref(&self) -> &[u16] {
+        self.as_slice()
+    }
+}
+impl<const N: usize> Borrow<[u16]> for ArrayUtf16String<N> {
+    fn borrow(&self) -> &[u16] {
+        self.as_slice()
+    }
+}
+impl<const N: usize> fmt::Write for ArrayUtf16String<N> {
+    fn write_str(&mut self,  s: &str) -> fmt::Result {
+        self.push_str(s).map_err(|_| fmt::Error )
+    }
+    fn write_char(&mut self,  c: char) -> fmt::Result {
+        self.push(Utf16Char::from(c)).map_err(|_| fmt::Error )
+    }
+}
+impl<const N: usize> Extend<Utf16Char> for ArrayUtf16String<N> {
+    fn extend<I:IntoIterator<Item=Utf16Char>>(&mut self,  iter: I) {
+        self.try_extend(iter).expect("ArrayUtf16String is full")
+    }
+}
+impl<'a, const N: usize> Extend<&'a Utf16Char> for ArrayUtf16String<N> {
+    fn extend<I:IntoIterator<Item=&'a Utf16Char>>(&mut self,  iter: I) {
+        self.try_extend(iter).expect("ArrayUtf16String is full")
+    }
+}
+impl<const N: usize> FromIterator<Utf16Char> for ArrayUtf16String<N> {
+    fn from_iter<I:IntoIterator<Item=Utf16Char>>(iter: I) -> Self {
+        Self::try_from_iter(iter).expect("ArrayUtf16String is full")
+    }
+}
+impl<'a, const N: usize> FromIterator<&'a Utf16Char> for ArrayUtf16String<N> {
+    fn from_iter<I:IntoIterator<Item=&'a Utf16Char>>(iter: I) -> Self {
+        Self::try_from_iter(iter).expect("ArrayUtf16String is full")
+    }
+}
+impl<const N: usize, const M: usize> PartialEq<ArrayUtf16String<M>> for ArrayUtf16String<N> {
+    fn eq(&self,  rhs: &ArrayUtf16String<M>) -> bool {
+        self.as_slice() == rhs.as_slice()
+    }
+}
+impl<const N: usize> PartialEq<[u16]> for ArrayUtf16String<N> {
+    fn eq(&self,  rhs: &[u16]) -> bool {
+        self.as_slice() == rhs
+    }
+}
+impl<const N: usize> Eq for ArrayUtf16String<N> {}
+impl<const N: usize> hash::Hash for ArrayUtf16String<N> {
+    fn hash<H : hash::Hasher>(&self,  state: &mut H) {
+        self.as_slice().hash(state);
+    }
+}
+impl<const N: usize> fmt::Debug for ArrayUtf16String<N> {
+    fn fmt(&self,  fmtr: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Write::write_char(fmtr, '"')?;
+        for c in self.chars() {
+            write!(fmtr, "{}", c.escape_debug())?;
+        }
+        fmt::Write::write_char(fmtr, '"')
+    }
+}
+impl<const N: usize> fmt::Display for ArrayUtf16String<N> {
+    fn fmt(&self,  fmtr: &mut fmt::Formatter) -> fmt::Result {
+        for c in self.chars() {
+            fmt::Write::write_char(fmtr, c)?;
+        }
+        Ok(())
+    }
+}
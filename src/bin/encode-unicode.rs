@@ -0,0 +1,417 @@
+/* Copyright 2018 Torbjørn Birch Moltu
+ *
+ * Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+ * http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+ * http://opensource.org/licenses/MIT>, at your option. This file may not be
+ * copied, modified, or distributed except according to those terms.
+ */
+
+//! Command-line tools for looking at, validating and converting encoded text.
+//!
+//! `encode-unicode inspect [FILE]` prints every codepoint of a file or stdin
+//! with its UTF-8 and UTF-16 representations.
+//! `encode-unicode validate` reports invalid sequences like a compiler
+//! reports errors, and `encode-unicode transcode` converts between UTF-8,
+//! UTF-16 and UTF-32.
+
+extern crate encode_unicode;
+use encode_unicode::{Utf8Char, Utf16Char, U8UtfExt, CharExt};
+use encode_unicode::notation::Notation;
+use encode_unicode::error::InvalidUtf8Slice;
+use encode_unicode::error::InvalidUtf8::{FirstByte, NotAContinuationByte, OverLong};
+use std::io::{self, Read, Write};
+use std::{env, fs, process};
+
+
+const USAGE: &str = "\
+Usage: encode-unicode inspect [FILE]
+       encode-unicode validate [--encoding ENCODING] [FILE...]
+       encode-unicode transcode [--from ENCODING] [--to ENCODING] [--lossy] [FILE]
+
+Commands:
+    inspect   Print the offset, codepoint, UTF-8 bytes and UTF-16 units
+              of every character, and the reason for invalid sequences.
+    validate  Print the line, column and reason of every invalid sequence,
+              and exit with status 1 if there are any.
+    transcode Convert to another encoding and write the result to stdout.
+              Invalid sequences are reported and nothing is written,
+              unless --lossy replaces them with U+FFFD.
+
+Encodings are utf-8, utf-16le, utf-16be, utf-32le and utf-32be,
+and utf-8 is the default. Byte order marks are neither added nor removed.
+Reads from stdin if no file is given, or if it is -.";
+
+
+/// The encodings `validate` and `transcode` understand.
+#[derive(Clone,Copy, Debug, PartialEq,Eq)]
+enum Encoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    Utf32Le,
+    Utf32Be,
+}
+impl Encoding {
+    fn parse(name: &str) -> Option<Self> {
+        match &name.to_ascii_lowercase().replace('-', "")[..] {
+            "utf8" => Some(Encoding::Utf8),
+            "utf16le" => Some(Encoding::Utf16Le),
+            "utf16be" => Some(Encoding::Utf16Be),
+            "utf32le" => Some(Encoding::Utf32Le),
+            "utf32be" => Some(Encoding::Utf32Be),
+            _ => None,
+        }
+    }
+}
+
+/// The command-line arguments after the command.
+#[derive(Debug)]
+struct Options {
+    from: Encoding,
+    to: Encoding,
+    lossy: bool,
+    paths: Vec<String>,
+}
+impl Options {
+    fn parse(command: &str,  args: &[String]) -> Result<Self,String> {
+        let mut options = Options {
+            from: Encoding::Utf8,
+            to: Encoding::Utf8,
+            lossy: false,
+            paths: Vec::new(),
+        };
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let encoding = match (command, arg.as_str()) {
+                ("validate", "--encoding") | ("transcode", "--from") => &mut options.from,
+                ("transcode", "--to") => &mut options.to,
+                ("transcode", "--lossy") => {
+                    options.lossy = true;
+                    continue;
+                },
+                (_, "-") => {
+                    options.paths.push(arg.clone());
+                    continue;
+                },
+                (_, option) if option.starts_with('-') => {
+                    return Err(format!("unknown option {}", option));
+                },
+                _ => {
+                    options.paths.push(arg.clone());
+                    continue;
+                },
+            };
+            match args.next() {
+                Some(name) => match Encoding::parse(name) {
+                    Some(parsed) => *encoding = parsed,
+                    None => return Err(format!("unknown encoding {}", name)),
+                },
+                None => return Err(format!("{} requires an encoding", arg)),
+            }
+        }
+        if options.paths.len() > 1  &&  command != "validate" {
+            return Err(format!("{} takes at most one file", command));
+        }
+        Ok(options)
+    }
+}
+
+
+/// Read the whole file, or stdin if there is no path or it is `-`.
+fn read_input(path: Option<&str>) -> io::Result<Vec<u8>> {
+    let mut input = Vec::new();
+    match path {
+        None | Some("-") => io::stdin().read_to_end(&mut input)?,
+        Some(path) => fs::File::open(path)?.read_to_end(&mut input)?,
+    };
+    Ok(input)
+}
+
+/// The name to use for a file in diagnostics.
+fn display_name(path: Option<&str>) -> &str {
+    match path {
+        None | Some("-") => "<stdin>",
+        Some(path) => path,
+    }
+}
+
+/// How many bytes an invalid sequence at the start of `bytes` covers.
+fn invalid_len(bytes: &[u8],  error: InvalidUtf8Slice) -> usize {
+    match error {
+        InvalidUtf8Slice::Utf8(FirstByte(_)) => 1,
+        InvalidUtf8Slice::Utf8(NotAContinuationByte(i)) => i,
+        // the lead byte and the continuation bytes that follow it
+        InvalidUtf8Slice::TooShort(_) => 1 + bytes[1..].iter().take_while(|&&b| b & 0xc0 == 0x80 ).count(),
+        InvalidUtf8Slice::Utf8(OverLong) |
+        InvalidUtf8Slice::Codepoint(_) => 1 + bytes[0].extra_utf8_bytes().unwrap_or(0),
+    }
+}
+
+/// Why an UTF-8 sequence is invalid, as a sentence for diagnostics.
+fn utf8_reason(error: InvalidUtf8Slice) -> String {
+    match error {
+        InvalidUtf8Slice::Utf8(e) => e.to_string(),
+        InvalidUtf8Slice::Codepoint(e) => format!("the encoded codepoint {}", e),
+        InvalidUtf8Slice::TooShort(_) => "the input ends in the middle of a sequence".to_string(),
+    }
+}
+
+fn hex_bytes(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b) ).collect::<Vec<_>>().join(" ")
+}
+
+fn inspect(input: &[u8],  out: &mut dyn Write) -> io::Result<()> {
+    writeln!(out, "{:>8}  {:<9}  {:<11}  {:<9}  {:<5}  char", "offset", "codepoint", "UTF-8", "UTF-16", "ASCII")?;
+    let mut offset = 0;
+    while offset < input.len() {
+        match Utf8Char::from_slice_start(&input[offset..]) {
+            Ok((u8c, len)) => {
+                let (utf8, utf8_len) = u8c.to_array();
+                let utf16 = match Utf16Char::from(u8c).to_tuple() {
+                    (first, Some(second)) => format!("{:04x} {:04x}", first, second),
+                    (first, None) => format!("{:04x}", first),
+                };
+                writeln!(out, "{:>8}  {:<9}  {:<11}  {:<9}  {:<5}  '{}'",
+                    offset,
+                    Notation(u8c.to_char()).to_string(),
+                    hex_bytes(&utf8[..utf8_len]),
+                    utf16,
+                    if u8c.is_ascii() {"yes"} else {"no"},
+                    u8c.to_char().escape_debug()
+                )?;
+                offset += len;
+            },
+            Err(e) => {
+                let len = invalid_len(&input[offset..], e);
+                writeln!(out, "{:>8}  {:<9}  {:<11}  {:<9}  {:<5}  {:?}",
+                    offset, "invalid", hex_bytes(&input[offset..offset+len]), "", "", e
+                )?;
+                offset += len;
+            },
+        }
+    }
+    Ok(())
+}
+
+
+/// A codepoint or an invalid sequence, and where in the input it is.
+#[derive(Clone, Debug)]
+struct Decoded {
+    offset: usize,
+    result: Result<char,String>,
+}
+
+fn decode(input: &[u8],  encoding: Encoding) -> Vec<Decoded> {
+    let mut decoded = Vec::new();
+    let unit_size = match encoding {
+        Encoding::Utf8 => 1,
+        Encoding::Utf16Le | Encoding::Utf16Be => 2,
+        Encoding::Utf32Le | Encoding::Utf32Be => 4,
+    };
+    match encoding {
+        Encoding::Utf8 => {
+            let mut offset = 0;
+            while offset < input.len() {
+                let (len, result) = match Utf8Char::from_slice_start(&input[offset..]) {
+                    Ok((u8c, len)) => (len, Ok(u8c.to_char())),
+                    Err(e) => (invalid_len(&input[offset..], e), Err(utf8_reason(e))),
+                };
+                decoded.push(Decoded{ offset,  result });
+                offset += len;
+            }
+        },
+        Encoding::Utf16Le | Encoding::Utf16Be => {
+            let units: Vec<u16> = input.chunks_exact(2).map(|pair| match encoding {
+                Encoding::Utf16Le => u16::from_le_bytes([pair[0], pair[1]]),
+                _ => u16::from_be_bytes([pair[0], pair[1]]),
+            }).collect();
+            let mut i = 0;
+            while i < units.len() {
+                let (len, result) = match Utf16Char::from_slice_start(&units[i..]) {
+                    Ok((u16c, len)) => (len, Ok(u16c.to_char())),
+                    Err(e) => (1, Err(e.to_string())),
+                };
+                decoded.push(Decoded{ offset: 2*i,  result });
+                i += len;
+            }
+        },
+        Encoding::Utf32Le | Encoding::Utf32Be => {
+            for (i, quad) in input.chunks_exact(4).enumerate() {
+                let quad = [quad[0], quad[1], quad[2], quad[3]];
+                let unit = match encoding {
+                    Encoding::Utf32Le => u32::from_le_bytes(quad),
+                    _ => u32::from_be_bytes(quad),
+                };
+                let result = char::from_u32_detailed(unit)
+                    .map_err(|e| format!("the codepoint {}", e) );
+                decoded.push(Decoded{ offset: 4*i,  result });
+            }
+        },
+    }
+    let whole = input.len() - input.len() % unit_size;
+    if whole < input.len() {
+        decoded.push(Decoded {
+            offset: whole,
+            result: Err("the input ends in the middle of a unit".to_string()),
+        });
+    }
+    decoded
+}
+
+/// Print every invalid sequence with its line and column, followed by the
+/// line and a caret under the sequence, and return how many there were.
+///
+/// Columns count codepoints, and an invalid sequence counts as one.
+fn report(name: &str,  decoded: &[Decoded],  out: &mut dyn Write) -> io::Result<usize> {
+    let mut errors = 0;
+    let mut line = 1;
+    let mut line_start = 0;
+    for (i, d) in decoded.iter().enumerate() {
+        let reason = match d.result {
+            Ok('\n') => {
+                line += 1;
+                line_start = i + 1;
+                continue;
+            },
+            Ok(_) => continue,
+            Err(ref reason) => reason,
+        };
+        errors += 1;
+        let mut text = String::new();
+        let mut caret = String::new();
+        for (column, d) in decoded[line_start..].iter().enumerate() {
+            match d.result {
+                Ok('\n') => break,
+                Ok(c) => text.push(c),
+                Err(_) => text.push('\u{fffd}'),
+            }
+            if column < i - line_start {
+                caret.push(if d.result == Ok('\t') {'\t'} else {' '});
+            }
+        }
+        if text.ends_with('\r') {
+            text.pop();
+        }
+        let margin = " ".repeat(line.to_string().len());
+        writeln!(out, "{}:{}:{}: error: {} (at byte {})",
+            name, line, i - line_start + 1, reason, d.offset
+        )?;
+        writeln!(out, "{} |", margin)?;
+        writeln!(out, "{} | {}", line, text)?;
+        writeln!(out, "{} | {}^", margin, caret)?;
+    }
+    Ok(errors)
+}
+
+fn encode(decoded: &[Decoded],  encoding: Encoding) -> Vec<u8> {
+    let mut encoded = Vec::with_capacity(decoded.len());
+    for d in decoded {
+        let c = d.result.clone().unwrap_or('\u{fffd}');
+        match encoding {
+            Encoding::Utf8 => encoded.extend_from_slice(Utf8Char::from(c).as_bytes()),
+            Encoding::Utf16Le | Encoding::Utf16Be => {
+                for unit in Utf16Char::from(c) {
+                    encoded.extend_from_slice(&match encoding {
+                        Encoding::Utf16Le => unit.to_le_bytes(),
+                        _ => unit.to_be_bytes(),
+                    });
+                }
+            },
+            Encoding::Utf32Le => encoded.extend_from_slice(&(c as u32).to_le_bytes()),
+            Encoding::Utf32Be => encoded.extend_from_slice(&(c as u32).to_be_bytes()),
+        }
+    }
+    encoded
+}
+
+/// Validate every file, and return whether all of them could be read and
+/// were valid.
+fn validate(options: &Options) -> bool {
+    let stderr = io::stderr();
+    let mut out = stderr.lock();
+    let mut paths: Vec<Option<&str>> = options.paths.iter().map(|path| Some(path.as_str()) ).collect();
+    if paths.is_empty() {
+        paths.push(None);
+    }
+    let mut valid = true;
+    for path in paths {
+        let name = display_name(path);
+        let result = read_input(path).and_then(|input| {
+            report(name, &decode(&input, options.from), &mut out)
+        });
+        match result {
+            Ok(0) => {},
+            Ok(_) => valid = false,
+            Err(e) => {
+                let _ = writeln!(out, "encode-unicode: {}: {}", name, e);
+                valid = false;
+            },
+        }
+    }
+    valid
+}
+
+/// Convert a file, and return whether it could be read and was valid or
+/// `--lossy` was given.
+fn transcode(options: &Options) -> bool {
+    let path = options.paths.first().map(|path| path.as_str() );
+    let name = display_name(path);
+    let stderr = io::stderr();
+    let mut err = stderr.lock();
+    let result = read_input(path).and_then(|input| {
+        let decoded = decode(&input, options.from);
+        if !options.lossy  &&  report(name, &decoded, &mut err)? != 0 {
+            return Ok(false);
+        }
+        let stdout = io::stdout();
+        let mut out = stdout.lock();
+        out.write_all(&encode(&decoded, options.to))?;
+        out.flush()?;
+        Ok(true)
+    });
+    match result {
+        Ok(valid) => valid,
+        Err(e) => {
+            let _ = writeln!(err, "encode-unicode: {}: {}", name, e);
+            false
+        },
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let command = args.first().map(|command| command.as_str() ).unwrap_or("");
+    if command != "inspect"  &&  command != "validate"  &&  command != "transcode" {
+        eprintln!("{}", USAGE);
+        process::exit(2);
+    }
+    let options = match Options::parse(command, &args[1..]) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("encode-unicode: {}\n\n{}", message, USAGE);
+            process::exit(2);
+        },
+    };
+    let success = match command {
+        "validate" => validate(&options),
+        "transcode" => transcode(&options),
+        _ => {
+            let path = options.paths.first().map(|path| path.as_str() );
+            let result = read_input(path).and_then(|input| {
+                let stdout = io::stdout();
+                let mut out = stdout.lock();
+                inspect(&input, &mut out)
+            });
+            match result {
+                Ok(()) => true,
+                Err(e) => {
+                    eprintln!("encode-unicode: {}", e);
+                    false
+                },
+            }
+        },
+    };
+    if !success {
+        process::exit(1);
+    }
+}
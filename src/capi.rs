@@ -0,0 +1,318 @@
+/* Copyright 2018 Torbjørn Birch Moltu
+ *
+ * Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+ * http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+ * http://opensource.org/licenses/MIT>, at your option. This file may not be
+ * copied, modified, or distributed except according to those terms.
+ */
+
+//! Functions for calling from C, enabled by the `capi` feature.
+//!
+//! They are declared in `include/encode_unicode.h`, which is generated with
+//! `cbindgen --config cbindgen.toml --output include/encode_unicode.h src/capi.rs`
+//! and must be regenerated when this module changes.
+//! The crate in `capi/` builds them as a shared and a static library:
+//! `cargo build --release --manifest-path capi/Cargo.toml`.
+//!
+//! Every function returns an `eu_status`, which is zero on success and
+//! otherwise mirrors the variants of `InvalidUtf8Slice` and
+//! `InvalidUtf16Slice`.
+//! Pointers to outputs must not be null, and a pointer to input can only be
+//! null if its length is zero.
+
+use utf8_char::Utf8Char;
+use utf16_char::Utf16Char;
+use errors::{InvalidUtf8Slice, InvalidUtf16Slice, InvalidCodepoint};
+use errors::InvalidUtf8::*;
+use errors::InvalidUtf8FirstByte::*;
+extern crate core;
+use self::core::slice;
+
+
+/// The result of every function, `eu_status` in C,
+/// where the variants are named like `EU_STATUS_TOO_SHORT`.
+#[repr(C)]
+#[derive(Clone,Copy, Debug, PartialEq,Eq, Hash)]
+pub enum Status {
+    /// The function succeeded.
+    Ok = 0,
+    /// The first byte is greater than 247.
+    TooLongSequence = 1,
+    /// The first byte is a continuation byte.
+    ContinuationByte = 2,
+    /// A byte after the first is not a continuation byte.
+    NotAContinuationByte = 3,
+    /// The sequence could have been shorter.
+    OverLong = 4,
+    /// The codepoint is reserved for UTF-16 surrogates.
+    Utf16Reserved = 5,
+    /// The codepoint is higher than U+10FFFF.
+    TooHigh = 6,
+    /// The input ends in the middle of a sequence, or is empty.
+    TooShort = 7,
+    /// The first unit is a trailing surrogate.
+    FirstLowSurrogate = 8,
+    /// The input ends after a leading surrogate.
+    MissingSecond = 9,
+    /// A leading surrogate is followed by
+    /// something else than a trailing surrogate.
+    SecondNotLowSurrogate = 10,
+    /// The output doesn't have room for the next codepoint.
+    BufferTooSmall = 11,
+    /// A required pointer is null.
+    NullPointer = 12,
+}
+
+/// The status for an error, and the detail `eu_utf8_decode()` reports.
+fn utf8_status(error: InvalidUtf8Slice) -> (Status,usize) {
+    match error {
+        InvalidUtf8Slice::Utf8(FirstByte(TooLongSeqence)) => (Status::TooLongSequence, 0),
+        InvalidUtf8Slice::Utf8(FirstByte(ContinuationByte)) => (Status::ContinuationByte, 0),
+        InvalidUtf8Slice::Utf8(NotAContinuationByte(i)) => (Status::NotAContinuationByte, i),
+        InvalidUtf8Slice::Utf8(OverLong) => (Status::OverLong, 0),
+        InvalidUtf8Slice::Codepoint(InvalidCodepoint::Utf16Reserved) => (Status::Utf16Reserved, 0),
+        InvalidUtf8Slice::Codepoint(InvalidCodepoint::TooHigh) => (Status::TooHigh, 0),
+        InvalidUtf8Slice::TooShort(needed) => (Status::TooShort, needed),
+    }
+}
+
+fn utf16_status(error: InvalidUtf16Slice) -> Status {
+    match error {
+        InvalidUtf16Slice::EmptySlice => Status::TooShort,
+        InvalidUtf16Slice::FirstLowSurrogate => Status::FirstLowSurrogate,
+        InvalidUtf16Slice::MissingSecond => Status::MissingSecond,
+        InvalidUtf16Slice::SecondNotLowSurrogate => Status::SecondNotLowSurrogate,
+    }
+}
+
+/// Create a slice from C, where the pointer is allowed to be null if the
+/// length is zero.
+unsafe fn input<'a,T>(src: *const T,  len: usize) -> Option<&'a [T]> {
+    if len == 0 {
+        Some(&[])
+    } else if src.is_null() {
+        None
+    } else {
+        Some(slice::from_raw_parts(src, len))
+    }
+}
+
+/// Create a mutable slice from C, like `input()`.
+unsafe fn output<'a,T>(dst: *mut T,  len: usize) -> Option<&'a mut [T]> {
+    if len == 0 {
+        Some(&mut [])
+    } else if dst.is_null() {
+        None
+    } else {
+        Some(slice::from_raw_parts_mut(dst, len))
+    }
+}
+
+
+/// Decode the first codepoint of UTF-8 bytes, `eu_utf8_decode()` in C.
+///
+/// On success `*codepoint` is set and `*consumed` is the length of the
+/// sequence.
+/// For `EU_STATUS_NOT_A_CONTINUATION_BYTE` `*consumed` is the index of the
+/// offending byte, for `EU_STATUS_TOO_SHORT` it's the length the sequence
+/// would have, and for other errors it's zero.
+///
+/// # Safety
+///
+/// `src` must be valid for reads of `len` bytes, and the output pointers must
+/// be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn eu_utf8_decode(src: *const u8,  len: usize,
+                                        codepoint: *mut u32,  consumed: *mut usize
+                                       ) -> Status {
+    let src = match input(src, len) {
+        Some(src) if !codepoint.is_null()  &&  !consumed.is_null() => src,
+        _ => return Status::NullPointer,
+    };
+    match Utf8Char::from_slice_start(src) {
+        Ok((u8c, len)) => {
+            *codepoint = u8c.to_char() as u32;
+            *consumed = len;
+            Status::Ok
+        },
+        Err(e) => {
+            let (status, detail) = utf8_status(e);
+            *consumed = detail;
+            status
+        },
+    }
+}
+
+/// Decode the first codepoint of UTF-16 units, `eu_utf16_decode()` in C.
+///
+/// On success `*codepoint` is set and `*consumed` is the number of units.
+///
+/// # Safety
+///
+/// `src` must be valid for reads of `len` units, and the output pointers must
+/// be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn eu_utf16_decode(src: *const u16,  len: usize,
+                                         codepoint: *mut u32,  consumed: *mut usize
+                                        ) -> Status {
+    let src = match input(src, len) {
+        Some(src) if !codepoint.is_null()  &&  !consumed.is_null() => src,
+        _ => return Status::NullPointer,
+    };
+    match Utf16Char::from_slice_start(src) {
+        Ok((u16c, len)) => {
+            *codepoint = u16c.to_char() as u32;
+            *consumed = len;
+            Status::Ok
+        },
+        Err(e) => utf16_status(e),
+    }
+}
+
+/// Check that bytes are valid UTF-8, `eu_utf8_validate()` in C.
+///
+/// `*valid_up_to` is set to the length of the valid prefix,
+/// which is the index of the invalid sequence if there is one.
+///
+/// # Safety
+///
+/// `src` must be valid for reads of `len` bytes, and `valid_up_to` must be
+/// valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn eu_utf8_validate(src: *const u8,  len: usize,  valid_up_to: *mut usize) -> Status {
+    let src = match input(src, len) {
+        Some(src) if !valid_up_to.is_null() => src,
+        _ => return Status::NullPointer,
+    };
+    let mut read = 0;
+    while read < src.len() {
+        match Utf8Char::from_slice_start(&src[read..]) {
+            Ok((_, len)) => read += len,
+            Err(e) => {
+                *valid_up_to = read;
+                return utf8_status(e).0;
+            },
+        }
+    }
+    *valid_up_to = read;
+    Status::Ok
+}
+
+/// Check that units are valid UTF-16, `eu_utf16_validate()` in C.
+///
+/// `*valid_up_to` is set to the length of the valid prefix,
+/// which is the index of the invalid unit if there is one.
+///
+/// # Safety
+///
+/// `src` must be valid for reads of `len` units, and `valid_up_to` must be
+/// valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn eu_utf16_validate(src: *const u16,  len: usize,  valid_up_to: *mut usize) -> Status {
+    let src = match input(src, len) {
+        Some(src) if !valid_up_to.is_null() => src,
+        _ => return Status::NullPointer,
+    };
+    let mut read = 0;
+    while read < src.len() {
+        match Utf16Char::from_slice_start(&src[read..]) {
+            Ok((_, len)) => read += len,
+            Err(e) => {
+                *valid_up_to = read;
+                return utf16_status(e);
+            },
+        }
+    }
+    *valid_up_to = read;
+    Status::Ok
+}
+
+/// Convert UTF-8 to UTF-16, `eu_utf8_to_utf16()` in C.
+///
+/// Stops at the first invalid sequence, or when the next codepoint doesn't
+/// fit in `dst`.
+/// `*read` and `*written` are always set to how many bytes and units were
+/// converted, so that a conversion can be continued with a new buffer.
+///
+/// # Safety
+///
+/// `src` must be valid for reads of `src_len` bytes, `dst` for writes of
+/// `dst_len` units, and the other pointers must be valid for writes.
+/// `src` and `dst` must not overlap.
+#[no_mangle]
+pub unsafe extern "C" fn eu_utf8_to_utf16(src: *const u8,  src_len: usize,
+                                          dst: *mut u16,  dst_len: usize,
+                                          read: *mut usize,  written: *mut usize
+                                         ) -> Status {
+    let (src, dst) = match (input(src, src_len), output(dst, dst_len)) {
+        (Some(src), Some(dst)) if !read.is_null()  &&  !written.is_null() => (src, dst),
+        _ => return Status::NullPointer,
+    };
+    let (mut r, mut w) = (0, 0);
+    let mut status = Status::Ok;
+    while r < src.len() {
+        match Utf8Char::from_slice_start(&src[r..]) {
+            Ok((u8c, len)) => {
+                let u16c = Utf16Char::from(u8c);
+                if w + u16c.len() > dst.len() {
+                    status = Status::BufferTooSmall;
+                    break;
+                }
+                w += u16c.to_slice(&mut dst[w..]);
+                r += len;
+            },
+            Err(e) => {
+                status = utf8_status(e).0;
+                break;
+            },
+        }
+    }
+    *read = r;
+    *written = w;
+    status
+}
+
+/// Convert UTF-16 to UTF-8, `eu_utf16_to_utf8()` in C.
+///
+/// Stops at the first invalid unit, or when the next codepoint doesn't fit
+/// in `dst`.
+/// `*read` and `*written` are always set to how many units and bytes were
+/// converted, so that a conversion can be continued with a new buffer.
+///
+/// # Safety
+///
+/// `src` must be valid for reads of `src_len` units, `dst` for writes of
+/// `dst_len` bytes, and the other pointers must be valid for writes.
+/// `src` and `dst` must not overlap.
+#[no_mangle]
+pub unsafe extern "C" fn eu_utf16_to_utf8(src: *const u16,  src_len: usize,
+                                          dst: *mut u8,  dst_len: usize,
+                                          read: *mut usize,  written: *mut usize
+                                         ) -> Status {
+    let (src, dst) = match (input(src, src_len), output(dst, dst_len)) {
+        (Some(src), Some(dst)) if !read.is_null()  &&  !written.is_null() => (src, dst),
+        _ => return Status::NullPointer,
+    };
+    let (mut r, mut w) = (0, 0);
+    let mut status = Status::Ok;
+    while r < src.len() {
+        match Utf16Char::from_slice_start(&src[r..]) {
+            Ok((u16c, len)) => {
+                let u8c = Utf8Char::from(u16c);
+                if w + u8c.len() > dst.len() {
+                    status = Status::BufferTooSmall;
+                    break;
+                }
+                w += u8c.to_slice(&mut dst[w..]);
+                r += len;
+            },
+            Err(e) => {
+                status = utf16_status(e);
+                break;
+            },
+        }
+    }
+    *read = r;
+    *written = w;
+    status
+}
@@ -0,0 +1,585 @@
+/* Copyright 2018 Torbjørn Birch Moltu
+ *
+ * Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+ * http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+ * http://opensource.org/licenses/MIT>, at your option. This file may not be
+ * copied, modified, or distributed except according to those terms.
+ */
+
+use utf8_char::Utf8Char;
+use utf16_char::Utf16Char;
+use errors::Unmappable;
+extern crate core;
+use self::core::char;
+#[cfg(feature="std")]
+use std::io::{Read, Error as ioError, ErrorKind};
+
+
+/// A single-byte legacy character encoding.
+///
+/// All of them are supersets of ASCII, and only differ in which
+/// characters the bytes `0x80` to `0xff` represent.
+/// For the ISO-8859 encodings `0x80` to `0x9f` are the C1 control characters.
+///
+/// # Examples
+///
+/// ```
+/// use encode_unicode::{CodePage, Utf8Char};
+/// use encode_unicode::error::Unmappable;
+///
+/// assert_eq!(CodePage::Windows1252.decode(0x80), Ok(Utf8Char::from('€')));
+/// assert_eq!(CodePage::Latin1.decode(0x80), Ok(Utf8Char::from('\u{80}')));
+/// assert_eq!(CodePage::Windows1252.decode(0x81), Err(Unmappable::Byte(0x81)));
+///
+/// assert_eq!(CodePage::Iso8859_15.encode('€'), Ok(0xa4));
+/// assert_eq!(CodePage::Latin1.encode('€'), Err(Unmappable::Char('€')));
+/// ```
+#[derive(Clone,Copy, Debug, PartialEq,Eq, Hash)]
+pub enum CodePage {
+    /// ISO-8859-1, which maps every byte to the codepoint with the same value.
+    Latin1,
+    /// Windows-1252, the Western European code page of Windows.
+    ///
+    /// Replaces most of the C1 control characters in Latin-1 with punctuation
+    /// and letters, but leaves `0x81`, `0x8d`, `0x8f`, `0x90` and `0x9d`
+    /// undefined.
+    Windows1252,
+    /// ISO-8859-2 (Latin-2), for Central European languages.
+    Iso8859_2,
+    /// ISO-8859-3 (Latin-3), for Maltese and Esperanto.
+    Iso8859_3,
+    /// ISO-8859-4 (Latin-4), for the Baltic languages.
+    Iso8859_4,
+    /// ISO-8859-5, for Cyrillic.
+    Iso8859_5,
+    /// ISO-8859-6, for Arabic.
+    Iso8859_6,
+    /// ISO-8859-7, for Greek.
+    Iso8859_7,
+    /// ISO-8859-8, for Hebrew.
+    Iso8859_8,
+    /// ISO-8859-9 (Latin-5), for Turkish.
+    Iso8859_9,
+    /// ISO-8859-10 (Latin-6), for the Nordic languages.
+    Iso8859_10,
+    /// ISO-8859-11, for Thai.
+    Iso8859_11,
+    /// ISO-8859-13 (Latin-7), for the Baltic languages.
+    Iso8859_13,
+    /// ISO-8859-14 (Latin-8), for the Celtic languages.
+    Iso8859_14,
+    /// ISO-8859-15 (Latin-9), Latin-1 with the euro sign.
+    Iso8859_15,
+    /// ISO-8859-16 (Latin-10), for South-Eastern European languages.
+    Iso8859_16,
+}
+impl CodePage {
+    /// The codepoints of bytes `0x80` to `0xff`, where zero means undefined.
+    fn table(self) -> Option<&'static [u16; 128]> {
+        match self {
+            CodePage::Latin1 => None,
+            CodePage::Windows1252 => Some(&WINDOWS_1252),
+            CodePage::Iso8859_2 => Some(&ISO_8859_2),
+            CodePage::Iso8859_3 => Some(&ISO_8859_3),
+            CodePage::Iso8859_4 => Some(&ISO_8859_4),
+            CodePage::Iso8859_5 => Some(&ISO_8859_5),
+            CodePage::Iso8859_6 => Some(&ISO_8859_6),
+            CodePage::Iso8859_7 => Some(&ISO_8859_7),
+            CodePage::Iso8859_8 => Some(&ISO_8859_8),
+            CodePage::Iso8859_9 => Some(&ISO_8859_9),
+            CodePage::Iso8859_10 => Some(&ISO_8859_10),
+            CodePage::Iso8859_11 => Some(&ISO_8859_11),
+            CodePage::Iso8859_13 => Some(&ISO_8859_13),
+            CodePage::Iso8859_14 => Some(&ISO_8859_14),
+            CodePage::Iso8859_15 => Some(&ISO_8859_15),
+            CodePage::Iso8859_16 => Some(&ISO_8859_16),
+        }
+    }
+    fn decode_char(self,  byte: u8) -> Result<char,Unmappable> {
+        let table = match self.table() {
+            Some(table) if byte >= 0x80 => table,
+            _ => return Ok(byte as char),
+        };
+        match table[(byte-0x80) as usize] {
+            0 => Err(Unmappable::Byte(byte)),
+            // the tables don't contain surrogates
+            codepoint => Ok(unsafe{ char::from_u32_unchecked(codepoint as u32) }),
+        }
+    }
+    /// Get the character a byte represents in this code page.
+    ///
+    /// Returns `Unmappable::Byte` if the byte isn't assigned a character.
+    pub fn decode(self,  byte: u8) -> Result<Utf8Char,Unmappable> {
+        self.decode_char(byte).map(Utf8Char::from)
+    }
+    /// Get the character a byte represents in this code page, as UTF-16.
+    ///
+    /// Returns `Unmappable::Byte` if the byte isn't assigned a character.
+    pub fn decode_utf16(self,  byte: u8) -> Result<Utf16Char,Unmappable> {
+        self.decode_char(byte).map(Utf16Char::from)
+    }
+    /// Get the byte that represents a `char`, `Utf8Char` or `Utf16Char` in
+    /// this code page.
+    ///
+    /// Returns `Unmappable::Char` if the code page doesn't contain it.
+    pub fn encode<C:Into<char>>(self,  c: C) -> Result<u8,Unmappable> {
+        let c = c.into();
+        let codepoint = c as u32;
+        if codepoint < 0x80 {
+            return Ok(codepoint as u8);
+        }
+        match self.table() {
+            None if codepoint < 0x100 => Ok(codepoint as u8),
+            None => Err(Unmappable::Char(c)),
+            // codepoint is never zero here, so undefined entries don't match
+            Some(table) => match table.iter().position(|&u| u as u32 == codepoint ) {
+                Some(i) => Ok(0x80 + i as u8),
+                None => Err(Unmappable::Char(c)),
+            },
+        }
+    }
+
+    /// Decode bytes in this code page to an iterator of `Utf8Char`s.
+    ///
+    /// The iterator also implements `Read` (if the `std` feature isn't disabled),
+    /// which produces UTF-8.
+    /// Bytes that aren't assigned a character become an error of kind
+    /// `InvalidData`, which is returned after any bytes read before it.
+    /// If a read stops in the middle of a character, the rest of it is
+    /// returned by the next read but not by `.next()`.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(feature="std", doc=" ```")]
+    #[cfg_attr(not(feature="std"), doc=" ```no_compile")]
+    /// use encode_unicode::CodePage;
+    /// use std::io::Read;
+    ///
+    /// let csv = b"caf\xe9;\x80 5\r\n";
+    /// let mut decoded = String::new();
+    /// CodePage::Windows1252.decode_bytes(csv.iter().cloned())
+    ///                      .read_to_string(&mut decoded)
+    ///                      .unwrap();
+    /// assert_eq!(decoded, "café;€ 5\r\n");
+    ///
+    /// let mut decoder = CodePage::Windows1252.decode_bytes(vec![b'a', 0x81]);
+    /// let mut buf = [0; 4];
+    /// assert_eq!(decoder.read(&mut buf).unwrap(), 1);
+    /// assert!(decoder.read(&mut buf).is_err());
+    /// ```
+    pub fn decode_bytes<I:IntoIterator<Item=u8>>(self,  bytes: I) -> CodePageDecoder<I::IntoIter> {
+        CodePageDecoder{ inner: bytes.into_iter(),  code_page: self,  prev: 0,  error: None }
+    }
+    /// Encode `char`s, `Utf8Char`s or `Utf16Char`s in this code page.
+    ///
+    /// The iterator also implements `Read` (if the `std` feature isn't disabled).
+    /// Characters that aren't in the code page become an error of kind
+    /// `InvalidData`, which is returned after any bytes read before it.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(feature="std", doc=" ```")]
+    #[cfg_attr(not(feature="std"), doc=" ```no_compile")]
+    /// use encode_unicode::CodePage;
+    /// use encode_unicode::error::Unmappable;
+    ///
+    /// let encoded: Result<Vec<u8>,_> = CodePage::Iso8859_5.encode_chars("Привет".chars()).collect();
+    /// assert_eq!(encoded.unwrap(), b"\xbf\xe0\xd8\xd2\xd5\xe2");
+    ///
+    /// let encoded: Result<Vec<u8>,_> = CodePage::Latin1.encode_chars("Привет".chars()).collect();
+    /// assert_eq!(encoded, Err(Unmappable::Char('П')));
+    /// ```
+    pub fn encode_chars<C:Into<char>, I:IntoIterator<Item=C>>
+    (self,  chars: I) -> CodePageEncoder<C, I::IntoIter> {
+        CodePageEncoder{ inner: chars.into_iter(),  code_page: self,  error: None }
+    }
+}
+
+
+/// The iterator type returned by `CodePage::decode_bytes()`
+///
+/// See its documentation for details.
+#[derive(Clone)]
+pub struct CodePageDecoder<I:Iterator<Item=u8>> {
+    inner: I,
+    code_page: CodePage,
+    /// Bytes of a character that didn't fit in the buffer passed to `read()`.
+    #[cfg_attr(not(feature="std"), allow(dead_code))]
+    prev: u32,
+    /// An error to return from the next `read()`.
+    #[cfg_attr(not(feature="std"), allow(dead_code))]
+    error: Option<Unmappable>,
+}
+impl<I:Iterator<Item=u8>> CodePageDecoder<I> {
+    /// Extracts the source iterator.
+    pub fn into_inner(self) -> I {
+        self.inner
+    }
+}
+impl<I:Iterator<Item=u8>> Iterator for CodePageDecoder<I> {
+    type Item = Result<Utf8Char,Unmappable>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let code_page = self.code_page;
+        self.inner.next().map(|byte| code_page.decode(byte) )
+    }
+    fn size_hint(&self) -> (usize,Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+#[cfg(feature="std")]
+impl<I:Iterator<Item=u8>> Read for CodePageDecoder<I> {
+    fn read(&mut self,  buf: &mut[u8]) -> Result<usize, ioError> {
+        let mut i = 0;
+        // write remaining bytes of previous codepoint
+        while self.prev != 0  &&  i < buf.len() {
+            buf[i] = self.prev as u8;
+            self.prev >>= 8;
+            i += 1;
+        }
+        if let Some(e) = self.error.take() {
+            return Err(ioError::new(ErrorKind::InvalidData, e));
+        }
+        while i < buf.len() {
+            let (bytes, len) = match self.next() {
+                Some(Ok(u8c)) => u8c.to_array(),
+                Some(Err(e)) => {
+                    if i == 0 {
+                        return Err(ioError::new(ErrorKind::InvalidData, e));
+                    }
+                    self.error = Some(e);
+                    break;
+                },
+                None => break,
+            };
+            let fits = if len < buf.len()-i {len} else {buf.len()-i};
+            buf[i..i+fits].copy_from_slice(&bytes[..fits]);
+            i += fits;
+            for &b in bytes[fits..len].iter().rev() {
+                self.prev = (self.prev << 8) | b as u32;
+            }
+        }
+        Ok(i)
+    }
+}
+
+
+/// The iterator type returned by `CodePage::encode_chars()`
+///
+/// See its documentation for details.
+#[derive(Clone)]
+pub struct CodePageEncoder<C:Into<char>, I:Iterator<Item=C>> {
+    inner: I,
+    code_page: CodePage,
+    /// An error to return from the next `read()`.
+    #[cfg_attr(not(feature="std"), allow(dead_code))]
+    error: Option<Unmappable>,
+}
+impl<C:Into<char>, I:Iterator<Item=C>> CodePageEncoder<C,I> {
+    /// Extracts the source iterator.
+    pub fn into_inner(self) -> I {
+        self.inner
+    }
+}
+impl<C:Into<char>, I:Iterator<Item=C>> Iterator for CodePageEncoder<C,I> {
+    type Item = Result<u8,Unmappable>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let code_page = self.code_page;
+        self.inner.next().map(|c| code_page.encode(c) )
+    }
+    fn size_hint(&self) -> (usize,Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+#[cfg(feature="std")]
+impl<C:Into<char>, I:Iterator<Item=C>> Read for CodePageEncoder<C,I> {
+    fn read(&mut self,  buf: &mut[u8]) -> Result<usize, ioError> {
+        if let Some(e) = self.error.take() {
+            return Err(ioError::new(ErrorKind::InvalidData, e));
+        }
+        for (i, dst) in buf.iter_mut().enumerate() {
+            match self.next() {
+                Some(Ok(b)) => *dst = b,
+                Some(Err(e)) if i == 0 => return Err(ioError::new(ErrorKind::InvalidData, e)),
+                Some(Err(e)) => {
+                    self.error = Some(e);
+                    return Ok(i);
+                },
+                None => return Ok(i),
+            }
+        }
+        Ok(buf.len())
+    }
+}
+
+
+
+static WINDOWS_1252: [u16; 128] = [
+    0x20ac, 0x0000, 0x201a, 0x0192, 0x201e, 0x2026, 0x2020, 0x2021,
+    0x02c6, 0x2030, 0x0160, 0x2039, 0x0152, 0x0000, 0x017d, 0x0000,
+    0x0000, 0x2018, 0x2019, 0x201c, 0x201d, 0x2022, 0x2013, 0x2014,
+    0x02dc, 0x2122, 0x0161, 0x203a, 0x0153, 0x0000, 0x017e, 0x0178,
+    0x00a0, 0x00a1, 0x00a2, 0x00a3, 0x00a4, 0x00a5, 0x00a6, 0x00a7,
+    0x00a8, 0x00a9, 0x00aa, 0x00ab, 0x00ac, 0x00ad, 0x00ae, 0x00af,
+    0x00b0, 0x00b1, 0x00b2, 0x00b3, 0x00b4, 0x00b5, 0x00b6, 0x00b7,
+    0x00b8, 0x00b9, 0x00ba, 0x00bb, 0x00bc, 0x00bd, 0x00be, 0x00bf,
+    0x00c0, 0x00c1, 0x00c2, 0x00c3, 0x00c4, 0x00c5, 0x00c6, 0x00c7,
+    0x00c8, 0x00c9, 0x00ca, 0x00cb, 0x00cc, 0x00cd, 0x00ce, 0x00cf,
+    0x00d0, 0x00d1, 0x00d2, 0x00d3, 0x00d4, 0x00d5, 0x00d6, 0x00d7,
+    0x00d8, 0x00d9, 0x00da, 0x00db, 0x00dc, 0x00dd, 0x00de, 0x00df,
+    0x00e0, 0x00e1, 0x00e2, 0x00e3, 0x00e4, 0x00e5, 0x00e6, 0x00e7,
+    0x00e8, 0x00e9, 0x00ea, 0x00eb, 0x00ec, 0x00ed, 0x00ee, 0x00ef,
+    0x00f0, 0x00f1, 0x00f2, 0x00f3, 0x00f4, 0x00f5, 0x00f6, 0x00f7,
+    0x00f8, 0x00f9, 0x00fa, 0x00fb, 0x00fc, 0x00fd, 0x00fe, 0x00ff,
+];
+static ISO_8859_2: [u16; 128] = [
+    0x0080, 0x0081, 0x0082, 0x0083, 0x0084, 0x0085, 0x0086, 0x0087,
+    0x0088, 0x0089, 0x008a, 0x008b, 0x008c, 0x008d, 0x008e, 0x008f,
+    0x0090, 0x0091, 0x0092, 0x0093, 0x0094, 0x0095, 0x0096, 0x0097,
+    0x0098, 0x0099, 0x009a, 0x009b, 0x009c, 0x009d, 0x009e, 0x009f,
+    0x00a0, 0x0104, 0x02d8, 0x0141, 0x00a4, 0x013d, 0x015a, 0x00a7,
+    0x00a8, 0x0160, 0x015e, 0x0164, 0x0179, 0x00ad, 0x017d, 0x017b,
+    0x00b0, 0x0105, 0x02db, 0x0142, 0x00b4, 0x013e, 0x015b, 0x02c7,
+    0x00b8, 0x0161, 0x015f, 0x0165, 0x017a, 0x02dd, 0x017e, 0x017c,
+    0x0154, 0x00c1, 0x00c2, 0x0102, 0x00c4, 0x0139, 0x0106, 0x00c7,
+    0x010c, 0x00c9, 0x0118, 0x00cb, 0x011a, 0x00cd, 0x00ce, 0x010e,
+    0x0110, 0x0143, 0x0147, 0x00d3, 0x00d4, 0x0150, 0x00d6, 0x00d7,
+    0x0158, 0x016e, 0x00da, 0x0170, 0x00dc, 0x00dd, 0x0162, 0x00df,
+    0x0155, 0x00e1, 0x00e2, 0x0103, 0x00e4, 0x013a, 0x0107, 0x00e7,
+    0x010d, 0x00e9, 0x0119, 0x00eb, 0x011b, 0x00ed, 0x00ee, 0x010f,
+    0x0111, 0x0144, 0x0148, 0x00f3, 0x00f4, 0x0151, 0x00f6, 0x00f7,
+    0x0159, 0x016f, 0x00fa, 0x0171, 0x00fc, 0x00fd, 0x0163, 0x02d9,
+];
+static ISO_8859_3: [u16; 128] = [
+    0x0080, 0x0081, 0x0082, 0x0083, 0x0084, 0x0085, 0x0086, 0x0087,
+    0x0088, 0x0089, 0x008a, 0x008b, 0x008c, 0x008d, 0x008e, 0x008f,
+    0x0090, 0x0091, 0x0092, 0x0093, 0x0094, 0x0095, 0x0096, 0x0097,
+    0x0098, 0x0099, 0x009a, 0x009b, 0x009c, 0x009d, 0x009e, 0x009f,
+    0x00a0, 0x0126, 0x02d8, 0x00a3, 0x00a4, 0x0000, 0x0124, 0x00a7,
+    0x00a8, 0x0130, 0x015e, 0x011e, 0x0134, 0x00ad, 0x0000, 0x017b,
+    0x00b0, 0x0127, 0x00b2, 0x00b3, 0x00b4, 0x00b5, 0x0125, 0x00b7,
+    0x00b8, 0x0131, 0x015f, 0x011f, 0x0135, 0x00bd, 0x0000, 0x017c,
+    0x00c0, 0x00c1, 0x00c2, 0x0000, 0x00c4, 0x010a, 0x0108, 0x00c7,
+    0x00c8, 0x00c9, 0x00ca, 0x00cb, 0x00cc, 0x00cd, 0x00ce, 0x00cf,
+    0x0000, 0x00d1, 0x00d2, 0x00d3, 0x00d4, 0x0120, 0x00d6, 0x00d7,
+    0x011c, 0x00d9, 0x00da, 0x00db, 0x00dc, 0x016c, 0x015c, 0x00df,
+    0x00e0, 0x00e1, 0x00e2, 0x0000, 0x00e4, 0x010b, 0x0109, 0x00e7,
+    0x00e8, 0x00e9, 0x00ea, 0x00eb, 0x00ec, 0x00ed, 0x00ee, 0x00ef,
+    0x0000, 0x00f1, 0x00f2, 0x00f3, 0x00f4, 0x0121, 0x00f6, 0x00f7,
+    0x011d, 0x00f9, 0x00fa, 0x00fb, 0x00fc, 0x016d, 0x015d, 0x02d9,
+];
+static ISO_8859_4: [u16; 128] = [
+    0x0080, 0x0081, 0x0082, 0x0083, 0x0084, 0x0085, 0x0086, 0x0087,
+    0x0088, 0x0089, 0x008a, 0x008b, 0x008c, 0x008d, 0x008e, 0x008f,
+    0x0090, 0x0091, 0x0092, 0x0093, 0x0094, 0x0095, 0x0096, 0x0097,
+    0x0098, 0x0099, 0x009a, 0x009b, 0x009c, 0x009d, 0x009e, 0x009f,
+    0x00a0, 0x0104, 0x0138, 0x0156, 0x00a4, 0x0128, 0x013b, 0x00a7,
+    0x00a8, 0x0160, 0x0112, 0x0122, 0x0166, 0x00ad, 0x017d, 0x00af,
+    0x00b0, 0x0105, 0x02db, 0x0157, 0x00b4, 0x0129, 0x013c, 0x02c7,
+    0x00b8, 0x0161, 0x0113, 0x0123, 0x0167, 0x014a, 0x017e, 0x014b,
+    0x0100, 0x00c1, 0x00c2, 0x00c3, 0x00c4, 0x00c5, 0x00c6, 0x012e,
+    0x010c, 0x00c9, 0x0118, 0x00cb, 0x0116, 0x00cd, 0x00ce, 0x012a,
+    0x0110, 0x0145, 0x014c, 0x0136, 0x00d4, 0x00d5, 0x00d6, 0x00d7,
+    0x00d8, 0x0172, 0x00da, 0x00db, 0x00dc, 0x0168, 0x016a, 0x00df,
+    0x0101, 0x00e1, 0x00e2, 0x00e3, 0x00e4, 0x00e5, 0x00e6, 0x012f,
+    0x010d, 0x00e9, 0x0119, 0x00eb, 0x0117, 0x00ed, 0x00ee, 0x012b,
+    0x0111, 0x0146, 0x014d, 0x0137, 0x00f4, 0x00f5, 0x00f6, 0x00f7,
+    0x00f8, 0x0173, 0x00fa, 0x00fb, 0x00fc, 0x0169, 0x016b, 0x02d9,
+];
+static ISO_8859_5: [u16; 128] = [
+    0x0080, 0x0081, 0x0082, 0x0083, 0x0084, 0x0085, 0x0086, 0x0087,
+    0x0088, 0x0089, 0x008a, 0x008b, 0x008c, 0x008d, 0x008e, 0x008f,
+    0x0090, 0x0091, 0x0092, 0x0093, 0x0094, 0x0095, 0x0096, 0x0097,
+    0x0098, 0x0099, 0x009a, 0x009b, 0x009c, 0x009d, 0x009e, 0x009f,
+    0x00a0, 0x0401, 0x0402, 0x0403, 0x0404, 0x0405, 0x0406, 0x0407,
+    0x0408, 0x0409, 0x040a, 0x040b, 0x040c, 0x00ad, 0x040e, 0x040f,
+    0x0410, 0x0411, 0x0412, 0x0413, 0x0414, 0x0415, 0x0416, 0x0417,
+    0x0418, 0x0419, 0x041a, 0x041b, 0x041c, 0x041d, 0x041e, 0x041f,
+    0x0420, 0x0421, 0x0422, 0x0423, 0x0424, 0x0425, 0x0426, 0x0427,
+    0x0428, 0x0429, 0x042a, 0x042b, 0x042c, 0x042d, 0x042e, 0x042f,
+    0x0430, 0x0431, 0x0432, 0x0433, 0x0434, 0x0435, 0x0436, 0x0437,
+    0x0438, 0x0439, 0x043a, 0x043b, 0x043c, 0x043d, 0x043e, 0x043f,
+    0x0440, 0x0441, 0x0442, 0x0443, 0x0444, 0x0445, 0x0446, 0x0447,
+    0x0448, 0x0449, 0x044a, 0x044b, 0x044c, 0x044d, 0x044e, 0x044f,
+    0x2116, 0x0451, 0x0452, 0x0453, 0x0454, 0x0455, 0x0456, 0x0457,
+    0x0458, 0x0459, 0x045a, 0x045b, 0x045c, 0x00a7, 0x045e, 0x045f,
+];
+static ISO_8859_6: [u16; 128] = [
+    0x0080, 0x0081, 0x0082, 0x0083, 0x0084, 0x0085, 0x0086, 0x0087,
+    0x0088, 0x0089, 0x008a, 0x008b, 0x008c, 0x008d, 0x008e, 0x008f,
+    0x0090, 0x0091, 0x0092, 0x0093, 0x0094, 0x0095, 0x0096, 0x0097,
+    0x0098, 0x0099, 0x009a, 0x009b, 0x009c, 0x009d, 0x009e, 0x009f,
+    0x00a0, 0x0000, 0x0000, 0x0000, 0x00a4, 0x0000, 0x0000, 0x0000,
+    0x0000, 0x0000, 0x0000, 0x0000, 0x060c, 0x00ad, 0x0000, 0x0000,
+    0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000,
+    0x0000, 0x0000, 0x0000, 0x061b, 0x0000, 0x0000, 0x0000, 0x061f,
+    0x0000, 0x0621, 0x0622, 0x0623, 0x0624, 0x0625, 0x0626, 0x0627,
+    0x0628, 0x0629, 0x062a, 0x062b, 0x062c, 0x062d, 0x062e, 0x062f,
+    0x0630, 0x0631, 0x0632, 0x0633, 0x0634, 0x0635, 0x0636, 0x0637,
+    0x0638, 0x0639, 0x063a, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000,
+    0x0640, 0x0641, 0x0642, 0x0643, 0x0644, 0x0645, 0x0646, 0x0647,
+    0x0648, 0x0649, 0x064a, 0x064b, 0x064c, 0x064d, 0x064e, 0x064f,
+    0x0650, 0x0651, 0x0652, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000,
+    0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000,
+];
+static ISO_8859_7: [u16; 128] = [
+    0x0080, 0x0081, 0x0082, 0x0083, 0x0084, 0x0085, 0x0086, 0x0087,
+    0x0088, 0x0089, 0x008a, 0x008b, 0x008c, 0x008d, 0x008e, 0x008f,
+    0x0090, 0x0091, 0x0092, 0x0093, 0x0094, 0x0095, 0x0096, 0x0097,
+    0x0098, 0x0099, 0x009a, 0x009b, 0x009c, 0x009d, 0x009e, 0x009f,
+    0x00a0, 0x2018, 0x2019, 0x00a3, 0x20ac, 0x20af, 0x00a6, 0x00a7,
+    0x00a8, 0x00a9, 0x037a, 0x00ab, 0x00ac, 0x00ad, 0x0000, 0x2015,
+    0x00b0, 0x00b1, 0x00b2, 0x00b3, 0x0384, 0x0385, 0x0386, 0x00b7,
+    0x0388, 0x0389, 0x038a, 0x00bb, 0x038c, 0x00bd, 0x038e, 0x038f,
+    0x0390, 0x0391, 0x0392, 0x0393, 0x0394, 0x0395, 0x0396, 0x0397,
+    0x0398, 0x0399, 0x039a, 0x039b, 0x039c, 0x039d, 0x039e, 0x039f,
+    0x03a0, 0x03a1, 0x0000, 0x03a3, 0x03a4, 0x03a5, 0x03a6, 0x03a7,
+    0x03a8, 0x03a9, 0x03aa, 0x03ab, 0x03ac, 0x03ad, 0x03ae, 0x03af,
+    0x03b0, 0x03b1, 0x03b2, 0x03b3, 0x03b4, 0x03b5, 0x03b6, 0x03b7,
+    0x03b8, 0x03b9, 0x03ba, 0x03bb, 0x03bc, 0x03bd, 0x03be, 0x03bf,
+    0x03c0, 0x03c1, 0x03c2, 0x03c3, 0x03c4, 0x03c5, 0x03c6, 0x03c7,
+    0x03c8, 0x03c9, 0x03ca, 0x03cb, 0x03cc, 0x03cd, 0x03ce, 0x0000,
+];
+static ISO_8859_8: [u16; 128] = [
+    0x0080, 0x0081, 0x0082, 0x0083, 0x0084, 0x0085, 0x0086, 0x0087,
+    0x0088, 0x0089, 0x008a, 0x008b, 0x008c, 0x008d, 0x008e, 0x008f,
+    0x0090, 0x0091, 0x0092, 0x0093, 0x0094, 0x0095, 0x0096, 0x0097,
+    0x0098, 0x0099, 0x009a, 0x009b, 0x009c, 0x009d, 0x009e, 0x009f,
+    0x00a0, 0x0000, 0x00a2, 0x00a3, 0x00a4, 0x00a5, 0x00a6, 0x00a7,
+    0x00a8, 0x00a9, 0x00d7, 0x00ab, 0x00ac, 0x00ad, 0x00ae, 0x00af,
+    0x00b0, 0x00b1, 0x00b2, 0x00b3, 0x00b4, 0x00b5, 0x00b6, 0x00b7,
+    0x00b8, 0x00b9, 0x00f7, 0x00bb, 0x00bc, 0x00bd, 0x00be, 0x0000,
+    0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000,
+    0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000,
+    0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000,
+    0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x2017,
+    0x05d0, 0x05d1, 0x05d2, 0x05d3, 0x05d4, 0x05d5, 0x05d6, 0x05d7,
+    0x05d8, 0x05d9, 0x05da, 0x05db, 0x05dc, 0x05dd, 0x05de, 0x05df,
+    0x05e0, 0x05e1, 0x05e2, 0x05e3, 0x05e4, 0x05e5, 0x05e6, 0x05e7,
+    0x05e8, 0x05e9, 0x05ea, 0x0000, 0x0000, 0x200e, 0x200f, 0x0000,
+];
+static ISO_8859_9: [u16; 128] = [
+    0x0080, 0x0081, 0x0082, 0x0083, 0x0084, 0x0085, 0x0086, 0x0087,
+    0x0088, 0x0089, 0x008a, 0x008b, 0x008c, 0x008d, 0x008e, 0x008f,
+    0x0090, 0x0091, 0x0092, 0x0093, 0x0094, 0x0095, 0x0096, 0x0097,
+    0x0098, 0x0099, 0x009a, 0x009b, 0x009c, 0x009d, 0x009e, 0x009f,
+    0x00a0, 0x00a1, 0x00a2, 0x00a3, 0x00a4, 0x00a5, 0x00a6, 0x00a7,
+    0x00a8, 0x00a9, 0x00aa, 0x00ab, 0x00ac, 0x00ad, 0x00ae, 0x00af,
+    0x00b0, 0x00b1, 0x00b2, 0x00b3, 0x00b4, 0x00b5, 0x00b6, 0x00b7,
+    0x00b8, 0x00b9, 0x00ba, 0x00bb, 0x00bc, 0x00bd, 0x00be, 0x00bf,
+    0x00c0, 0x00c1, 0x00c2, 0x00c3, 0x00c4, 0x00c5, 0x00c6, 0x00c7,
+    0x00c8, 0x00c9, 0x00ca, 0x00cb, 0x00cc, 0x00cd, 0x00ce, 0x00cf,
+    0x011e, 0x00d1, 0x00d2, 0x00d3, 0x00d4, 0x00d5, 0x00d6, 0x00d7,
+    0x00d8, 0x00d9, 0x00da, 0x00db, 0x00dc, 0x0130, 0x015e, 0x00df,
+    0x00e0, 0x00e1, 0x00e2, 0x00e3, 0x00e4, 0x00e5, 0x00e6, 0x00e7,
+    0x00e8, 0x00e9, 0x00ea, 0x00eb, 0x00ec, 0x00ed, 0x00ee, 0x00ef,
+    0x011f, 0x00f1, 0x00f2, 0x00f3, 0x00f4, 0x00f5, 0x00f6, 0x00f7,
+    0x00f8, 0x00f9, 0x00fa, 0x00fb, 0x00fc, 0x0131, 0x015f, 0x00ff,
+];
+static ISO_8859_10: [u16; 128] = [
+    0x0080, 0x0081, 0x0082, 0x0083, 0x0084, 0x0085, 0x0086, 0x0087,
+    0x0088, 0x0089, 0x008a, 0x008b, 0x008c, 0x008d, 0x008e, 0x008f,
+    0x0090, 0x0091, 0x0092, 0x0093, 0x0094, 0x0095, 0x0096, 0x0097,
+    0x0098, 0x0099, 0x009a, 0x009b, 0x009c, 0x009d, 0x009e, 0x009f,
+    0x00a0, 0x0104, 0x0112, 0x0122, 0x012a, 0x0128, 0x0136, 0x00a7,
+    0x013b, 0x0110, 0x0160, 0x0166, 0x017d, 0x00ad, 0x016a, 0x014a,
+    0x00b0, 0x0105, 0x0113, 0x0123, 0x012b, 0x0129, 0x0137, 0x00b7,
+    0x013c, 0x0111, 0x0161, 0x0167, 0x017e, 0x2015, 0x016b, 0x014b,
+    0x0100, 0x00c1, 0x00c2, 0x00c3, 0x00c4, 0x00c5, 0x00c6, 0x012e,
+    0x010c, 0x00c9, 0x0118, 0x00cb, 0x0116, 0x00cd, 0x00ce, 0x00cf,
+    0x00d0, 0x0145, 0x014c, 0x00d3, 0x00d4, 0x00d5, 0x00d6, 0x0168,
+    0x00d8, 0x0172, 0x00da, 0x00db, 0x00dc, 0x00dd, 0x00de, 0x00df,
+    0x0101, 0x00e1, 0x00e2, 0x00e3, 0x00e4, 0x00e5, 0x00e6, 0x012f,
+    0x010d, 0x00e9, 0x0119, 0x00eb, 0x0117, 0x00ed, 0x00ee, 0x00ef,
+    0x00f0, 0x0146, 0x014d, 0x00f3, 0x00f4, 0x00f5, 0x00f6, 0x0169,
+    0x00f8, 0x0173, 0x00fa, 0x00fb, 0x00fc, 0x00fd, 0x00fe, 0x0138,
+];
+static ISO_8859_11: [u16; 128] = [
+    0x0080, 0x0081, 0x0082, 0x0083, 0x0084, 0x0085, 0x0086, 0x0087,
+    0x0088, 0x0089, 0x008a, 0x008b, 0x008c, 0x008d, 0x008e, 0x008f,
+    0x0090, 0x0091, 0x0092, 0x0093, 0x0094, 0x0095, 0x0096, 0x0097,
+    0x0098, 0x0099, 0x009a, 0x009b, 0x009c, 0x009d, 0x009e, 0x009f,
+    0x00a0, 0x0e01, 0x0e02, 0x0e03, 0x0e04, 0x0e05, 0x0e06, 0x0e07,
+    0x0e08, 0x0e09, 0x0e0a, 0x0e0b, 0x0e0c, 0x0e0d, 0x0e0e, 0x0e0f,
+    0x0e10, 0x0e11, 0x0e12, 0x0e13, 0x0e14, 0x0e15, 0x0e16, 0x0e17,
+    0x0e18, 0x0e19, 0x0e1a, 0x0e1b, 0x0e1c, 0x0e1d, 0x0e1e, 0x0e1f,
+    0x0e20, 0x0e21, 0x0e22, 0x0e23, 0x0e24, 0x0e25, 0x0e26, 0x0e27,
+    0x0e28, 0x0e29, 0x0e2a, 0x0e2b, 0x0e2c, 0x0e2d, 0x0e2e, 0x0e2f,
+    0x0e30, 0x0e31, 0x0e32, 0x0e33, 0x0e34, 0x0e35, 0x0e36, 0x0e37,
+    0x0e38, 0x0e39, 0x0e3a, 0x0000, 0x0000, 0x0000, 0x0000, 0x0e3f,
+    0x0e40, 0x0e41, 0x0e42, 0x0e43, 0x0e44, 0x0e45, 0x0e46, 0x0e47,
+    0x0e48, 0x0e49, 0x0e4a, 0x0e4b, 0x0e4c, 0x0e4d, 0x0e4e, 0x0e4f,
+    0x0e50, 0x0e51, 0x0e52, 0x0e53, 0x0e54, 0x0e55, 0x0e56, 0x0e57,
+    0x0e58, 0x0e59, 0x0e5a, 0x0e5b, 0x0000, 0x0000, 0x0000, 0x0000,
+];
+static ISO_8859_13: [u16; 128] = [
+    0x0080, 0x0081, 0x0082, 0x0083, 0x0084, 0x0085, 0x0086, 0x0087,
+    0x0088, 0x0089, 0x008a, 0x008b, 0x008c, 0x008d, 0x008e, 0x008f,
+    0x0090, 0x0091, 0x0092, 0x0093, 0x0094, 0x0095, 0x0096, 0x0097,
+    0x0098, 0x0099, 0x009a, 0x009b, 0x009c, 0x009d, 0x009e, 0x009f,
+    0x00a0, 0x201d, 0x00a2, 0x00a3, 0x00a4, 0x201e, 0x00a6, 0x00a7,
+    0x00d8, 0x00a9, 0x0156, 0x00ab, 0x00ac, 0x00ad, 0x00ae, 0x00c6,
+    0x00b0, 0x00b1, 0x00b2, 0x00b3, 0x201c, 0x00b5, 0x00b6, 0x00b7,
+    0x00f8, 0x00b9, 0x0157, 0x00bb, 0x00bc, 0x00bd, 0x00be, 0x00e6,
+    0x0104, 0x012e, 0x0100, 0x0106, 0x00c4, 0x00c5, 0x0118, 0x0112,
+    0x010c, 0x00c9, 0x0179, 0x0116, 0x0122, 0x0136, 0x012a, 0x013b,
+    0x0160, 0x0143, 0x0145, 0x00d3, 0x014c, 0x00d5, 0x00d6, 0x00d7,
+    0x0172, 0x0141, 0x015a, 0x016a, 0x00dc, 0x017b, 0x017d, 0x00df,
+    0x0105, 0x012f, 0x0101, 0x0107, 0x00e4, 0x00e5, 0x0119, 0x0113,
+    0x010d, 0x00e9, 0x017a, 0x0117, 0x0123, 0x0137, 0x012b, 0x013c,
+    0x0161, 0x0144, 0x0146, 0x00f3, 0x014d, 0x00f5, 0x00f6, 0x00f7,
+    0x0173, 0x0142, 0x015b, 0x016b, 0x00fc, 0x017c, 0x017e, 0x2019,
+];
+static ISO_8859_14: [u16; 128] = [
+    0x0080, 0x0081, 0x0082, 0x0083, 0x0084, 0x0085, 0x0086, 0x0087,
+    0x0088, 0x0089, 0x008a, 0x008b, 0x008c, 0x008d, 0x008e, 0x008f,
+    0x0090, 0x0091, 0x0092, 0x0093, 0x0094, 0x0095, 0x0096, 0x0097,
+    0x0098, 0x0099, 0x009a, 0x009b, 0x009c, 0x009d, 0x009e, 0x009f,
+    0x00a0, 0x1e02, 0x1e03, 0x00a3, 0x010a, 0x010b, 0x1e0a, 0x00a7,
+    0x1e80, 0x00a9, 0x1e82, 0x1e0b, 0x1ef2, 0x00ad, 0x00ae, 0x0178,
+    0x1e1e, 0x1e1f, 0x0120, 0x0121, 0x1e40, 0x1e41, 0x00b6, 0x1e56,
+    0x1e81, 0x1e57, 0x1e83, 0x1e60, 0x1ef3, 0x1e84, 0x1e85, 0x1e61,
+    0x00c0, 0x00c1, 0x00c2, 0x00c3, 0x00c4, 0x00c5, 0x00c6, 0x00c7,
+    0x00c8, 0x00c9, 0x00ca, 0x00cb, 0x00cc, 0x00cd, 0x00ce, 0x00cf,
+    0x0174, 0x00d1, 0x00d2, 0x00d3, 0x00d4, 0x00d5, 0x00d6, 0x1e6a,
+    0x00d8, 0x00d9, 0x00da, 0x00db, 0x00dc, 0x00dd, 0x0176, 0x00df,
+    0x00e0, 0x00e1, 0x00e2, 0x00e3, 0x00e4, 0x00e5, 0x00e6, 0x00e7,
+    0x00e8, 0x00e9, 0x00ea, 0x00eb, 0x00ec, 0x00ed, 0x00ee, 0x00ef,
+    0x0175, 0x00f1, 0x00f2, 0x00f3, 0x00f4, 0x00f5, 0x00f6, 0x1e6b,
+    0x00f8, 0x00f9, 0x00fa, 0x00fb, 0x00fc, 0x00fd, 0x0177, 0x00ff,
+];
+static ISO_8859_15: [u16; 128] = [
+    0x0080, 0x0081, 0x0082, 0x0083, 0x0084, 0x0085, 0x0086, 0x0087,
+    0x0088, 0x0089, 0x008a, 0x008b, 0x008c, 0x008d, 0x008e, 0x008f,
+    0x0090, 0x0091, 0x0092, 0x0093, 0x0094, 0x0095, 0x0096, 0x0097,
+    0x0098, 0x0099, 0x009a, 0x009b, 0x009c, 0x009d, 0x009e, 0x009f,
+    0x00a0, 0x00a1, 0x00a2, 0x00a3, 0x20ac, 0x00a5, 0x0160, 0x00a7,
+    0x0161, 0x00a9, 0x00aa, 0x00ab, 0x00ac, 0x00ad, 0x00ae, 0x00af,
+    0x00b0, 0x00b1, 0x00b2, 0x00b3, 0x017d, 0x00b5, 0x00b6, 0x00b7,
+    0x017e, 0x00b9, 0x00ba, 0x00bb, 0x0152, 0x0153, 0x0178, 0x00bf,
+    0x00c0, 0x00c1, 0x00c2, 0x00c3, 0x00c4, 0x00c5, 0x00c6, 0x00c7,
+    0x00c8, 0x00c9, 0x00ca, 0x00cb, 0x00cc, 0x00cd, 0x00ce, 0x00cf,
+    0x00d0, 0x00d1, 0x00d2, 0x00d3, 0x00d4, 0x00d5, 0x00d6, 0x00d7,
+    0x00d8, 0x00d9, 0x00da, 0x00db, 0x00dc, 0x00dd, 0x00de, 0x00df,
+    0x00e0, 0x00e1, 0x00e2, 0x00e3, 0x00e4, 0x00e5, 0x00e6, 0x00e7,
+    0x00e8, 0x00e9, 0x00ea, 0x00eb, 0x00ec, 0x00ed, 0x00ee, 0x00ef,
+    0x00f0, 0x00f1, 0x00f2, 0x00f3, 0x00f4, 0x00f5, 0x00f6, 0x00f7,
+    0x00f8, 0x00f9, 0x00fa, 0x00fb, 0x00fc, 0x00fd, 0x00fe, 0x00ff,
+];
+static ISO_8859_16: [u16; 128] = [
+    0x0080, 0x0081, 0x0082, 0x0083, 0x0084, 0x0085, 0x0086, 0x0087,
+    0x0088, 0x0089, 0x008a, 0x008b, 0x008c, 0x008d, 0x008e, 0x008f,
+    0x0090, 0x0091, 0x0092, 0x0093, 0x0094, 0x0095, 0x0096, 0x0097,
+    0x0098, 0x0099, 0x009a, 0x009b, 0x009c, 0x009d, 0x009e, 0x009f,
+    0x00a0, 0x0104, 0x0105, 0x0141, 0x20ac, 0x201e, 0x0160, 0x00a7,
+    0x0161, 0x00a9, 0x0218, 0x00ab, 0x0179, 0x00ad, 0x017a, 0x017b,
+    0x00b0, 0x00b1, 0x010c, 0x0142, 0x017d, 0x201d, 0x00b6, 0x00b7,
+    0x017e, 0x010d, 0x0219, 0x00bb, 0x0152, 0x0153, 0x0178, 0x017c,
+    0x00c0, 0x00c1, 0x00c2, 0x0102, 0x00c4, 0x0106, 0x00c6, 0x00c7,
+    0x00c8, 0x00c9, 0x00ca, 0x00cb, 0x00cc, 0x00cd, 0x00ce, 0x00cf,
+    0x0110, 0x0143, 0x00d2, 0x00d3, 0x00d4, 0x0150, 0x00d6, 0x015a,
+    0x0170, 0x00d9, 0x00da, 0x00db, 0x00dc, 0x0118, 0x021a, 0x00df,
+    0x00e0, 0x00e1, 0x00e2, 0x0103, 0x00e4, 0x0107, 0x00e6, 0x00e7,
+    0x00e8, 0x00e9, 0x00ea, 0x00eb, 0x00ec, 0x00ed, 0x00ee, 0x00ef,
+    0x0111, 0x0144, 0x00f2, 0x00f3, 0x00f4, 0x0151, 0x00f6, 0x015b,
+    0x0171, 0x00f9, 0x00fa, 0x00fb, 0x00fc, 0x0119, 0x021b, 0x00ff,
+];
@@ -0,0 +1,368 @@
+/* Copyright 2018 Torbjørn Birch Moltu
+ *
+ * Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+ * http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+ * http://opensource.org/licenses/MIT>, at your option. This file may not be
+ * copied, modified, or distributed except according to those terms.
+ */
+
+//! Detecting which Unicode encoding a text is stored in.
+//!
+//! # Examples
+//!
+//! ```
+//! use encode_unicode::detect::{sniff, Encoding, Confidence};
+//!
+//! assert_eq!(sniff(b"\xef\xbb\xbfabc"), (Encoding::Utf8, 3, Confidence::Certain));
+//! assert_eq!(sniff(b"a\0b\0c\0"), (Encoding::Utf16Le, 0, Confidence::High));
+//! assert_eq!(sniff("æøå".as_bytes()), (Encoding::Utf8, 0, Confidence::High));
+//! assert_eq!(sniff(b"plain"), (Encoding::Utf8, 0, Confidence::Medium));
+//! assert_eq!(sniff(b"caf\xe9 au lait"), (Encoding::Utf8, 0, Confidence::Low));
+//! ```
+
+use utf8_char::Utf8Char;
+use errors::InvalidUtf8Slice;
+extern crate core;
+use self::core::char;
+#[cfg(feature="std")]
+use std::io::{Read, Error as ioError, ErrorKind};
+
+
+/// The Unicode encodings `sniff()` can detect.
+#[derive(Clone,Copy, Debug, PartialEq,Eq, Hash)]
+pub enum Encoding {
+    /// UTF-8
+    Utf8,
+    /// UTF-16 with the least significant byte of each unit first.
+    Utf16Le,
+    /// UTF-16 with the most significant byte of each unit first.
+    Utf16Be,
+    /// UTF-32 with the least significant byte first.
+    Utf32Le,
+    /// UTF-32 with the most significant byte first.
+    Utf32Be,
+}
+impl Encoding {
+    /// The byte order mark of this encoding.
+    pub fn bom(self) -> &'static [u8] {
+        match self {
+            Encoding::Utf8 => b"\xef\xbb\xbf",
+            Encoding::Utf16Le => b"\xff\xfe",
+            Encoding::Utf16Be => b"\xfe\xff",
+            Encoding::Utf32Le => b"\xff\xfe\x00\x00",
+            Encoding::Utf32Be => b"\x00\x00\xfe\xff",
+        }
+    }
+    /// The number of bytes in a code unit: 1, 2 or 4.
+    pub fn unit_len(self) -> usize {
+        match self {
+            Encoding::Utf8 => 1,
+            Encoding::Utf16Le | Encoding::Utf16Be => 2,
+            Encoding::Utf32Le | Encoding::Utf32Be => 4,
+        }
+    }
+    fn unit(self,  bytes: &[u8]) -> u32 {
+        match self {
+            Encoding::Utf8 => bytes[0] as u32,
+            Encoding::Utf16Le => bytes[0] as u32 | (bytes[1] as u32) << 8,
+            Encoding::Utf16Be => (bytes[0] as u32) << 8 | bytes[1] as u32,
+            Encoding::Utf32Le => bytes.iter().rev().fold(0, |acc, &b| acc << 8 | b as u32 ),
+            Encoding::Utf32Be => bytes.iter().fold(0, |acc, &b| acc << 8 | b as u32 ),
+        }
+    }
+}
+
+/// How sure `sniff()` is about its answer.
+#[derive(Clone,Copy, Debug, PartialEq,Eq, PartialOrd,Ord, Hash)]
+pub enum Confidence {
+    /// The input isn't valid in any of the encodings,
+    /// and UTF-8 is returned as a default.
+    Low,
+    /// The input is valid in the encoding but also in others,
+    /// such as when it's all ASCII,
+    /// or it's UTF-8 where only a few of the non-ASCII bytes are invalid.
+    Medium,
+    /// The input is valid in the encoding and has patterns that are
+    /// characteristic of it.
+    High,
+    /// The input starts with a byte order mark.
+    Certain,
+}
+
+/// Guess the encoding of the start of a text.
+///
+/// Returns the encoding, the length of the byte order mark
+/// (zero if there is none), and how reliable the guess is.
+///
+/// Without a byte order mark, input that contains zero bytes is checked for
+/// being valid UTF-32 or UTF-16 without NUL characters,
+/// and the byte order is decided by where the zero bytes are.
+/// Otherwise the input is validated as UTF-8 with
+/// `Utf8Char::from_slice_start()`, skipping one byte at a time past invalid
+/// sequences. If at least nine in ten of the non-ASCII bytes are part of
+/// valid sequences, the confidence is `Medium`, and otherwise `Low`.
+/// The input can end in the middle of a character, but only complete units
+/// count as evidence for UTF-16 or UTF-32, so input shorter than one unit
+/// is never guessed to be in them.
+pub fn sniff(start: &[u8]) -> (Encoding, usize, Confidence) {
+    // UTF-32LE must be checked before UTF-16LE, as its BOM starts with that.
+    for &encoding in &[Encoding::Utf32Le, Encoding::Utf32Be, Encoding::Utf8,
+                       Encoding::Utf16Le, Encoding::Utf16Be] {
+        if start.starts_with(encoding.bom()) {
+            return (encoding, encoding.bom().len(), Confidence::Certain);
+        }
+    }
+
+    if start.contains(&0) {
+        for &encoding in &[Encoding::Utf32Le, Encoding::Utf32Be] {
+            if is_valid_without_nul(start, encoding) {
+                return (encoding, 0, Confidence::High);
+            }
+        }
+        let whole = &start[..start.len() & !1];
+        let zeros_at = |parity: usize| whole.iter().enumerate().any(|(i,&b)| b == 0  &&  i % 2 == parity );
+        for &(encoding, zeros, no_zeros) in &[(Encoding::Utf16Le, 1, 0), (Encoding::Utf16Be, 0, 1)] {
+            if zeros_at(zeros)  &&  !zeros_at(no_zeros)  &&  is_valid_without_nul(start, encoding) {
+                return (encoding, 0, Confidence::High);
+            }
+        }
+    }
+
+    let mut pos = 0;
+    let (mut valid_non_ascii, mut invalid) = (0, 0);
+    while pos < start.len() {
+        match Utf8Char::from_slice_start(&start[pos..]) {
+            Ok((_,len)) => {
+                pos += len;
+                if len > 1 {
+                    valid_non_ascii += len;
+                }
+            },
+            Err(InvalidUtf8Slice::TooShort(_)) => break,
+            Err(_) => {
+                pos += 1;
+                invalid += 1;
+            },
+        }
+    }
+    let confidence = if invalid == 0  &&  valid_non_ascii > 0 {
+        Confidence::High
+    } else if invalid*10 <= valid_non_ascii + invalid {
+        Confidence::Medium
+    } else {
+        Confidence::Low
+    };
+    (Encoding::Utf8, 0, confidence)
+}
+
+/// Check that the input is valid UTF-16 or UTF-32 and contains no NULs,
+/// but allow it to end in the middle of a character or unit.
+///
+/// Returns false if there isn't a single complete unit.
+fn is_valid_without_nul(start: &[u8],  encoding: Encoding) -> bool {
+    let whole = &start[..start.len() - start.len() % encoding.unit_len()];
+    if whole.is_empty() {
+        return false;
+    }
+    let mut after_lead = false;
+    for unit in whole.chunks_exact(encoding.unit_len()) {
+        let unit = encoding.unit(unit);
+        let is_trail = unit & 0xffff_fc00 == 0xdc00;
+        let valid = if encoding.unit_len() == 4 {
+            unit != 0  &&  char::from_u32(unit).is_some()
+        } else if after_lead {
+            is_trail
+        } else {
+            unit != 0  &&  !is_trail
+        };
+        if !valid {
+            return false;
+        }
+        after_lead = encoding.unit_len() == 2  &&  unit & 0xfc00 == 0xd800  &&  !after_lead;
+    }
+    true
+}
+
+
+/// A reader that detects the encoding of another reader,
+/// strips any byte order mark and converts the text to UTF-8.
+///
+/// The encoding is detected with `sniff()` from the start of the stream
+/// when the reader is created.
+/// Input that isn't valid in the detected encoding produces errors of kind
+/// `InvalidData`, and reading can then continue after the invalid byte or unit.
+///
+/// # Examples
+///
+/// ```
+/// use encode_unicode::detect::{SniffReader, Encoding};
+/// use std::io::Read;
+///
+/// let file: &[u8] = b"\xff\xfeh\0\xe9\0l\0l\0o\0";
+/// let mut reader = SniffReader::new(file).unwrap();
+/// assert_eq!(reader.encoding(), Encoding::Utf16Le);
+/// let mut text = String::new();
+/// reader.read_to_string(&mut text).unwrap();
+/// assert_eq!(text, "héllo");
+/// ```
+#[cfg(feature="std")]
+pub struct SniffReader<R:Read> {
+    inner: R,
+    encoding: Encoding,
+    confidence: Confidence,
+    /// Bytes read from `inner` but not converted yet.
+    raw: Vec<u8>,
+    raw_pos: usize,
+    /// UTF-8 bytes of a character that didn't fit in the buffer passed to `read()`.
+    prev: u32,
+    /// An error to return from the next `read()`.
+    error: Option<ioError>,
+}
+#[cfg(feature="std")]
+impl<R:Read> SniffReader<R> {
+    /// Read the start of the stream and detect its encoding.
+    ///
+    /// Up to 1024 bytes are read, unless the stream ends before that.
+    pub fn new(mut reader: R) -> Result<Self,ioError> {
+        let mut start = Vec::with_capacity(1024);
+        (&mut reader).take(1024).read_to_end(&mut start)?;
+        let (detected, bom_len, sure) = sniff(&start);
+        Ok(SniffReader {
+            inner: reader,
+            encoding: detected,
+            confidence: sure,
+            raw: start,
+            raw_pos: bom_len,
+            prev: 0,
+            error: None,
+        })
+    }
+    /// The detected encoding.
+    pub fn encoding(&self) -> Encoding {
+        self.encoding
+    }
+    /// How sure the detection was.
+    pub fn confidence(&self) -> Confidence {
+        self.confidence
+    }
+    /// Extracts the source reader.
+    ///
+    /// Bytes that have been read from it but not converted are lost.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+    /// Make at least `n` unconverted bytes available, unless the stream ends.
+    fn fill(&mut self,  n: usize) -> Result<bool,ioError> {
+        if self.raw.len() - self.raw_pos >= n {
+            return Ok(true);
+        }
+        self.raw.drain(..self.raw_pos);
+        self.raw_pos = 0;
+        while self.raw.len() < n {
+            let mut buf = [0; 256];
+            match self.inner.read(&mut buf)? {
+                0 => return Ok(false),
+                read => self.raw.extend_from_slice(&buf[..read]),
+            }
+        }
+        Ok(true)
+    }
+    /// Read an unit, or `None` at the end of the stream.
+    fn unit(&mut self) -> Result<Option<u32>,ioError> {
+        let len = self.encoding.unit_len();
+        if !self.fill(len)? {
+            return if self.raw_pos == self.raw.len() {
+                Ok(None)
+            } else {
+                Err(ioError::new(ErrorKind::InvalidData, "the stream ends in the middle of a unit"))
+            };
+        }
+        let unit = self.encoding.unit(&self.raw[self.raw_pos..self.raw_pos+len]);
+        self.raw_pos += len;
+        Ok(Some(unit))
+    }
+    /// Decode an UTF-8 sequence, reading only as many bytes as it needs.
+    fn next_utf8(&mut self) -> Result<Option<char>,ioError> {
+        let mut needed = 1;
+        loop {
+            let complete = self.fill(needed)?;
+            if self.raw_pos == self.raw.len() {
+                return Ok(None);
+            }
+            match Utf8Char::from_slice_start(&self.raw[self.raw_pos..]) {
+                Ok((u8c, len)) => {
+                    self.raw_pos += len;
+                    return Ok(Some(u8c.to_char()));
+                },
+                Err(InvalidUtf8Slice::TooShort(len)) if complete => needed = len,
+                Err(InvalidUtf8Slice::TooShort(_)) => {
+                    self.raw_pos = self.raw.len();
+                    let message = "the stream ends in the middle of a character";
+                    return Err(ioError::new(ErrorKind::InvalidData, message));
+                },
+                Err(e) => {
+                    self.raw_pos += 1;
+                    return Err(ioError::new(ErrorKind::InvalidData, e));
+                },
+            }
+        }
+    }
+    fn next_char(&mut self) -> Result<Option<char>,ioError> {
+        if self.encoding == Encoding::Utf8 {
+            return self.next_utf8();
+        }
+        let first = match self.unit()? {
+            Some(unit) => unit,
+            None => return Ok(None),
+        };
+        let codepoint = if self.encoding.unit_len() == 2  &&  first & 0xfc00 == 0xd800 {
+            match self.unit()? {
+                Some(second) if second & 0xfc00 == 0xdc00 => {
+                    0x10000 + ((first & 0x3ff) << 10 | (second & 0x3ff))
+                },
+                _ => return Err(ioError::new(ErrorKind::InvalidData, "unpaired surrogate")),
+            }
+        } else {
+            first
+        };
+        match char::from_u32(codepoint) {
+            Some(c) => Ok(Some(c)),
+            None => Err(ioError::new(ErrorKind::InvalidData, "invalid codepoint")),
+        }
+    }
+}
+#[cfg(feature="std")]
+impl<R:Read> Read for SniffReader<R> {
+    fn read(&mut self,  buf: &mut[u8]) -> Result<usize, ioError> {
+        let mut i = 0;
+        // write remaining bytes of previous codepoint
+        while self.prev != 0  &&  i < buf.len() {
+            buf[i] = self.prev as u8;
+            self.prev >>= 8;
+            i += 1;
+        }
+        if let Some(e) = self.error.take() {
+            return Err(e);
+        }
+        while i < buf.len() {
+            let (bytes, len) = match self.next_char() {
+                Ok(Some(c)) => Utf8Char::from(c).to_array(),
+                Ok(None) => break,
+                Err(e) => {
+                    if i == 0 {
+                        return Err(e);
+                    }
+                    self.error = Some(e);
+                    break;
+                },
+            };
+            let fits = if len < buf.len()-i {len} else {buf.len()-i};
+            buf[i..i+fits].copy_from_slice(&bytes[..fits]);
+            i += fits;
+            for &b in bytes[fits..len].iter().rev() {
+                self.prev = (self.prev << 8) | b as u32;
+            }
+        }
+        Ok(i)
+    }
+}
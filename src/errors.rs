@@ -11,9 +11,13 @@
 
 extern crate core;
 use self::core::fmt::{self,Display,Formatter};
+use self::core::error::Error;
 #[cfg(feature="std")]
-use std::error::Error;
+use std::io;
 
+// `Error` is in `core` since Rust 1.81, so it's implemented in all
+// configurations, but the inherent `description()` that `#![no_std]` builds
+// had before is kept so that calling it doesn't require importing the trait.
 macro_rules! description {($err:ty, $desc:expr) => {
     #[cfg(not(feature="std"))]
     impl $err {
@@ -22,7 +26,6 @@ macro_rules! description {($err:ty, $desc:expr) => {
             ($desc)(self)
         }
     }
-    #[cfg(feature="std")]
     impl Error for $err {
         fn description(&self) -> &'static str {
             ($desc)(self)
@@ -43,6 +46,46 @@ pub struct InvalidUtf16FirstUnit;
 description!{InvalidUtf16FirstUnit, |_| "Is a trailing surrogate" }
 
 
+/// Cannot store a codepoint as a single UTF-16 unit (UCS-2),
+/// because it is outside the Basic Multilingual Plane and needs a surrogate pair.
+#[derive(Clone,Copy, Debug, PartialEq,Eq)]
+pub struct NonBmpError;
+description!{NonBmpError, |_| "is outside the Basic Multilingual Plane" }
+
+/// Encoding as UCS-2 stopped at a codepoint outside the Basic Multilingual Plane,
+/// or at an unpaired surrogate in UTF-16.
+///
+/// Returned by `Ucs2Char::encode_str()`, `encode_chars()` and `encode_utf16()`
+/// when the substitution policy is `Ucs2Substitution::Reject`.
+#[derive(Clone,Copy, Debug, PartialEq,Eq)]
+pub struct Ucs2EncodeError {
+    read: usize,
+    written: usize,
+}
+description!{Ucs2EncodeError, |_| "cannot encode a codepoint outside the Basic Multilingual Plane as UCS-2" }
+impl Ucs2EncodeError {
+    pub(crate) fn new(valid_up_to: usize,  units_written: usize) -> Self {
+        Ucs2EncodeError{ read: valid_up_to,  written: units_written }
+    }
+    /// The number of bytes, `char`s or units that were encoded before the
+    /// offending codepoint, which is also the index of it.
+    pub fn valid_up_to(&self) -> usize {
+        self.read
+    }
+    /// The number of units written to the destination slice before stopping.
+    pub fn units_written(&self) -> usize {
+        self.written
+    }
+}
+
+
+/// Cannot add a character or string to an `ArrayUtf8String` or
+/// `ArrayUtf16String`, because there isn't room for all of it.
+#[derive(Clone,Copy, Debug, PartialEq,Eq)]
+pub struct CapacityError;
+description!{CapacityError, |_| "there isn't enough capacity left" }
+
+
 macro_rules! simple {(#[$tydoc:meta] $err:ident  {
                           $($(#[$vardoc:meta])* ::$variant:ident => $string:expr),+,
                       } ) => {
@@ -67,7 +110,7 @@ impl InvalidCodepoint {
     /// Get the range of values for which this error would be given.
     pub fn error_range(self) -> (u32,u32) {match self {
         Utf16Reserved => (0xd8_00, 0xdf_ff),
-        TooHigh => (0x00_10_ff_ff, 0xff_ff_ff_ff),
+        TooHigh => (0x00_11_00_00, 0xff_ff_ff_ff),
     }}
 }
 
@@ -144,22 +187,24 @@ macro_rules! complex {
         pub fn description(&self) -> &'static str {
             match *self{ $($desc => $string,)* }
         }
-        /// A hack to avoid two Display impls
-        fn cause(&self) -> Option<&Display> {None}
     }
-    #[cfg(feature="std")]
     impl Error for $err {
         fn description(&self) -> &'static str {
             match *self{ $($desc => $string,)* }
         }
         $(#[$causedoc])*
-        fn cause(&self) -> Option<&Error> {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
             match *self{ $($cause => $result,)* }
         }
     }
     impl Display for $err {
         fn fmt(&self,  fmtr: &mut Formatter) -> fmt::Result {
-            match (self.cause(), $use_cause) {
+            // without std the cause has never been included
+            #[cfg(feature="std")]
+            let cause = self.source();
+            #[cfg(not(feature="std"))]
+            let cause: Option<&dyn Error> = None;
+            match (cause, $use_cause) {
                 (Some(d),true) => write!(fmtr, "{}: {}", self.description(), d),
                         _      => write!(fmtr, "{}", self.description()),
             }
@@ -243,3 +288,536 @@ complex!{InvalidUtf8Slice {
         InvalidUtf8Slice::TooShort(_) => None,
     }
 }
+
+
+/// Reasons why a string is not valid UTF-7 or IMAP modified UTF-7.
+///
+/// The `usize` is the byte index of the offending character,
+/// or of the `+` or `&` that starts the offending shifted sequence.
+#[derive(Clone,Copy, Debug, PartialEq,Eq)]
+pub enum InvalidUtf7 {
+    /// The character is not allowed outside of shifted sequences.
+    UnexpectedCharacter(usize),
+    /// A shifted sequence contains no characters.
+    EmptyShift(usize),
+    /// The bits left at the end of a shifted sequence are not zero,
+    /// or are enough for another base64 character.
+    InvalidPadding(usize),
+    /// A shifted sequence decodes to a surrogate that isn't part of a pair.
+    UnpairedSurrogate(usize),
+    /// A shifted sequence is not terminated by `-`,
+    /// which the IMAP variant requires.
+    UnterminatedShift(usize),
+    /// A shifted sequence contains a character that must be encoded
+    /// directly in the IMAP variant.
+    DirectlyEncodable(usize),
+}
+complex!{InvalidUtf7 {} {
+        InvalidUtf7::UnexpectedCharacter(_) => "the character is not allowed outside of shifted sequences",
+        InvalidUtf7::EmptyShift(_) => "a shifted sequence is empty",
+        InvalidUtf7::InvalidPadding(_) => "a shifted sequence has invalid padding bits",
+        InvalidUtf7::UnpairedSurrogate(_) => "a shifted sequence contains an unpaired surrogate",
+        InvalidUtf7::UnterminatedShift(_) => "a shifted sequence is not terminated by '-'",
+        InvalidUtf7::DirectlyEncodable(_) => "a shifted sequence contains a printable ASCII character",
+    } => false => {
+        _ => None,
+    }
+}
+impl InvalidUtf7 {
+    /// The byte index in the input where the error was detected.
+    pub fn position(self) -> usize {match self {
+        InvalidUtf7::UnexpectedCharacter(i) |
+        InvalidUtf7::EmptyShift(i) |
+        InvalidUtf7::InvalidPadding(i) |
+        InvalidUtf7::UnpairedSurrogate(i) |
+        InvalidUtf7::UnterminatedShift(i) |
+        InvalidUtf7::DirectlyEncodable(i) => i,
+    }}
+}
+
+
+/// Reasons why Punycode encoding or decoding failed.
+#[derive(Clone,Copy, Debug, PartialEq,Eq)]
+pub enum InvalidPunycode {
+    /// The character at this byte index is not ASCII.
+    NonBasic(usize),
+    /// The character at this byte index is not a base 36 digit.
+    InvalidDigit(usize),
+    /// The input ends in the middle of an encoded number.
+    Truncated,
+    /// An intermediate value doesn't fit in an `u32`.
+    ///
+    /// When encoding this requires very long input.
+    Overflow,
+    /// A decoded codepoint is invalid.
+    Codepoint(InvalidCodepoint),
+}
+complex!{InvalidPunycode {
+        InvalidCodepoint => InvalidPunycode::Codepoint,
+    } {
+        InvalidPunycode::NonBasic(_) => "the input contains a non-ASCII character",
+        InvalidPunycode::InvalidDigit(_) => "the input contains an invalid digit",
+        InvalidPunycode::Truncated => "the input ends in the middle of a number",
+        InvalidPunycode::Overflow => "an intermediate value overflowed",
+        InvalidPunycode::Codepoint(_) => "a decoded codepoint is invalid",
+    } => true => {
+        InvalidPunycode::Codepoint(ref c) => Some(c),
+        _ => None,
+    }
+}
+
+
+/// A byte or character that a single-byte code page cannot convert.
+#[derive(Clone,Copy, Debug, PartialEq,Eq)]
+pub enum Unmappable {
+    /// The byte isn't assigned a character in the code page.
+    Byte(u8),
+    /// The character isn't in the code page.
+    Char(char),
+}
+complex!{Unmappable {} {
+        Unmappable::Byte(_) => "the byte isn't assigned a character in the code page",
+        Unmappable::Char(_) => "the character isn't in the code page",
+    } => false => {
+        _ => None,
+    }
+}
+
+
+/// Reasons why SCSU decoding failed.
+///
+/// All variants contain the index of the byte that starts the invalid
+/// sequence.
+#[derive(Clone,Copy, Debug, PartialEq,Eq)]
+pub enum InvalidScsu {
+    /// The byte is a tag that UTS #6 reserves for future use.
+    ReservedTag(usize),
+    /// A tag defines a window with an offset that is reserved.
+    ReservedWindowOffset(usize),
+    /// A unit is a surrogate that isn't part of a pair.
+    UnpairedSurrogate(usize),
+    /// The input ends in the middle of a tag or unit.
+    Truncated(usize),
+}
+complex!{InvalidScsu {} {
+        InvalidScsu::ReservedTag(_) => "the input contains a reserved tag",
+        InvalidScsu::ReservedWindowOffset(_) => "a window is defined with a reserved offset",
+        InvalidScsu::UnpairedSurrogate(_) => "the input contains an unpaired surrogate",
+        InvalidScsu::Truncated(_) => "the input ends in the middle of a tag or unit",
+    } => false => {
+        _ => None,
+    }
+}
+impl InvalidScsu {
+    /// The byte index in the input where the invalid sequence starts.
+    pub fn position(self) -> usize {match self {
+        InvalidScsu::ReservedTag(i) |
+        InvalidScsu::ReservedWindowOffset(i) |
+        InvalidScsu::UnpairedSurrogate(i) |
+        InvalidScsu::Truncated(i) => i,
+    }}
+}
+
+
+/// Reasons why text cannot be encoded back to the bytes it was decoded from
+/// with Python's `surrogateescape` error handler.
+///
+/// The `usize` is the index of the offending unit or byte.
+#[derive(Clone,Copy, Debug, PartialEq,Eq)]
+pub enum InvalidSurrogateEscape {
+    /// A surrogate isn't part of a pair and isn't in the range
+    /// U+DC80..U+DCFF used for escaped bytes.
+    UnpairedSurrogate(usize),
+    /// The input is not valid WTF-8.
+    InvalidWtf8(usize),
+}
+complex!{InvalidSurrogateEscape {} {
+        InvalidSurrogateEscape::UnpairedSurrogate(_) => "the input contains a surrogate that is not an escaped byte",
+        InvalidSurrogateEscape::InvalidWtf8(_) => "the input is not valid WTF-8",
+    } => false => {
+        _ => None,
+    }
+}
+impl InvalidSurrogateEscape {
+    /// The index in the input where the invalid unit or sequence starts.
+    pub fn position(self) -> usize {match self {
+        InvalidSurrogateEscape::UnpairedSurrogate(i) |
+        InvalidSurrogateEscape::InvalidWtf8(i) => i,
+    }}
+}
+
+
+/// Reasons why the contents of a JSON string cannot be unescaped.
+///
+/// The `usize` is the byte index of the offending character,
+/// or of the backslash that starts the offending escape.
+#[derive(Clone,Copy, Debug, PartialEq,Eq)]
+pub enum InvalidJsonString {
+    /// A quote or control character is not escaped.
+    Unescaped(usize),
+    /// A backslash is not followed by a valid escape,
+    /// or `\u` is not followed by four hexadecimal digits.
+    InvalidEscape(usize),
+    /// The units of one or two `\u` escapes are not valid UTF-16.
+    Utf16(usize, InvalidUtf16Tuple),
+}
+complex!{InvalidJsonString {} {
+        InvalidJsonString::Unescaped(_) => "the string contains a character that must be escaped",
+        InvalidJsonString::InvalidEscape(_) => "the string contains an invalid escape",
+        InvalidJsonString::Utf16(_, _) => "the string contains an invalid surrogate escape",
+    } => true => {
+        InvalidJsonString::Utf16(_, ref e) => Some(e),
+        _ => None,
+    }
+}
+impl InvalidJsonString {
+    /// The byte index in the string where the invalid character or escape starts.
+    pub fn position(self) -> usize {match self {
+        InvalidJsonString::Unescaped(i) |
+        InvalidJsonString::InvalidEscape(i) |
+        InvalidJsonString::Utf16(i, _) => i,
+    }}
+}
+
+
+/// Reasons why an HTML or XML character reference is invalid.
+///
+/// The `usize` is the byte index of the `&` that starts the reference.
+#[derive(Clone,Copy, Debug, PartialEq,Eq)]
+pub enum InvalidCharRef {
+    /// A numeric reference has no digits or doesn't end with a `;`.
+    Malformed(usize),
+    /// A numeric reference is not a valid codepoint.
+    Codepoint(usize, InvalidCodepoint),
+    /// A name followed by `;` is not a named character reference.
+    UnknownEntity(usize),
+}
+complex!{InvalidCharRef {} {
+        InvalidCharRef::Malformed(_) => "a numeric character reference is malformed",
+        InvalidCharRef::Codepoint(_, _) => "a numeric character reference is not a valid codepoint",
+        InvalidCharRef::UnknownEntity(_) => "the named character reference is unknown",
+    } => true => {
+        InvalidCharRef::Codepoint(_, ref e) => Some(e),
+        _ => None,
+    }
+}
+impl InvalidCharRef {
+    /// The byte index of the `&` that starts the invalid reference.
+    pub fn position(self) -> usize {match self {
+        InvalidCharRef::Malformed(i) |
+        InvalidCharRef::Codepoint(i, _) |
+        InvalidCharRef::UnknownEntity(i) => i,
+    }}
+}
+
+
+/// Reasons why a string doesn't start with a valid escape sequence.
+#[derive(Clone,Copy, Debug, PartialEq,Eq)]
+pub enum InvalidEscape {
+    /// The string doesn't start with the backslash and letter of the style.
+    NotAnEscape,
+    /// There are too few hexadecimal digits, or a brace is missing.
+    Malformed,
+    /// The value is not a valid codepoint.
+    Codepoint(InvalidCodepoint),
+    /// The escaped units are not valid UTF-16.
+    Utf16(InvalidUtf16Tuple),
+    /// The escaped bytes are not valid UTF-8.
+    Utf8(InvalidUtf8Slice),
+}
+complex!{InvalidEscape {
+        InvalidCodepoint => InvalidEscape::Codepoint,
+        InvalidUtf16Tuple => InvalidEscape::Utf16,
+        InvalidUtf8Slice => InvalidEscape::Utf8,
+    } {
+        InvalidEscape::NotAnEscape => "the string doesn't start with an escape sequence",
+        InvalidEscape::Malformed => "the escape sequence is malformed",
+        InvalidEscape::Codepoint(_) => "the escaped codepoint is invalid",
+        InvalidEscape::Utf16(_) => "the escaped units are invalid UTF-16",
+        InvalidEscape::Utf8(_) => "the escaped bytes are invalid UTF-8",
+    } => true => {
+        InvalidEscape::Codepoint(ref e) => Some(e),
+        InvalidEscape::Utf16(ref e) => Some(e),
+        InvalidEscape::Utf8(ref e) => Some(e),
+        _ => None,
+    }
+}
+
+
+/// Reasons why a string is not a codepoint or range in the `U+XXXX` notation.
+#[derive(Clone,Copy, Debug, PartialEq,Eq)]
+pub enum InvalidNotation {
+    /// `U+` is missing, there are too few or too many digits,
+    /// or something follows where nothing should.
+    Malformed,
+    /// The value is not a valid codepoint.
+    Codepoint(InvalidCodepoint),
+    /// The last codepoint of the range is before the first.
+    Reversed,
+}
+complex!{InvalidNotation {
+        InvalidCodepoint => InvalidNotation::Codepoint,
+    } {
+        InvalidNotation::Malformed => "the notation is malformed",
+        InvalidNotation::Codepoint(_) => "the value is not a valid codepoint",
+        InvalidNotation::Reversed => "the range ends before it starts",
+    } => true => {
+        InvalidNotation::Codepoint(ref e) => Some(e),
+        _ => None,
+    }
+}
+
+
+/// Reasons why a string is not valid percent-encoded UTF-8.
+#[derive(Clone,Copy, Debug, PartialEq,Eq)]
+pub enum InvalidPercentEncoding {
+    /// A `%` at this byte index is not followed by two hexadecimal digits.
+    Malformed(usize),
+    /// The bytes encoded by the escapes starting at this byte index
+    /// are not valid UTF-8.
+    Utf8(usize, InvalidUtf8Slice),
+}
+complex!{InvalidPercentEncoding {} {
+        InvalidPercentEncoding::Malformed(_) => "a % is not followed by two hexadecimal digits",
+        InvalidPercentEncoding::Utf8(_, _) => "the encoded bytes are not valid UTF-8",
+    } => true => {
+        InvalidPercentEncoding::Utf8(_, ref e) => Some(e),
+        _ => None,
+    }
+}
+impl InvalidPercentEncoding {
+    /// The byte index of the `%` that starts the offending escape or sequence.
+    pub fn position(self) -> usize {match self {
+        InvalidPercentEncoding::Malformed(i) |
+        InvalidPercentEncoding::Utf8(i, _) => i,
+    }}
+}
+
+
+/// Reasons why units are not a NUL-terminated string or cannot be decoded.
+#[derive(Clone,Copy, Debug, PartialEq,Eq)]
+pub enum InvalidCString {
+    /// There is a NUL at this index before the end.
+    InteriorNul(usize),
+    /// There is no NUL among the first this many units.
+    MissingNul(usize),
+    /// The bytes starting at this index are not valid UTF-8.
+    Utf8(usize, InvalidUtf8Slice),
+    /// The units starting at this index are not valid UTF-16.
+    Utf16(usize, InvalidUtf16Slice),
+    /// The unit at this index is not a valid codepoint.
+    Codepoint(usize, InvalidCodepoint),
+}
+complex!{InvalidCString {} {
+        InvalidCString::InteriorNul(_) => "the string contains a NUL",
+        InvalidCString::MissingNul(_) => "the string is not NUL-terminated",
+        InvalidCString::Utf8(_, _) => "the string is not valid UTF-8",
+        InvalidCString::Utf16(_, _) => "the string is not valid UTF-16",
+        InvalidCString::Codepoint(_, _) => "the string contains an invalid codepoint",
+    } => true => {
+        InvalidCString::Utf8(_, ref e) => Some(e),
+        InvalidCString::Utf16(_, ref e) => Some(e),
+        InvalidCString::Codepoint(_, ref e) => Some(e),
+        _ => None,
+    }
+}
+impl InvalidCString {
+    /// The index of the offending NUL or unit,
+    /// or the number of units searched for a NUL.
+    pub fn position(self) -> usize {match self {
+        InvalidCString::InteriorNul(i) |
+        InvalidCString::MissingNul(i) |
+        InvalidCString::Utf8(i, _) |
+        InvalidCString::Utf16(i, _) |
+        InvalidCString::Codepoint(i, _) => i,
+    }}
+}
+
+
+/// Any error from this crate, with the position for those that have one.
+///
+/// This makes it possible to return errors from different decoders through
+/// one type, and to convert them into `io::Error`s with
+/// `ErrorKind::InvalidData` (with the `std` feature) from which the original
+/// error can be recovered by downcasting.
+///
+/// `InvalidUtf8Slice` and `InvalidUtf16Slice` don't contain the index of the
+/// invalid sequence, so their variants have it, and there is no `From`
+/// conversion for them.
+///
+/// # Examples
+///
+#[cfg_attr(feature="std", doc=" ```")]
+#[cfg_attr(not(feature="std"), doc=" ```no_compile")]
+/// use encode_unicode::{Utf8Char, DecodeError};
+/// use encode_unicode::error::{InvalidUtf8Slice, InvalidUtf8};
+/// use std::io;
+///
+/// fn first_char(bytes: &[u8],  start: usize) -> io::Result<Utf8Char> {
+///     match Utf8Char::from_slice_start(&bytes[start..]) {
+///         Ok((u8c, _)) => Ok(u8c),
+///         Err(e) => Err(DecodeError::Utf8(start, e).into()),
+///     }
+/// }
+///
+/// let error = first_char(b"ab\xc0\xae", 2).unwrap_err();
+/// assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+/// let decode_error = error.get_ref().unwrap().downcast_ref::<DecodeError>().unwrap();
+/// assert_eq!(decode_error, &DecodeError::Utf8(2, InvalidUtf8Slice::Utf8(InvalidUtf8::OverLong)));
+/// assert_eq!(decode_error.position(), Some(2));
+/// ```
+#[derive(Clone,Copy, Debug, PartialEq,Eq)]
+pub enum DecodeError {
+    /// Invalid UTF-8 starting at this byte index.
+    Utf8(usize, InvalidUtf8Slice),
+    /// Invalid UTF-16 starting at this unit index.
+    Utf16(usize, InvalidUtf16Slice),
+    /// See `InvalidUtf8`.
+    Utf8Sequence(InvalidUtf8),
+    /// See `InvalidUtf8Array`.
+    Utf8Array(InvalidUtf8Array),
+    /// See `InvalidUtf8FirstByte`.
+    Utf8FirstByte(InvalidUtf8FirstByte),
+    /// See `InvalidUtf16Tuple`.
+    Utf16Tuple(InvalidUtf16Tuple),
+    /// See `InvalidUtf16FirstUnit`.
+    Utf16FirstUnit(InvalidUtf16FirstUnit),
+    /// See `InvalidCodepoint`.
+    Codepoint(InvalidCodepoint),
+    /// See `FromStrError`.
+    FromStr(FromStrError),
+    /// See `EmptyStrError`.
+    EmptyStr(EmptyStrError),
+    /// See `NonBmpError`.
+    NonBmp(NonBmpError),
+    /// See `Ucs2EncodeError`.
+    Ucs2(Ucs2EncodeError),
+    /// See `InvalidUtf7`.
+    Utf7(InvalidUtf7),
+    /// See `InvalidPunycode`.
+    Punycode(InvalidPunycode),
+    /// See `InvalidScsu`.
+    Scsu(InvalidScsu),
+    /// See `Unmappable`.
+    Unmappable(Unmappable),
+    /// See `InvalidSurrogateEscape`.
+    SurrogateEscape(InvalidSurrogateEscape),
+    /// See `InvalidJsonString`.
+    Json(InvalidJsonString),
+    /// See `InvalidCharRef`.
+    CharRef(InvalidCharRef),
+    /// See `InvalidEscape`.
+    Escape(InvalidEscape),
+    /// See `InvalidNotation`.
+    Notation(InvalidNotation),
+    /// See `InvalidPercentEncoding`.
+    Percent(InvalidPercentEncoding),
+    /// See `InvalidCString`.
+    CString(InvalidCString),
+    /// See `CapacityError`.
+    Capacity(CapacityError),
+}
+complex!{DecodeError {
+        InvalidUtf8 => DecodeError::Utf8Sequence,
+        InvalidUtf8Array => DecodeError::Utf8Array,
+        InvalidUtf8FirstByte => DecodeError::Utf8FirstByte,
+        InvalidUtf16Tuple => DecodeError::Utf16Tuple,
+        InvalidUtf16FirstUnit => DecodeError::Utf16FirstUnit,
+        InvalidCodepoint => DecodeError::Codepoint,
+        FromStrError => DecodeError::FromStr,
+        EmptyStrError => DecodeError::EmptyStr,
+        NonBmpError => DecodeError::NonBmp,
+        Ucs2EncodeError => DecodeError::Ucs2,
+        InvalidUtf7 => DecodeError::Utf7,
+        InvalidPunycode => DecodeError::Punycode,
+        InvalidScsu => DecodeError::Scsu,
+        Unmappable => DecodeError::Unmappable,
+        InvalidSurrogateEscape => DecodeError::SurrogateEscape,
+        InvalidJsonString => DecodeError::Json,
+        InvalidCharRef => DecodeError::CharRef,
+        InvalidEscape => DecodeError::Escape,
+        InvalidNotation => DecodeError::Notation,
+        InvalidPercentEncoding => DecodeError::Percent,
+        InvalidCString => DecodeError::CString,
+        CapacityError => DecodeError::Capacity,
+    } {
+        DecodeError::Utf8(_, _) => "invalid UTF-8",
+        DecodeError::Utf8Sequence(_) => "invalid UTF-8",
+        DecodeError::Utf8Array(_) => "invalid UTF-8",
+        DecodeError::Utf8FirstByte(_) => "invalid UTF-8",
+        DecodeError::Utf16(_, _) => "invalid UTF-16",
+        DecodeError::Utf16Tuple(_) => "invalid UTF-16",
+        DecodeError::Utf16FirstUnit(_) => "invalid UTF-16",
+        DecodeError::Codepoint(_) => "invalid codepoint",
+        DecodeError::FromStr(_) => "invalid string",
+        DecodeError::EmptyStr(_) => "invalid string",
+        DecodeError::NonBmp(_) => "not representable in UCS-2",
+        DecodeError::Ucs2(_) => "not representable in UCS-2",
+        DecodeError::Utf7(_) => "invalid UTF-7",
+        DecodeError::Punycode(_) => "invalid Punycode",
+        DecodeError::Scsu(_) => "invalid SCSU",
+        DecodeError::Unmappable(_) => "not representable in the code page",
+        DecodeError::SurrogateEscape(_) => "invalid surrogateescape",
+        DecodeError::Json(_) => "invalid JSON string",
+        DecodeError::CharRef(_) => "invalid character reference",
+        DecodeError::Escape(_) => "invalid escape sequence",
+        DecodeError::Notation(_) => "invalid codepoint notation",
+        DecodeError::Percent(_) => "invalid percent-encoding",
+        DecodeError::CString(_) => "invalid C string",
+        DecodeError::Capacity(_) => "too long",
+    } => true => {
+        DecodeError::Utf8(_, ref e) => Some(e),
+        DecodeError::Utf16(_, ref e) => Some(e),
+        DecodeError::Utf8Sequence(ref e) => Some(e),
+        DecodeError::Utf8Array(ref e) => Some(e),
+        DecodeError::Utf8FirstByte(ref e) => Some(e),
+        DecodeError::Utf16Tuple(ref e) => Some(e),
+        DecodeError::Utf16FirstUnit(ref e) => Some(e),
+        DecodeError::Codepoint(ref e) => Some(e),
+        DecodeError::FromStr(ref e) => Some(e),
+        DecodeError::EmptyStr(ref e) => Some(e),
+        DecodeError::NonBmp(ref e) => Some(e),
+        DecodeError::Ucs2(ref e) => Some(e),
+        DecodeError::Utf7(ref e) => Some(e),
+        DecodeError::Punycode(ref e) => Some(e),
+        DecodeError::Scsu(ref e) => Some(e),
+        DecodeError::Unmappable(ref e) => Some(e),
+        DecodeError::SurrogateEscape(ref e) => Some(e),
+        DecodeError::Json(ref e) => Some(e),
+        DecodeError::CharRef(ref e) => Some(e),
+        DecodeError::Escape(ref e) => Some(e),
+        DecodeError::Notation(ref e) => Some(e),
+        DecodeError::Percent(ref e) => Some(e),
+        DecodeError::CString(ref e) => Some(e),
+        DecodeError::Capacity(ref e) => Some(e),
+    }/// Returns the wrapped error.
+}
+#[cfg(feature="std")]
+impl From<DecodeError> for io::Error {
+    /// Create an error of kind `InvalidData` that contains the `DecodeError`.
+    fn from(error: DecodeError) -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidData, error)
+    }
+}
+impl DecodeError {
+    /// The index in the input where the error was detected, for the errors
+    /// that have one.
+    ///
+    /// It's a byte index for UTF-8 and a unit index for UTF-16,
+    /// see the wrapped error for what it is in other cases.
+    pub fn position(self) -> Option<usize> {match self {
+        DecodeError::Utf8(i, _) |
+        DecodeError::Utf16(i, _) => Some(i),
+        DecodeError::Ucs2(e) => Some(e.valid_up_to()),
+        DecodeError::Utf7(e) => Some(e.position()),
+        DecodeError::Punycode(InvalidPunycode::NonBasic(i)) |
+        DecodeError::Punycode(InvalidPunycode::InvalidDigit(i)) => Some(i),
+        DecodeError::Scsu(e) => Some(e.position()),
+        DecodeError::SurrogateEscape(e) => Some(e.position()),
+        DecodeError::Json(e) => Some(e.position()),
+        DecodeError::CharRef(e) => Some(e.position()),
+        DecodeError::Percent(e) => Some(e.position()),
+        DecodeError::CString(e) => Some(e.position()),
+        _ => None,
+    }}
+}
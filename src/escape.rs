@@ -0,0 +1,198 @@
+/* Copyright 2018 Torbjørn Birch Moltu
+ *
+ * Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+ * http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+ * http://opensource.org/licenses/MIT>, at your option. This file may not be
+ * copied, modified, or distributed except according to those terms.
+ */
+
+//! Escape sequences of programming languages for single codepoints.
+
+use utf8_char::Utf8Char;
+use utf16_char::Utf16Char;
+use traits::CharExt;
+use errors::InvalidEscape;
+use errors::InvalidEscape::*;
+extern crate core;
+use self::core::{fmt, str};
+
+
+/// The escape sequence to write a codepoint as in a string literal.
+///
+/// Hexadecimal digits are written in lowercase but can be parsed in either case.
+#[derive(Clone,Copy, Debug, PartialEq,Eq, Hash)]
+pub enum EscapeStyle {
+    /// `\u{1f4a3}`, as in Rust and in JavaScript since ES2015.
+    Braced,
+    /// `\u00e6` for the Basic Multilingual Plane and `\U0001f4a3` for the
+    /// rest, as the universal character names of C and C++ and in Python.
+    Universal,
+    /// `\u00e6` and `\ud83d\udca3`, which are UTF-16 units as in JavaScript,
+    /// Java and JSON.
+    Utf16,
+    /// `\xc3\xa6`, which are UTF-8 bytes as in Python `bytes` and in C.
+    ///
+    /// C continues a `\x` escape for as long as there are hexadecimal digits,
+    /// so a literal that has one after the escape needs to be split.
+    Utf8Bytes,
+}
+
+static HEX: [u8; 16] = *b"0123456789abcdef";
+
+
+/// The iterator type returned by `Utf8Char::escape()`
+///
+/// See its documentation for details.
+#[derive(Clone)]
+pub struct Utf8CharEscape {
+    // `\xf0\x9f\x92\xa3` is the longest
+    buf: [u8; 16],
+    pos: u8,
+    len: u8,
+}
+impl Utf8CharEscape {
+    fn push(&mut self,  b: u8) {
+        self.buf[self.len as usize] = b;
+        self.len += 1;
+    }
+    fn push_hex(&mut self,  prefix: u8,  value: u32,  digits: u32) {
+        self.push(b'\\');
+        self.push(prefix);
+        for i in (0..digits).rev() {
+            self.push(HEX[(value >> (4*i)) as usize & 0xf]);
+        }
+    }
+    /// The part of the escape sequence that hasn't been returned yet.
+    pub fn as_str(&self) -> &str {
+        unsafe{ str::from_utf8_unchecked(&self.buf[self.pos as usize..self.len as usize]) }
+    }
+}
+impl Iterator for Utf8CharEscape {
+    type Item = char;
+    fn next(&mut self) -> Option<char> {
+        if self.pos == self.len {
+            return None;
+        }
+        self.pos += 1;
+        Some(self.buf[self.pos as usize - 1] as char)
+    }
+    fn size_hint(&self) -> (usize,Option<usize>) {
+        let len = (self.len - self.pos) as usize;
+        (len, Some(len))
+    }
+}
+impl ExactSizeIterator for Utf8CharEscape {}
+impl fmt::Display for Utf8CharEscape {
+    fn fmt(&self,  fmtr: &mut fmt::Formatter) -> fmt::Result {
+        fmtr.write_str(self.as_str())
+    }
+}
+impl fmt::Debug for Utf8CharEscape {
+    fn fmt(&self,  fmtr: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), fmtr)
+    }
+}
+
+
+// Is not re-exported, `Utf8Char::escape()` is the public constructor.
+pub fn escape(u8c: Utf8Char,  style: EscapeStyle) -> Utf8CharEscape {
+    let mut escape = Utf8CharEscape{ buf: [0; 16],  pos: 0,  len: 0 };
+    let c = u8c.to_char() as u32;
+    match style {
+        EscapeStyle::Braced => {
+            let mut digits = 1;
+            while digits < 6  &&  c >> (4*digits) != 0 {
+                digits += 1;
+            }
+            escape.push(b'\\');
+            escape.push(b'u');
+            escape.push(b'{');
+            for i in (0..digits).rev() {
+                escape.push(HEX[(c >> (4*i)) as usize & 0xf]);
+            }
+            escape.push(b'}');
+        },
+        EscapeStyle::Universal if c < 0x1_00_00 => escape.push_hex(b'u', c, 4),
+        EscapeStyle::Universal => escape.push_hex(b'U', c, 8),
+        EscapeStyle::Utf16 => {
+            for unit in Utf16Char::from(u8c) {
+                escape.push_hex(b'u', unit as u32, 4);
+            }
+        },
+        EscapeStyle::Utf8Bytes => {
+            for &b in u8c.as_bytes() {
+                escape.push_hex(b'x', b as u32, 2);
+            }
+        },
+    }
+    escape
+}
+
+
+/// Parse exactly `digits` hexadecimal digits starting at `start`.
+fn hex(bytes: &[u8],  start: usize,  digits: usize) -> Result<u32,InvalidEscape> {
+    if bytes.len() < start+digits {
+        return Err(Malformed);
+    }
+    let mut value = 0;
+    for &digit in &bytes[start..start+digits] {
+        match (digit as char).to_digit(16) {
+            Some(digit) => value = value << 4  |  digit,
+            None => return Err(Malformed),
+        }
+    }
+    Ok(value)
+}
+
+// Is not re-exported, `Utf8Char::from_escape_start()` is the public constructor.
+pub fn from_escape_start(src: &str,  style: EscapeStyle) -> Result<(Utf8Char,usize),InvalidEscape> {
+    let bytes = src.as_bytes();
+    let letter = match style {
+        EscapeStyle::Universal if bytes.get(1) == Some(&b'U') => b'U',
+        EscapeStyle::Utf8Bytes => b'x',
+        _ => b'u',
+    };
+    if !bytes.starts_with(&[b'\\', letter]) {
+        return Err(NotAnEscape);
+    }
+    match style {
+        EscapeStyle::Braced => {
+            if bytes.get(2) != Some(&b'{') {
+                return Err(Malformed);
+            }
+            let digits = bytes[3..].iter().take_while(|&&b| b.wrapping_sub(b'0') < 10  ||  (b | 0x20).wrapping_sub(b'a') < 6 ).count();
+            if digits == 0  ||  digits > 6  ||  bytes.get(3+digits) != Some(&b'}') {
+                return Err(Malformed);
+            }
+            let c = char::from_u32_detailed(hex(bytes, 3, digits)?)?;
+            Ok((Utf8Char::from(c), 4+digits))
+        },
+        EscapeStyle::Universal => {
+            let digits = if letter == b'U' {8} else {4};
+            let c = char::from_u32_detailed(hex(bytes, 2, digits)?)?;
+            Ok((Utf8Char::from(c), 2+digits))
+        },
+        EscapeStyle::Utf16 => {
+            let first = hex(bytes, 2, 4)? as u16;
+            let mut second = None;
+            if first & 0xfc00 == 0xd800  &&  bytes[6..].starts_with(b"\\u") {
+                second = Some(hex(bytes, 8, 4)? as u16);
+            }
+            let u16c = Utf16Char::from_tuple((first, second))?;
+            Ok((Utf8Char::from(u16c), 6*u16c.len()))
+        },
+        EscapeStyle::Utf8Bytes => {
+            let mut utf8 = [hex(bytes, 2, 2)? as u8, 0, 0, 0];
+            let mut found = 1;
+            while found < 4  &&  bytes[4*found..].starts_with(b"\\x") {
+                match hex(bytes, 4*found+2, 2) {
+                    Ok(b) => utf8[found] = b as u8,
+                    Err(_) => break,
+                }
+                found += 1;
+            }
+            let (u8c, len) = Utf8Char::from_slice_start(&utf8[..found])?;
+            Ok((u8c, 4*len))
+        },
+    }
+}
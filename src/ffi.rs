@@ -0,0 +1,367 @@
+/* Copyright 2018 Torbjørn Birch Moltu
+ *
+ * Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+ * http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+ * http://opensource.org/licenses/MIT>, at your option. This file may not be
+ * copied, modified, or distributed except according to those terms.
+ */
+
+//! NUL-terminated UTF-8, UTF-16 and UTF-32 strings for passing to and from C.
+//!
+//! `wchar_t` is 16 bits and UTF-16 on Windows but 32 bits on most other
+//! platforms, so `WideCString` and `WideCStr` are aliases for whichever
+//! matches the target.
+//! Unlike `std::ffi::CString`, `U8CString` and `U8CStr` are available
+//! without `std` and report where invalid UTF-8 is.
+//!
+//! The owned types can only be created from strings without NULs, and always
+//! end with a single NUL that is not part of `as_slice()`.
+//! The borrowed types make no promise about the units being valid UTF-16 or
+//! codepoints, as they are usually created from pointers received from C;
+//! that is checked when converting them to a `String`.
+//!
+//! # Examples
+//!
+//! ```
+//! use encode_unicode::ffi::{U16CString, U16CStr};
+//! use encode_unicode::error::InvalidCString;
+//!
+//! let title: U16CString = "Blåbær 💣".parse().unwrap();
+//! assert_eq!(title.len(), 9);
+//! assert_eq!(title.as_slice_with_nul().last(), Some(&0));
+//! // pretend that C returned it
+//! let returned = unsafe{ U16CStr::from_ptr(title.as_ptr(), 100) }.unwrap();
+//! assert_eq!(returned.to_string(), Ok(String::from("Blåbær 💣")));
+//!
+//! assert_eq!("a\0b".parse::<U16CString>(), Err(InvalidCString::InteriorNul(1)));
+//! ```
+
+use utf8_char::Utf8Char;
+use utf16_char::Utf16Char;
+use traits::{CharExt, StrExt};
+use errors::InvalidCString;
+use errors::InvalidCString::*;
+extern crate core;
+use self::core::borrow::Borrow;
+use self::core::ops::Deref;
+use self::core::{fmt, slice, str};
+extern crate alloc;
+use self::alloc::borrow::ToOwned;
+use self::alloc::string::String;
+use self::alloc::vec::Vec;
+
+
+macro_rules! c_string {(
+    $unit:ty,
+    $(#[$owned_doc:meta])* owned $owned:ident,
+    $(#[$borrowed_doc:meta])* borrowed $borrowed:ident
+) => {
+    $(#[$owned_doc])*
+    #[derive(Clone, PartialEq,Eq, PartialOrd,Ord, Hash)]
+    pub struct $owned {
+        /// Includes the NUL.
+        units: Vec<$unit>,
+    }
+    impl $owned {
+        /// Append a NUL to units that don't contain one.
+        ///
+        /// The units are not checked for being valid,
+        /// and the position in the error is a unit index.
+        pub fn from_vec(mut vec: Vec<$unit>) -> Result<Self,InvalidCString> {
+            if let Some(nul) = vec.iter().position(|&unit| unit == 0 ) {
+                return Err(InteriorNul(nul));
+            }
+            vec.push(0);
+            Ok($owned{ units: vec })
+        }
+        /// Get the units without the NUL.
+        pub fn into_vec(mut self) -> Vec<$unit> {
+            self.units.pop();
+            self.units
+        }
+        /// Get the units including the NUL.
+        pub fn into_vec_with_nul(self) -> Vec<$unit> {
+            self.units
+        }
+    }
+    impl Deref for $owned {
+        type Target = $borrowed;
+        fn deref(&self) -> &$borrowed {
+            unsafe{ $borrowed::from_slice_with_nul_unchecked(&self.units) }
+        }
+    }
+    impl Borrow<$borrowed> for $owned {
+        fn borrow(&self) -> &$borrowed {
+            self
+        }
+    }
+    impl AsRef<$borrowed> for $owned {
+        fn as_ref(&self) -> &$borrowed {
+            self
+        }
+    }
+    impl fmt::Debug for $owned {
+        fn fmt(&self,  fmtr: &mut fmt::Formatter) -> fmt::Result {
+            fmt::Debug::fmt(&**self, fmtr)
+        }
+    }
+
+    $(#[$borrowed_doc])*
+    #[derive(PartialEq,Eq, PartialOrd,Ord, Hash)]
+    #[repr(transparent)]
+    pub struct $borrowed {
+        /// Includes the NUL.
+        units: [$unit],
+    }
+    impl $borrowed {
+        /// Borrow a string that C has written at a pointer.
+        ///
+        /// At most `max_len` units are read while looking for the NUL,
+        /// which must be among them.
+        ///
+        /// # Safety
+        ///
+        /// `ptr` must be valid for reads up to and including the NUL or
+        /// `max_len` units, whichever comes first, and the string must not
+        /// be modified while the reference lives.
+        pub unsafe fn from_ptr<'a>(ptr: *const $unit,  max_len: usize) -> Result<&'a Self,InvalidCString> {
+            let mut len = 0;
+            while len < max_len {
+                if *ptr.offset(len as isize) == 0 {
+                    let with_nul = slice::from_raw_parts(ptr, len+1);
+                    return Ok(Self::from_slice_with_nul_unchecked(with_nul));
+                }
+                len += 1;
+            }
+            Err(MissingNul(max_len))
+        }
+        /// Borrow units that end with the only NUL among them.
+        pub fn from_slice_with_nul(units: &[$unit]) -> Result<&Self,InvalidCString> {
+            match units.iter().position(|&unit| unit == 0 ) {
+                Some(nul) if nul+1 == units.len() => {
+                    Ok(unsafe{ Self::from_slice_with_nul_unchecked(units) })
+                },
+                Some(nul) => Err(InteriorNul(nul)),
+                None => Err(MissingNul(units.len())),
+            }
+        }
+        /// Borrow units without checking that they end with the only NUL.
+        ///
+        /// # Safety
+        ///
+        /// The last unit must be a NUL, and the only one.
+        pub unsafe fn from_slice_with_nul_unchecked(units: &[$unit]) -> &Self {
+            &*(units as *const [$unit] as *const Self)
+        }
+        /// A pointer to the first unit, for passing to C.
+        pub fn as_ptr(&self) -> *const $unit {
+            self.units.as_ptr()
+        }
+        /// The units without the NUL.
+        pub fn as_slice(&self) -> &[$unit] {
+            &self.units[..self.units.len()-1]
+        }
+        /// The units including the NUL.
+        pub fn as_slice_with_nul(&self) -> &[$unit] {
+            &self.units
+        }
+        /// The number of units before the NUL.
+        pub fn len(&self) -> usize {
+            self.units.len() - 1
+        }
+        /// Whether the NUL is the only unit.
+        pub fn is_empty(&self) -> bool {
+            self.units.len() == 1
+        }
+    }
+    impl ToOwned for $borrowed {
+        type Owned = $owned;
+        fn to_owned(&self) -> $owned {
+            $owned{ units: self.units.to_owned() }
+        }
+    }
+    impl AsRef<[$unit]> for $borrowed {
+        fn as_ref(&self) -> &[$unit] {
+            self.as_slice()
+        }
+    }
+    impl fmt::Debug for $borrowed {
+        fn fmt(&self,  fmtr: &mut fmt::Formatter) -> fmt::Result {
+            fmt::Debug::fmt(self.as_slice(), fmtr)
+        }
+    }
+}}
+
+
+c_string!{u8,
+    /// An owned NUL-terminated string of bytes.
+    ///
+    /// It's UTF-8 when created from a `str`, but `from_vec()` accepts any bytes,
+    /// so they are only checked by `to_str()` and `to_string()`.
+    owned U8CString,
+    /// A borrowed NUL-terminated string of bytes, which might not be valid UTF-8.
+    borrowed U8CStr
+}
+impl str::FromStr for U8CString {
+    type Err = InvalidCString;
+    /// Copy a `str` and append a NUL.
+    ///
+    /// The position in the error is the byte index of the NUL in the `str`.
+    fn from_str(s: &str) -> Result<Self,InvalidCString> {
+        if let Some(nul) = s.find('\0') {
+            return Err(InteriorNul(nul));
+        }
+        let mut utf8 = Vec::with_capacity(s.len()+1);
+        utf8.extend_from_slice(s.as_bytes());
+        utf8.push(0);
+        Ok(U8CString{ units: utf8 })
+    }
+}
+impl U8CStr {
+    /// Borrow the bytes before the NUL as a `str`.
+    ///
+    /// The position in the error is the index of the offending byte.
+    pub fn to_str(&self) -> Result<&str,InvalidCString> {
+        let bytes = self.as_slice();
+        let mut i = 0;
+        while i < bytes.len() {
+            match Utf8Char::from_slice_start(&bytes[i..]) {
+                Ok((_, len)) => i += len,
+                Err(e) => return Err(Utf8(i, e)),
+            }
+        }
+        Ok(unsafe{ str::from_utf8_unchecked(bytes) })
+    }
+    /// Copy the bytes before the NUL into a `String`.
+    ///
+    /// The position in the error is the index of the offending byte.
+    pub fn to_string(&self) -> Result<String,InvalidCString> {
+        self.to_str().map(String::from)
+    }
+    /// Copy the bytes before the NUL into a `String`,
+    /// replacing invalid sequences with U+FFFD.
+    pub fn to_string_lossy(&self) -> String {
+        String::from_utf8_lossy(self.as_slice()).into_owned()
+    }
+}
+
+c_string!{u16,
+    /// An owned NUL-terminated UTF-16 string.
+    owned U16CString,
+    /// A borrowed NUL-terminated string of `u16`s, which might not be valid UTF-16.
+    borrowed U16CStr
+}
+impl str::FromStr for U16CString {
+    type Err = InvalidCString;
+    /// Encode a `str` as UTF-16 and append a NUL.
+    ///
+    /// The position in the error is the byte index of the NUL in the `str`.
+    fn from_str(s: &str) -> Result<Self,InvalidCString> {
+        if let Some(nul) = s.find('\0') {
+            return Err(InteriorNul(nul));
+        }
+        let mut utf16 = Vec::with_capacity(s.len()+1);
+        for u16c in s.utf16chars() {
+            utf16.extend(u16c);
+        }
+        utf16.push(0);
+        Ok(U16CString{ units: utf16 })
+    }
+}
+impl U16CStr {
+    /// Decode the units before the NUL.
+    ///
+    /// The position in the error is the index of the offending unit.
+    pub fn to_string(&self) -> Result<String,InvalidCString> {
+        let units = self.as_slice();
+        let mut string = String::with_capacity(units.len());
+        let mut i = 0;
+        while i < units.len() {
+            match Utf16Char::from_slice_start(&units[i..]) {
+                Ok((u16c, len)) => {
+                    string.push(u16c.to_char());
+                    i += len;
+                },
+                Err(e) => return Err(Utf16(i, e)),
+            }
+        }
+        Ok(string)
+    }
+    /// Decode the units before the NUL,
+    /// replacing unpaired surrogates with U+FFFD.
+    pub fn to_string_lossy(&self) -> String {
+        let units = self.as_slice();
+        let mut string = String::with_capacity(units.len());
+        let mut i = 0;
+        while i < units.len() {
+            match Utf16Char::from_slice_start(&units[i..]) {
+                Ok((u16c, len)) => {
+                    string.push(u16c.to_char());
+                    i += len;
+                },
+                Err(_) => {
+                    string.push('\u{fffd}');
+                    i += 1;
+                },
+            }
+        }
+        string
+    }
+}
+
+c_string!{u32,
+    /// An owned NUL-terminated string of codepoints.
+    owned U32CString,
+    /// A borrowed NUL-terminated string of `u32`s, which might not all be codepoints.
+    borrowed U32CStr
+}
+impl str::FromStr for U32CString {
+    type Err = InvalidCString;
+    /// Store the codepoints of a `str` and append a NUL.
+    ///
+    /// The position in the error is the byte index of the NUL in the `str`.
+    fn from_str(s: &str) -> Result<Self,InvalidCString> {
+        if let Some(nul) = s.find('\0') {
+            return Err(InteriorNul(nul));
+        }
+        let mut utf32: Vec<u32> = s.chars().map(|c| c as u32 ).collect();
+        utf32.push(0);
+        Ok(U32CString{ units: utf32 })
+    }
+}
+impl U32CStr {
+    /// Decode the units before the NUL.
+    ///
+    /// The position in the error is the index of the offending unit.
+    pub fn to_string(&self) -> Result<String,InvalidCString> {
+        let mut string = String::with_capacity(self.len());
+        for (i, &unit) in self.as_slice().iter().enumerate() {
+            match char::from_u32_detailed(unit) {
+                Ok(c) => string.push(c),
+                Err(e) => return Err(Codepoint(i, e)),
+            }
+        }
+        Ok(string)
+    }
+    /// Decode the units before the NUL,
+    /// replacing invalid codepoints with U+FFFD.
+    pub fn to_string_lossy(&self) -> String {
+        self.as_slice().iter()
+            .map(|&unit| char::from_u32_detailed(unit).unwrap_or('\u{fffd}') )
+            .collect()
+    }
+}
+
+
+/// A NUL-terminated string of `wchar_t`, which is UTF-16 on Windows.
+#[cfg(windows)]
+pub type WideCString = U16CString;
+/// A borrowed NUL-terminated string of `wchar_t`, which is UTF-16 on Windows.
+#[cfg(windows)]
+pub type WideCStr = U16CStr;
+/// A NUL-terminated string of `wchar_t`, which is UTF-32 outside Windows.
+#[cfg(not(windows))]
+pub type WideCString = U32CString;
+/// A borrowed NUL-terminated string of `wchar_t`, which is UTF-32 outside Windows.
+#[cfg(not(windows))]
+pub type WideCStr = U32CStr;
@@ -0,0 +1,254 @@
+/* Copyright 2018 Torbjørn Birch Moltu
+ *
+ * Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+ * http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+ * http://opensource.org/licenses/MIT>, at your option. This file may not be
+ * copied, modified, or distributed except according to those terms.
+ */
+
+use utf16_char::Utf16Char;
+extern crate core;
+use self::core::fmt;
+
+
+// Not a valid character, is the escape to the extension table.
+const ESC: u8 = 0x1b;
+
+/// The GSM 03.38 default alphabet, indexed by septet.
+static DEFAULT_ALPHABET: [char; 128] = [
+    '@', '£', '$', '¥', 'è', 'é', 'ù', 'ì', 'ò', 'Ç', '\n','Ø', 'ø', '\r','Å', 'å',
+    'Δ', '_', 'Φ', 'Γ', 'Λ', 'Ω', 'Π', 'Ψ', 'Σ', 'Θ', 'Ξ', '\u{1b}','Æ','æ','ß', 'É',
+    ' ', '!', '"', '#', '¤', '%', '&', '\'','(', ')', '*', '+', ',', '-', '.', '/',
+    '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', ':', ';', '<', '=', '>', '?',
+    '¡', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O',
+    'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z', 'Ä', 'Ö', 'Ñ', 'Ü', '§',
+    '¿', 'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o',
+    'p', 'q', 'r', 's', 't', 'u', 'v', 'w', 'x', 'y', 'z', 'ä', 'ö', 'ñ', 'ü', 'à',
+];
+
+/// The characters of the default extension table, which are prefixed by ESC.
+static EXTENSION_TABLE: [(u8,char); 10] = [
+    (0x0a, '\u{c}'), (0x14, '^'), (0x28, '{'), (0x29, '}'), (0x2f, '\\'),
+    (0x3c, '['), (0x3d, '~'), (0x3e, ']'), (0x40, '|'), (0x65, '€'),
+];
+
+
+/// Encode a `char` in the GSM 03.38 default alphabet.
+///
+/// Characters in the extension table are returned as an escape septet
+/// followed by the second septet.
+/// Returns `None` if the character isn't in the alphabet.
+///
+/// # Examples
+///
+/// ```
+/// use encode_unicode::sms::to_gsm7;
+///
+/// assert_eq!(to_gsm7('A'), Some((0x41, None)));
+/// assert_eq!(to_gsm7('@'), Some((0x00, None)));
+/// assert_eq!(to_gsm7('€'), Some((0x1b, Some(0x65))));
+/// assert_eq!(to_gsm7('💣'), None);
+/// ```
+pub fn to_gsm7(c: char) -> Option<(u8,Option<u8>)> {
+    // letters, digits and most punctuation have the same value as in ASCII
+    // (setting 0x20 makes uppercase ASCII letters lowercase)
+    let same_as_ascii = ((c as u32).wrapping_sub(' ' as u32) < 32  &&  c != '$')
+                     || (c as u32 | 0x20).wrapping_sub('a' as u32) < 26;
+    if same_as_ascii {
+        Some((c as u8, None))
+    } else if let Some(septet) = DEFAULT_ALPHABET.iter().position(|&d| d == c ) {
+        match septet as u8 {
+            ESC => None,
+            septet => Some((septet, None)),
+        }
+    } else {
+        EXTENSION_TABLE.iter()
+            .find(|&&(_,e)| e == c )
+            .map(|&(septet,_)| (ESC, Some(septet)) )
+    }
+}
+
+/// Decode a septet of the GSM 03.38 default alphabet.
+///
+/// Returns `None` for the escape to the extension table (0x1b),
+/// and for values that don't fit in seven bits.
+pub fn from_gsm7(septet: u8) -> Option<char> {
+    match DEFAULT_ALPHABET.get(septet as usize) {
+        Some(_) if septet == ESC => None,
+        Some(&c) => Some(c),
+        None => None,
+    }
+}
+
+/// Decode the septet that follows an escape, using the default extension
+/// table.
+///
+/// Returns `None` for values that aren't in the table.
+pub fn from_gsm7_extension(septet: u8) -> Option<char> {
+    EXTENSION_TABLE.iter()
+        .find(|&&(s,_)| s == septet )
+        .map(|&(_,c)| c )
+}
+
+
+/// How an SMS must be encoded.
+#[derive(Clone,Copy, Debug, PartialEq,Eq, Hash)]
+pub enum SmsEncoding {
+    /// The GSM 03.38 default alphabet with the extension table,
+    /// where each character is one or two septets.
+    Gsm7,
+    /// UCS-2, which phones decode as UTF-16.
+    /// Characters outside the Basic Multilingual Plane need two units.
+    Ucs2,
+}
+impl SmsEncoding {
+    /// Get the encoding that an SMS containing `text` must use.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use encode_unicode::sms::SmsEncoding;
+    ///
+    /// assert_eq!(SmsEncoding::for_text("Price: 5€ {approx}"), SmsEncoding::Gsm7);
+    /// assert_eq!(SmsEncoding::for_text("Pris: 5 kr 😀"), SmsEncoding::Ucs2);
+    /// ```
+    pub fn for_text(text: &str) -> Self {
+        if text.chars().all(|c| to_gsm7(c).is_some() ) {
+            SmsEncoding::Gsm7
+        } else {
+            SmsEncoding::Ucs2
+        }
+    }
+    /// The number of septets or UTF-16 units `c` needs in this encoding.
+    ///
+    /// Returns `None` if the character cannot be encoded as `Gsm7`.
+    pub fn char_len(self,  c: char) -> Option<usize> {
+        match self {
+            SmsEncoding::Gsm7 => to_gsm7(c).map(|(_,second)| 1 + second.map_or(0, |_| 1 ) ),
+            SmsEncoding::Ucs2 => Some(Utf16Char::from(c).len()),
+        }
+    }
+    /// The number of septets or UTF-16 units `text` needs in this encoding.
+    ///
+    /// Returns `None` if it cannot be encoded as `Gsm7`.
+    pub fn text_len(self,  text: &str) -> Option<usize> {
+        let mut len = 0;
+        for c in text.chars() {
+            match self.char_len(c) {
+                Some(char_len) => len += char_len,
+                None => return None,
+            }
+        }
+        Some(len)
+    }
+    /// How many septets or units fit in a message that isn't split. (160 or 70)
+    pub fn single_capacity(self) -> usize {
+        match self {
+            SmsEncoding::Gsm7 => 160,
+            SmsEncoding::Ucs2 => 70,
+        }
+    }
+    /// How many septets or units fit in each part of a concatenated message,
+    /// after room is made for the user data header. (153 or 67)
+    pub fn part_capacity(self) -> usize {
+        match self {
+            SmsEncoding::Gsm7 => 153,
+            SmsEncoding::Ucs2 => 67,
+        }
+    }
+}
+
+
+/// Split a text into the parts of a concatenated SMS.
+///
+/// The encoding is chosen with `SmsEncoding::for_text()`.
+/// Texts that fit in a single message are not split, and are returned as
+/// a single part even if empty.
+/// The text is never split inside a surrogate pair or escape sequence.
+///
+/// # Examples
+///
+/// ```
+/// use encode_unicode::sms::{segments, SmsEncoding};
+///
+/// let short = segments("Hello");
+/// assert_eq!(short.encoding(), SmsEncoding::Gsm7);
+/// assert_eq!(short.collect::<Vec<_>>(), ["Hello"]);
+///
+/// let long = "💣".repeat(40);
+/// let parts = segments(&long);
+/// assert_eq!(parts.encoding(), SmsEncoding::Ucs2);
+/// // 80 units doesn't fit in 70, and 67 is odd so only 33 pairs fit in each part.
+/// assert_eq!(parts.map(|part| part.chars().count() ).collect::<Vec<_>>(), [33, 7]);
+/// ```
+pub fn segments<'a>(text: &'a str) -> SmsSegments<'a> {
+    let chosen = SmsEncoding::for_text(text);
+    let len = chosen.text_len(text).unwrap();
+    let per_part = if len <= chosen.single_capacity() {
+        chosen.single_capacity()
+    } else {
+        chosen.part_capacity()
+    };
+    SmsSegments{ remaining: text,  encoding: chosen,  capacity: per_part,  started: false }
+}
+
+/// The iterator type returned by `segments()`
+///
+/// See its documentation for details.
+#[derive(Clone)]
+pub struct SmsSegments<'a> {
+    remaining: &'a str,
+    encoding: SmsEncoding,
+    capacity: usize,
+    started: bool,
+}
+impl<'a> SmsSegments<'a> {
+    /// The encoding all parts must be sent with.
+    pub fn encoding(&self) -> SmsEncoding {
+        self.encoding
+    }
+    /// Extract the part of the text that hasn't been returned yet.
+    pub fn as_str(&self) -> &'a str {
+        self.remaining
+    }
+}
+impl<'a> Iterator for SmsSegments<'a> {
+    type Item = &'a str;
+    fn next(&mut self) -> Option<&'a str> {
+        if self.remaining.is_empty() && self.started {
+            return None;
+        }
+        self.started = true;
+        let mut used = 0;
+        let mut end = self.remaining.len();
+        for (i,c) in self.remaining.char_indices() {
+            used += self.encoding.char_len(c).unwrap();
+            if used > self.capacity {
+                end = i;
+                break;
+            }
+        }
+        let (part, rest) = self.remaining.split_at(end);
+        self.remaining = rest;
+        Some(part)
+    }
+    fn size_hint(&self) -> (usize,Option<usize>) {
+        let bytes = self.remaining.len();
+        if bytes == 0 {
+            let left = if self.started {0} else {1};
+            (left, Some(left))
+        } else {
+            // A byte can become at most two septets (for example '{'),
+            // and a part might have room for one more that didn't fit.
+            (1,  Some((2*bytes + self.capacity-2) / (self.capacity-1)))
+        }
+    }
+}
+impl<'a> fmt::Debug for SmsSegments<'a> {
+    fn fmt(&self,  fmtr: &mut fmt::Formatter) -> fmt::Result {
+        fmtr.debug_struct("SmsSegments")
+            .field("encoding", &self.encoding)
+            .field("remaining", &self.remaining)
+            .finish()
+    }
+}
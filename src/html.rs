@@ -0,0 +1,198 @@
+/* Copyright 2018 Torbjørn Birch Moltu
+ *
+ * Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+ * http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+ * http://opensource.org/licenses/MIT>, at your option. This file may not be
+ * copied, modified, or distributed except according to those terms.
+ */
+
+//! HTML and XML character references:
+//! numeric ones such as `&#233;` and `&#xe9;`,
+//! and the named ones of HTML5 such as `&eacute;`.
+//!
+//! Numeric references must end with a `;` and encode a valid codepoint,
+//! which means that surrogates and values above 0x10ffff are rejected
+//! instead of being replaced with U+FFFD as HTML5 parsers do.
+//!
+//! # Examples
+//!
+//! ```
+//! use encode_unicode::html;
+//!
+//! assert_eq!(html::escape("<b>Åse & Øyvind</b>", |c| (c as u32) < 0x80 ),
+//!            "&lt;b&gt;&#xc5;se &amp; &#xd8;yvind&lt;/b&gt;");
+//! assert_eq!(html::unescape("&lt;b&gt;&#197;se &amp &Oslash;yvind").unwrap(),
+//!            "<b>Åse & Øyvind");
+//! ```
+
+use entities::ENTITIES;
+use traits::CharExt;
+use errors::InvalidCharRef;
+use errors::InvalidCharRef::*;
+extern crate core;
+use self::core::fmt::Write;
+use self::core::cmp;
+extern crate alloc;
+use self::alloc::string::String;
+
+
+/// Escape the characters that have a meaning in markup,
+/// and the characters that are not in the repertoire of the output.
+///
+/// `&`, `<`, `>`, `"` and `'` are always escaped,
+/// so that the result can be used both as text and in attribute values.
+/// Characters for which `in_repertoire` returns `false` are written as
+/// hexadecimal references.
+///
+/// # Examples
+///
+/// ```
+/// use encode_unicode::{html, CodePage};
+///
+/// let latin1 = html::escape("\"€10\" ≠ '£9'", |c| (c as u32) < 0x100 );
+/// assert_eq!(latin1, "&quot;&#x20ac;10&quot; &#x2260; &#39;£9&#39;");
+/// let windows1252 = html::escape("€10 ≠ £9", |c| CodePage::Windows1252.encode(c).is_ok() );
+/// assert_eq!(windows1252, "€10 &#x2260; £9");
+/// ```
+pub fn escape<F:FnMut(char)->bool>(s: &str,  mut in_repertoire: F) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ if in_repertoire(c) => escaped.push(c),
+            _ => write!(escaped, "&#x{:x};", c as u32).unwrap(),
+        }
+    }
+    escaped
+}
+
+
+/// Look up a named character reference of HTML5.
+///
+/// The name is without the `&` and `;`, and is case-sensitive.
+/// Some references stand for two characters.
+///
+/// # Examples
+///
+/// ```
+/// use encode_unicode::html;
+///
+/// assert_eq!(html::entity("eacute"), Some("é"));
+/// assert_eq!(html::entity("Eacute"), Some("É"));
+/// assert_eq!(html::entity("NotEqualTilde"), Some("\u{2242}\u{338}"));
+/// assert_eq!(html::entity("eacute;"), None);
+/// ```
+pub fn entity(name: &str) -> Option<&'static str> {
+    match ENTITIES.binary_search_by(|&(entity, _, _)| entity.cmp(name) ) {
+        Ok(i) => Some(ENTITIES[i].1),
+        Err(_) => None,
+    }
+}
+
+/// The longest named reference at the start of `name`
+/// that may be used without a `;`.
+fn legacy_entity(name: &str) -> Option<(usize, &'static str)> {
+    // none are longer than six characters
+    (2..cmp::min(name.len(), 6)+1).rev()
+        .filter_map(|len| match ENTITIES.binary_search_by(|&(entity, _, _)| entity.cmp(&name[..len]) ) {
+            Ok(i) if ENTITIES[i].2 => Some((len, ENTITIES[i].1)),
+            _ => None,
+        })
+        .next()
+}
+
+/// Parse the digits of a numeric reference that starts at `start`,
+/// and return the value and the index after the `;`.
+fn numeric(s: &str,  start: usize) -> Result<(char,usize),InvalidCharRef> {
+    let bytes = s.as_bytes();
+    let (radix, mut i) = match bytes.get(start+2) {
+        Some(&b'x') | Some(&b'X') => (16, start+3),
+        _ => (10, start+2),
+    };
+    let digits = i;
+    let mut value = 0u32;
+    while let Some(digit) = bytes.get(i).and_then(|&b| (b as char).to_digit(radix) ) {
+        // saturate so that huge values are reported as too high
+        value = value.saturating_mul(radix).saturating_add(digit);
+        i += 1;
+    }
+    if i == digits  ||  bytes.get(i) != Some(&b';') {
+        return Err(Malformed(start));
+    }
+    match char::from_u32_detailed(value) {
+        Ok(c) => Ok((c, i+1)),
+        Err(e) => Err(Codepoint(start, e)),
+    }
+}
+
+/// Replace numeric and named character references.
+///
+/// An `&` that doesn't start a reference is left as it is,
+/// as are names that aren't followed by `;` and don't start with one of
+/// the references HTML5 recognizes without it.
+///
+/// # Errors
+///
+/// Fails if a numeric reference has no digits, doesn't end with a `;`,
+/// or isn't a valid codepoint, and if a name followed by `;` is unknown.
+/// The position in the error is the byte index of the `&`.
+///
+/// # Examples
+///
+/// ```
+/// use encode_unicode::html;
+/// use encode_unicode::error::InvalidCharRef::*;
+/// use encode_unicode::error::InvalidCodepoint::*;
+///
+/// assert_eq!(html::unescape("&#x1F4A3; &#128163; &#X1f4a3;").unwrap(), "💣 💣 💣");
+/// assert_eq!(html::unescape("fish &chips; & &copy2018").unwrap_err(), UnknownEntity(5));
+/// assert_eq!(html::unescape("fish & chips &copy2018").unwrap(), "fish & chips ©2018");
+/// assert_eq!(html::unescape("&#xd800;"), Err(Codepoint(0, Utf16Reserved)));
+/// assert_eq!(html::unescape("&#1114112;"), Err(Codepoint(0, TooHigh)));
+/// assert_eq!(html::unescape("&#65"), Err(Malformed(0)));
+/// assert_eq!(html::unescape("&#x;"), Err(Malformed(0)));
+/// ```
+pub fn unescape(s: &str) -> Result<String,InvalidCharRef> {
+    let bytes = s.as_bytes();
+    let mut unescaped = String::with_capacity(s.len());
+    let mut copy_from = 0;
+    let mut i = 0;
+    while let Some(amp) = s[i..].find('&').map(|amp| amp+i ) {
+        i = amp + 1;
+        if bytes.get(i) == Some(&b'#') {
+            let (c, end) = numeric(s, amp)?;
+            unescaped.push_str(&s[copy_from..amp]);
+            unescaped.push(c);
+            i = end;
+        } else {
+            let name_len = bytes[i..].iter()
+                .take_while(|&&b| (b | 0x20).wrapping_sub(b'a') < 26  ||  b.wrapping_sub(b'0') < 10 )
+                .count();
+            let name = &s[i..i+name_len];
+            let (len, value) = if bytes.get(i+name_len) == Some(&b';')  &&  name_len != 0 {
+                match entity(name) {
+                    Some(value) => (name_len+1, value),
+                    None => match legacy_entity(name) {
+                        Some(legacy) => legacy,
+                        None => return Err(UnknownEntity(amp)),
+                    },
+                }
+            } else {
+                match legacy_entity(name) {
+                    Some(legacy) => legacy,
+                    None => continue,
+                }
+            };
+            unescaped.push_str(&s[copy_from..amp]);
+            unescaped.push_str(value);
+            i += len;
+        }
+        copy_from = i;
+    }
+    unescaped.push_str(&s[copy_from..]);
+    Ok(unescaped)
+}
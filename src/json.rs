@@ -0,0 +1,163 @@
+/* Copyright 2018 Torbjørn Birch Moltu
+ *
+ * Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+ * http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+ * http://opensource.org/licenses/MIT>, at your option. This file may not be
+ * copied, modified, or distributed except according to those terms.
+ */
+
+//! Escaping and unescaping the contents of JSON strings
+//! ([RFC 8259 section 7](https://tools.ietf.org/html/rfc8259#section-7)).
+//!
+//! JSON escapes characters outside the Basic Multilingual Plane as a pair
+//! of `\uXXXX` escapes of their UTF-16 surrogates, so the pair must be
+//! joined when unescaping, and a surrogate escape that isn't part of a pair
+//! is an error.
+//!
+//! The functions only deal with what is between the quotes.
+//!
+//! # Examples
+//!
+//! ```
+//! use encode_unicode::json;
+//!
+//! assert_eq!(json::escape("\"quoted\"\ttab"), "\\\"quoted\\\"\\ttab");
+//! assert_eq!(json::escape_non_ascii("Ünïcødé 💣"), "\\u00dcn\\u00efc\\u00f8d\\u00e9 \\ud83d\\udca3");
+//! assert_eq!(json::unescape("\\ud83d\\udca3 \\u00e6\\/").unwrap(), "💣 æ/");
+//! ```
+
+use utf16_char::Utf16Char;
+use traits::StrExt;
+use errors::InvalidJsonString;
+use errors::InvalidJsonString::*;
+extern crate core;
+use self::core::fmt::Write;
+extern crate alloc;
+use self::alloc::string::String;
+
+
+fn escape_where(s: &str,  non_ascii: bool) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for u16c in s.utf16chars() {
+        let (first, second) = u16c.to_tuple();
+        match first {
+            0x22 => escaped.push_str("\\\""),
+            0x5c => escaped.push_str("\\\\"),
+            0x08 => escaped.push_str("\\b"),
+            0x0c => escaped.push_str("\\f"),
+            0x0a => escaped.push_str("\\n"),
+            0x0d => escaped.push_str("\\r"),
+            0x09 => escaped.push_str("\\t"),
+            _ if first < 0x20  ||  (non_ascii  &&  first >= 0x80) => {
+                for unit in Some(first).into_iter().chain(second) {
+                    write!(escaped, "\\u{:04x}", unit).unwrap();
+                }
+            },
+            _ => escaped.push(u16c.to_char()),
+        }
+    }
+    escaped
+}
+
+/// Escape quotes, backslashes and control characters.
+///
+/// The common control characters use their short escapes such as `\n`,
+/// while the others use `\u00XX`.
+/// Other characters are unchanged.
+pub fn escape(s: &str) -> String {
+    escape_where(s, false)
+}
+
+/// Escape quotes, backslashes, control characters and all non-ASCII
+/// characters, for when the JSON has to be ASCII.
+///
+/// Characters outside the Basic Multilingual Plane become a pair of escapes.
+pub fn escape_non_ascii(s: &str) -> String {
+    escape_where(s, true)
+}
+
+
+/// Parse the four hexadecimal digits of a `\u` escape that starts at `start`.
+fn hex_escape(bytes: &[u8],  start: usize) -> Option<u16> {
+    if bytes.len() < start+6  ||  bytes[start+1] != b'u' {
+        return None;
+    }
+    let mut unit = 0;
+    for &digit in &bytes[start+2..start+6] {
+        match (digit as char).to_digit(16) {
+            Some(value) => unit = unit << 4  |  value as u16,
+            None => return None,
+        }
+    }
+    Some(unit)
+}
+
+/// Replace escape sequences in the contents of a JSON string.
+///
+/// Fails if an escape is invalid, if a quote or control character isn't
+/// escaped, or if the `\u` escapes aren't valid UTF-16.
+/// The position in the error is the byte index of the offending character,
+/// or of the backslash that starts the offending escape.
+///
+/// # Examples
+///
+/// ```
+/// use encode_unicode::json;
+/// use encode_unicode::error::InvalidJsonString::*;
+/// use encode_unicode::error::InvalidUtf16Tuple::*;
+///
+/// assert_eq!(json::unescape("\\u0041\\u00C6\\uD83D\\uDE00"), Ok(String::from("AÆ😀")));
+/// assert_eq!(json::unescape("ab\\ud83d"), Err(Utf16(2, MissingSecond)));
+/// assert_eq!(json::unescape("ab\\ud83d\\u0041"), Err(Utf16(2, InvalidSecond)));
+/// assert_eq!(json::unescape("\\udca3"), Err(Utf16(0, FirstIsTrailingSurrogate)));
+/// assert_eq!(json::unescape("\\x41"), Err(InvalidEscape(0)));
+/// assert_eq!(json::unescape("tab\t"), Err(Unescaped(3)));
+/// ```
+pub fn unescape(s: &str) -> Result<String,InvalidJsonString> {
+    let bytes = s.as_bytes();
+    let mut unescaped = String::with_capacity(s.len());
+    let mut copy_from = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => {},
+            b'"' => return Err(Unescaped(i)),
+            b if b < 0x20 => return Err(Unescaped(i)),
+            _ => {
+                i += 1;
+                continue;
+            },
+        }
+        unescaped.push_str(&s[copy_from..i]);
+        let start = i;
+        let short = match bytes.get(i+1) {
+            Some(&b'"') => Some('"'),
+            Some(&b'\\') => Some('\\'),
+            Some(&b'/') => Some('/'),
+            Some(&b'b') => Some('\u{8}'),
+            Some(&b'f') => Some('\u{c}'),
+            Some(&b'n') => Some('\n'),
+            Some(&b'r') => Some('\r'),
+            Some(&b't') => Some('\t'),
+            Some(&b'u') => None,
+            _ => return Err(InvalidEscape(start)),
+        };
+        if let Some(c) = short {
+            unescaped.push(c);
+            i += 2;
+        } else {
+            let first = hex_escape(bytes, i).ok_or(InvalidEscape(start))?;
+            i += 6;
+            let mut second = None;
+            if first & 0xfc00 == 0xd800  &&  bytes[i..].starts_with(b"\\u") {
+                second = Some(hex_escape(bytes, i).ok_or(InvalidEscape(i))?);
+                i += 6;
+            }
+            let u16c = Utf16Char::from_tuple((first, second)).map_err(|e| Utf16(start, e) )?;
+            unescaped.push(u16c.to_char());
+        }
+        copy_from = i;
+    }
+    unescaped.push_str(&s[copy_from..]);
+    Ok(unescaped)
+}
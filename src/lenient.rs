@@ -0,0 +1,228 @@
+/* Copyright 2018 Torbjørn Birch Moltu
+ *
+ * Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+ * http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+ * http://opensource.org/licenses/MIT>, at your option. This file may not be
+ * copied, modified, or distributed except according to those terms.
+ */
+
+//! Decoding UTF-8 that breaks the rules of RFC 3629 in ways old or broken
+//! encoders are known to.
+//!
+//! Which deviations are accepted is chosen with `Leniency`,
+//! and every accepted deviation is reported to a callback together with the
+//! position of the sequence, so that nothing is silently normalized.
+//! Deviations that aren't accepted are errors, as they are in
+//! `char::from_utf8_slice_start()`.
+//!
+//! As the decoded values might be surrogates or greater than U+10FFFF,
+//! they are produced as `u32`s.
+//!
+//! # Examples
+//!
+#![cfg_attr(feature="std", doc=" ```")]
+#![cfg_attr(not(feature="std"), doc=" ```no_compile")]
+//! use encode_unicode::lenient::{Leniency, Deviation};
+//! use encode_unicode::error::{InvalidUtf8Slice, InvalidUtf8};
+//!
+//! // CESU-8, as written by Java and Oracle databases
+//! let cesu8 = b"\xed\xa0\xbd\xed\xb2\xa3!";
+//! let mut deviations = Vec::new();
+//! let accept = Leniency{ surrogates: true, ..Leniency::default() };
+//! let decoded: Vec<_> = accept.decode(cesu8, |pos, dev| deviations.push((pos, dev)) ).collect();
+//! assert_eq!(decoded, [(0, Ok(0x1f4a3)), (6, Ok(b'!' as u32))]);
+//! assert_eq!(deviations, [(0, Deviation::SurrogatePair)]);
+//!
+//! // overlong sequences are still rejected
+//! let decoded: Vec<_> = accept.decode(b"\xc0\xaf", |_,_| () ).collect();
+//! assert_eq!(decoded[0], (0, Err(InvalidUtf8Slice::Utf8(InvalidUtf8::OverLong))));
+//! ```
+
+use errors::{InvalidUtf8Slice, InvalidCodepoint};
+use errors::InvalidUtf8::*;
+use errors::InvalidUtf8FirstByte::*;
+use errors::InvalidUtf8Slice::*;
+extern crate core;
+use self::core::fmt;
+
+
+/// The smallest value that needs a sequence of the index's length.
+static MINIMUM: [u32; 7] = [0, 0, 0x80, 0x800, 0x1_00_00, 0x20_00_00, 0x4_00_00_00];
+
+/// Which deviations from strict UTF-8 to accept.
+///
+/// The default is to accept nothing, which decodes exactly like
+/// `char::from_utf8_slice_start()`.
+#[derive(Clone,Copy, Debug, Default, PartialEq,Eq, Hash)]
+pub struct Leniency {
+    /// Accept sequences that are longer than necessary,
+    /// such as `[0xc0, 0x80]` for NUL as in Java's modified UTF-8.
+    pub overlong: bool,
+    /// Accept encoded surrogates, and combine pairs of three-byte sequences
+    /// into the codepoint they represent as in CESU-8.
+    pub surrogates: bool,
+    /// Accept codepoints greater than U+10FFFF, and the five- and six-byte
+    /// sequences of RFC 2279 that can encode values up to 0x7fff_ffff.
+    pub beyond_unicode: bool,
+}
+
+/// A deviation from RFC 3629 that was accepted.
+#[derive(Clone,Copy, Debug, PartialEq,Eq, Hash)]
+pub enum Deviation {
+    /// The sequence is longer than necessary.
+    OverLong,
+    /// A surrogate that isn't part of a pair.
+    Surrogate,
+    /// A surrogate pair encoded as two three-byte sequences.
+    SurrogatePair,
+    /// The codepoint is greater than U+10FFFF.
+    TooHigh,
+    /// The sequence is five or six bytes long.
+    LongSequence,
+}
+
+impl Leniency {
+    /// Decode bytes, reporting accepted deviations to `on_deviation`.
+    ///
+    /// The iterator produces the index of each sequence together with the
+    /// decoded value or why it is invalid.
+    /// After an error, decoding continues with the next byte.
+    /// `on_deviation` is called with the index of the sequence before the
+    /// value is returned, once for every deviation it has.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(feature="std", doc=" ```")]
+    #[cfg_attr(not(feature="std"), doc=" ```no_compile")]
+    /// use encode_unicode::lenient::{Leniency, Deviation};
+    /// use encode_unicode::error::{InvalidUtf8Slice, InvalidUtf8, InvalidUtf8FirstByte};
+    ///
+    /// let accept = Leniency{ overlong: true,  surrogates: true,  beyond_unicode: true };
+    /// let mut deviations = Vec::new();
+    /// let decoded: Vec<_> = accept.decode(b"\xc0\x80\xfc\x84\x80\x80\x80\x80\xff",
+    ///                                     |pos, dev| deviations.push((pos, dev)) ).collect();
+    /// assert_eq!(decoded, [
+    ///     (0, Ok(0)),
+    ///     (2, Ok(0x400_0000)),
+    ///     (8, Err(InvalidUtf8Slice::Utf8(InvalidUtf8::FirstByte(InvalidUtf8FirstByte::TooLongSeqence)))),
+    /// ]);
+    /// assert_eq!(deviations, [
+    ///     (0, Deviation::OverLong),
+    ///     (2, Deviation::LongSequence),
+    ///     (2, Deviation::TooHigh),
+    /// ]);
+    /// ```
+    pub fn decode<'a, F:FnMut(usize,Deviation)>
+    (self,  input: &'a [u8],  on_deviation: F) -> LenientDecoder<'a,F> {
+        LenientDecoder{ bytes: input,  pos: 0,  leniency: self,  callback: on_deviation }
+    }
+
+    /// Decode the sequence at the start of `bytes`,
+    /// and return the value and how many bytes were used.
+    fn decode_start<F:FnMut(Deviation)>(self,  bytes: &[u8],  found: &mut F)
+    -> Result<(u32,usize),InvalidUtf8Slice> {
+        let (value, len) = self.decode_sequence(bytes, found)?;
+        if value & 0xffff_fc00 == 0xd800  &&  len == 3  &&  bytes.len() >= 6
+        &&  bytes[3] == 0xed  &&  bytes[4] & 0xf0 == 0xb0  &&  bytes[5] >> 6 == 0b10 {
+            let trail = (bytes[4] as u32 & 0x3f) << 6  |  (bytes[5] as u32 & 0x3f);
+            found(Deviation::SurrogatePair);
+            return Ok((0x1_00_00 + ((value & 0x3ff) << 10 | (trail & 0x3ff)),  6));
+        }
+        if value & 0xffff_f800 == 0xd800 {
+            found(Deviation::Surrogate);
+        }
+        Ok((value, len))
+    }
+
+    /// Decode a single sequence, without combining surrogates.
+    fn decode_sequence<F:FnMut(Deviation)>(self,  bytes: &[u8],  found: &mut F)
+    -> Result<(u32,usize),InvalidUtf8Slice> {
+        let first = match bytes.first() {
+            Some(&first) => first,
+            None => return Err(TooShort(1)),
+        };
+        // the number of leading ones
+        let len = match (!first).leading_zeros() {
+            0 => return Ok((first as u32, 1)),
+            1 => return Err(Utf8(FirstByte(ContinuationByte))),
+            n if n <= 4 => n as usize,
+            n if n <= 6  &&  self.beyond_unicode => n as usize,
+            _ => return Err(Utf8(FirstByte(TooLongSeqence))),
+        };
+        if bytes.len() < len {
+            return Err(TooShort(len));
+        }
+        let mut value = first as u32 & (0x7f >> len);
+        for (i, &b) in bytes[..len].iter().enumerate().skip(1) {
+            if b >> 6 != 0b10 {
+                return Err(Utf8(NotAContinuationByte(i)));
+            }
+            value = value << 6  |  (b & 0x3f) as u32;
+        }
+        if value < MINIMUM[len] {
+            if !self.overlong {
+                return Err(Utf8(OverLong));
+            }
+            found(Deviation::OverLong);
+        }
+        if len > 4 {
+            found(Deviation::LongSequence);
+        }
+        if value > 0x10_ff_ff {
+            if !self.beyond_unicode {
+                return Err(Codepoint(InvalidCodepoint::TooHigh));
+            }
+            found(Deviation::TooHigh);
+        } else if value & 0xffff_f800 == 0xd800  &&  !self.surrogates {
+            return Err(Codepoint(InvalidCodepoint::Utf16Reserved));
+        }
+        Ok((value, len))
+    }
+}
+
+
+/// The iterator type returned by `Leniency::decode()`
+///
+/// See its documentation for details.
+#[derive(Clone)]
+pub struct LenientDecoder<'a, F:FnMut(usize,Deviation)> {
+    bytes: &'a [u8],
+    pos: usize,
+    leniency: Leniency,
+    callback: F,
+}
+impl<'a, F:FnMut(usize,Deviation)> LenientDecoder<'a,F> {
+    /// The bytes that haven't been decoded yet.
+    pub fn as_slice(&self) -> &'a [u8] {
+        self.bytes
+    }
+}
+impl<'a, F:FnMut(usize,Deviation)> Iterator for LenientDecoder<'a,F> {
+    type Item = (usize, Result<u32,InvalidUtf8Slice>);
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.bytes.is_empty() {
+            return None;
+        }
+        let start = self.pos;
+        let callback = &mut self.callback;
+        let (result, len) = match self.leniency.decode_start(self.bytes, &mut |dev| callback(start, dev) ) {
+            Ok((value, len)) => (Ok(value), len),
+            Err(e) => (Err(e), 1),
+        };
+        self.bytes = &self.bytes[len..];
+        self.pos += len;
+        Some((start, result))
+    }
+    fn size_hint(&self) -> (usize,Option<usize>) {
+        (self.bytes.len()/6,  Some(self.bytes.len()))
+    }
+}
+impl<'a, F:FnMut(usize,Deviation)> fmt::Debug for LenientDecoder<'a,F> {
+    fn fmt(&self,  fmtr: &mut fmt::Formatter) -> fmt::Result {
+        fmtr.debug_struct("LenientDecoder")
+            .field("bytes", &self.bytes)
+            .field("position", &self.pos)
+            .field("leniency", &self.leniency)
+            .finish()
+    }
+}
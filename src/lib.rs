@@ -13,17 +13,27 @@ Miscellaneous UTF-8 and UTF-16 types and methods.
 # Optional features:
 * `#![no_std]`-mode: There are a few differences:
   * `AsciiExt` doesn't exist, but `is_ascii()` is made available as an inherent impl.
-  * `Error` doesn't exist, but `description()` is made available as an inherent impl.
-  * `Extend`/`FromIterator`-implementations for `String`/`Vec<u8>`/`Vec<u16>` are missing.
+  * `description()` is also made available as an inherent impl,
+    so that it can be called without importing `core::error::Error`.
+  * `Extend`/`FromIterator`-implementations for `String`/`Vec<u8>`/`Vec<u16>` are missing,
+    and so are the modules and methods that return a `String` or `Vec`,
+    unless the `alloc` feature is enabled.
   * There is no `io`, so `Utf8Iterator` doesn't implement `Read`.
 
   This feature is enabled by setting `default-features=false` in `Cargo.toml`:
   `encode_unicode = {version="0.3", default-features=false}`
+* `alloc`: Everything that needs `String` or `Vec` without `std`,
+  for targets that have an allocator:
+  `encode_unicode = {version="0.3", default-features=false, features=["alloc"]}`.
+  The `std` feature implies it.
 * Integration with the [ascii](https://tomprogrammer.github.io/rust-ascii/ascii/index.html) crate:  
   Convert `Utf8Char` and `Utf16Char` to and from
   [ascii::`AsciiChar`](https://tomprogrammer.github.io/rust-ascii/ascii/enum.AsciiChar.html).
+* A C API: The `capi` feature exports the functions in [`capi`](capi/index.html),
+  which are declared in `include/encode_unicode.h`
+  and can be built as a library by the crate in `capi/`.
 
-The minimum supported version of Rust is 1.15,
+The minimum supported version of Rust is 1.81,
 older versions might work now but can break with a minor update.
 
 [crates.io page](https://crates.io/crates/encode_unicode)  
@@ -52,12 +62,29 @@ mod utf8_char;
 mod utf8_iterators;
 mod utf16_char;
 mod utf16_iterators;
+mod ucs2_char;
+mod gsm;
+mod codepage;
+mod utf8mb3;
+mod escape;
+mod literals;
+mod array_string;
+#[cfg(feature="alloc")]
+mod entities;
 
 pub use traits::{CharExt, U8UtfExt, U16UtfExt, StrExt, IterExt};
 pub use utf8_char::Utf8Char;
 pub use utf16_char::Utf16Char;
 pub use utf8_iterators::{Utf8Iterator, iter_bytes};
 pub use utf16_iterators::{Utf16Iterator, iter_units};
+pub use ucs2_char::{Ucs2Char, Ucs2Substitution};
+pub use utf8mb3::Utf8mb3Replacement;
+pub use codepage::CodePage;
+pub use escape::EscapeStyle;
+pub use errors::DecodeError;
+pub use array_string::{ArrayUtf8String, ArrayUtf16String};
+#[doc(hidden)]
+pub use literals::{utf16_len as __utf16_len, encode_utf16 as __encode_utf16};
 
 pub mod error {// keeping the public interface in one file
     //! Errors returned by various conversion methods in this crate.
@@ -66,10 +93,47 @@ pub mod error {// keeping the public interface in one file
     pub use errors::{InvalidUtf8FirstByte,InvalidUtf16FirstUnit};
     pub use errors::{InvalidUtf8Slice,InvalidUtf16Slice};
     pub use errors::{InvalidUtf8Array,InvalidUtf16Tuple};
+    pub use errors::{NonBmpError,Ucs2EncodeError};
+    pub use errors::{InvalidUtf7, InvalidPunycode, InvalidScsu};
+    pub use errors::Unmappable;
+    pub use errors::{InvalidSurrogateEscape, InvalidJsonString, InvalidCharRef};
+    pub use errors::{InvalidEscape, InvalidNotation, InvalidPercentEncoding, InvalidCString};
+    pub use errors::{DecodeError, CapacityError};
 }
 
+pub mod sms {
+    //! Choosing between the GSM 7-bit alphabet and UCS-2 for SMS,
+    //! and splitting long texts into concatenated messages.
+    pub use gsm::{SmsEncoding, SmsSegments, segments};
+    pub use gsm::{to_gsm7, from_gsm7, from_gsm7_extension};
+}
+
+pub mod scsu;
+pub mod detect;
+pub mod mojibake;
+pub mod surrogateescape;
+pub mod lenient;
+pub mod notation;
+#[cfg(feature="alloc")]
+pub mod utf7;
+#[cfg(feature="alloc")]
+pub mod punycode;
+#[cfg(feature="alloc")]
+pub mod json;
+#[cfg(feature="alloc")]
+pub mod html;
+#[cfg(feature="alloc")]
+pub mod percent;
+#[cfg(feature="alloc")]
+pub mod ffi;
+#[cfg(feature="capi")]
+pub mod capi;
+
 pub mod iterator {
     //! Iterator types that you should rarely need to name
     pub use utf8_iterators::{Utf8Iterator, Utf8CharSplitter, Utf8Chars, Utf8CharIndices};
     pub use utf16_iterators::{Utf16Iterator, Utf16CharSplitter, Utf16Chars, Utf16CharIndices};
+    pub use utf8mb3::Utf8mb3Chars;
+    pub use codepage::{CodePageDecoder, CodePageEncoder};
+    pub use escape::Utf8CharEscape;
 }
@@ -0,0 +1,145 @@
+/* Copyright 2018 Torbjørn Birch Moltu
+ *
+ * Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+ * http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+ * http://opensource.org/licenses/MIT>, at your option. This file may not be
+ * copied, modified, or distributed except according to those terms.
+ */
+
+//! Macros for `Utf8Char`, `Utf16Char` and UTF-16 string literals,
+//! and the `const fn`s they expand to.
+
+
+/// Create an `Utf8Char` from a `char` at compile time.
+///
+/// The result can be stored in a `const` item, which can then be used in
+/// `match` patterns.
+///
+/// # Examples
+///
+/// ```
+/// #[macro_use] extern crate encode_unicode;
+/// use encode_unicode::Utf8Char;
+///
+/// const E_ACUTE: Utf8Char = utf8char!('é');
+/// # fn main() {
+/// assert_eq!(E_ACUTE.as_str(), "é");
+/// assert_eq!(utf8char!('\u{10ffff}').to_array(), ([0xf4, 0x8f, 0xbf, 0xbf], 4));
+/// # }
+/// ```
+#[macro_export]
+macro_rules! utf8char {($c:expr) => {{
+    const UTF8CHAR: $crate::Utf8Char = $crate::Utf8Char::from_char_const($c);
+    UTF8CHAR
+}}}
+
+/// Create an `Utf16Char` from a `char` at compile time.
+///
+/// The result can be stored in a `const` item, which can then be used in
+/// `match` patterns.
+///
+/// # Examples
+///
+/// ```
+/// #[macro_use] extern crate encode_unicode;
+/// use encode_unicode::Utf16Char;
+///
+/// const BOMB: Utf16Char = utf16char!('💣');
+/// # fn main() {
+/// assert_eq!(BOMB.as_ref(), &[0xd83d, 0xdca3]);
+/// assert_eq!(utf16char!('a').as_ref(), &[0x61]);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! utf16char {($c:expr) => {{
+    const UTF16CHAR: $crate::Utf16Char = $crate::Utf16Char::new_const($c);
+    UTF16CHAR
+}}}
+
+/// Encode a string literal or constant as UTF-16 at compile time,
+/// producing a `&'static [u16]`.
+///
+/// Add `nul` after the string to append a NUL unit, as Windows APIs
+/// expect.
+///
+/// # Examples
+///
+/// ```
+/// #[macro_use] extern crate encode_unicode;
+///
+/// const TITLE: &[u16] = utf16!("Blåbær 💣");
+/// # fn main() {
+/// assert_eq!(TITLE, &"Blåbær 💣".encode_utf16().collect::<Vec<u16>>()[..]);
+/// assert_eq!(utf16!("æ", nul), &[0xe6, 0]);
+/// assert_eq!(utf16!(""), &[]);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! utf16 {
+    ($s:expr) => {{
+        const UTF16: [u16; $crate::__utf16_len($s)] = $crate::__encode_utf16($s);
+        &UTF16
+    }};
+    ($s:expr, nul) => {{
+        const UTF16: [u16; $crate::__utf16_len($s)+1] = $crate::__encode_utf16($s);
+        &UTF16
+    }};
+}
+
+
+/// The number of UTF-16 units needed for a `str`.
+#[doc(hidden)]
+pub const fn utf16_len(s: &str) -> usize {
+    let bytes = s.as_bytes();
+    let mut units = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        // count the start of every codepoint, and once more for surrogate pairs
+        if bytes[i] & 0xc0 != 0x80 {
+            units += 1;
+        }
+        if bytes[i] >= 0xf0 {
+            units += 1;
+        }
+        i += 1;
+    }
+    units
+}
+
+/// Encode a `str` as UTF-16 into an array, where any units after the end
+/// are zero.
+#[doc(hidden)]
+pub const fn encode_utf16<const N: usize>(s: &str) -> [u16; N] {
+    let bytes = s.as_bytes();
+    let mut units = [0; N];
+    let mut i = 0;
+    let mut unit = 0;
+    while i < bytes.len() {
+        let first = bytes[i] as u32;
+        let (len, mut c) = if first < 0x80 {
+            (1, first)
+        } else if first < 0xe0 {
+            (2, first & 0x1f)
+        } else if first < 0xf0 {
+            (3, first & 0x0f)
+        } else {
+            (4, first & 0x07)
+        };
+        let mut continuation = 1;
+        while continuation < len {
+            c = c << 6  |  (bytes[i+continuation] & 0x3f) as u32;
+            continuation += 1;
+        }
+        if c < 0x1_00_00 {
+            units[unit] = c as u16;
+            unit += 1;
+        } else {
+            let c = c - 0x1_00_00;
+            units[unit] = 0xd8_00 | (c >> 10) as u16;
+            units[unit+1] = 0xdc_00 | (c & 0x3_ff) as u16;
+            unit += 2;
+        }
+        i += len;
+    }
+    units
+}
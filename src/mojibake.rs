@@ -0,0 +1,111 @@
+/* Copyright 2018 Torbjørn Birch Moltu
+ *
+ * Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+ * http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+ * http://opensource.org/licenses/MIT>, at your option. This file may not be
+ * copied, modified, or distributed except according to those terms.
+ */
+
+//! Detecting and repairing UTF-8 that has been decoded as Windows-1252 or
+//! Latin-1 and then encoded as UTF-8 again, such as "Ã©" instead of "é".
+//!
+//! Every character of a double-encoded string is turned back into the byte it
+//! was decoded from, with Windows-1252, or Latin-1 for the bytes that
+//! Windows-1252 leaves undefined.
+//! The string is only considered double-encoded if those bytes are valid UTF-8
+//! and contain at least one non-ASCII character.
+
+use traits::{CharExt, U8UtfExt};
+use codepage::CodePage;
+#[cfg(feature="alloc")]
+extern crate alloc;
+#[cfg(feature="alloc")]
+use self::alloc::borrow::Cow;
+#[cfg(feature="alloc")]
+use self::alloc::string::String;
+
+
+/// The byte a character was decoded from if it is mojibake.
+fn original_byte(c: char) -> Option<u8> {
+    match CodePage::Windows1252.encode(c) {
+        Ok(b) => Some(b),
+        Err(_) if (c as u32) < 0x100 => Some(c as u8),
+        Err(_) => None,
+    }
+}
+
+/// Pass the characters the original bytes form to `found`,
+/// and return whether the whole string is double-encoded.
+fn reinterpret<F:FnMut(char)>(s: &str,  mut found: F) -> bool {
+    let mut chars = s.chars();
+    let mut non_ascii = false;
+    while let Some(c) = chars.next() {
+        let first = match original_byte(c) {
+            Some(b) => b,
+            None => return false,
+        };
+        let extra = match first.extra_utf8_bytes() {
+            Ok(extra) => extra,
+            Err(_) => return false,
+        };
+        let mut bytes = [first, 0, 0, 0];
+        for b in &mut bytes[1..extra+1] {
+            *b = match chars.next().and_then(original_byte) {
+                Some(b) => b,
+                None => return false,
+            };
+        }
+        match char::from_utf8_slice_start(&bytes[..extra+1]) {
+            Ok((original,_)) => found(original),
+            Err(_) => return false,
+        }
+        non_ascii |= extra != 0;
+    }
+    non_ascii
+}
+
+/// Check whether a string is UTF-8 that has been decoded as Windows-1252 or
+/// Latin-1 and encoded again.
+///
+/// # Examples
+///
+/// ```
+/// use encode_unicode::mojibake::is_double_encoded;
+///
+/// assert!(is_double_encoded("CafÃ©"));
+/// assert!(is_double_encoded("â€œquotedâ€\u{9d}"));
+/// assert!(!is_double_encoded("Café"));
+/// assert!(!is_double_encoded("plain ascii"));
+/// // the second character isn't a continuation byte
+/// assert!(!is_double_encoded("Ã!"));
+/// ```
+pub fn is_double_encoded(s: &str) -> bool {
+    reinterpret(s, |_| () )
+}
+
+/// Reverse double encoding if the whole string is double-encoded.
+///
+/// Strings that are only partially double-encoded, or that are valid
+/// as they are, are returned unchanged.
+/// Text that has been double-encoded several times needs several calls.
+///
+/// # Examples
+///
+/// ```
+/// use encode_unicode::mojibake::fix_double_encoding;
+/// use std::borrow::Cow;
+///
+/// assert_eq!(fix_double_encoding("CafÃ© Ã\u{81}lvarez"), "Café Álvarez");
+/// assert_eq!(fix_double_encoding("â€™"), "’");
+/// assert_eq!(fix_double_encoding("ðŸ’£"), "💣");
+/// assert!(match fix_double_encoding("Ã© and é") {Cow::Borrowed(_) => true, _ => false});
+/// ```
+#[cfg(feature="alloc")]
+pub fn fix_double_encoding<'a>(s: &'a str) -> Cow<'a,str> {
+    let mut fixed = String::with_capacity(s.len());
+    if reinterpret(s, |c| fixed.push(c) ) {
+        Cow::Owned(fixed)
+    } else {
+        Cow::Borrowed(s)
+    }
+}
@@ -0,0 +1,211 @@
+/* Copyright 2018 Torbjørn Birch Moltu
+ *
+ * Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+ * http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+ * http://opensource.org/licenses/MIT>, at your option. This file may not be
+ * copied, modified, or distributed except according to those terms.
+ */
+
+//! The `U+1F4A3` notation for codepoints, and `U+0041..U+005A` for ranges
+//! of them.
+//!
+//! Parsing accepts four to six hexadecimal digits in either case after `U+`
+//! or `u+`, also when wrapped in `\N{` and `}` as in Python and Perl.
+//! Formatting uses uppercase digits, padded with zeroes to at least four.
+//!
+//! # Examples
+//!
+#![cfg_attr(feature="std", doc=" ```")]
+#![cfg_attr(not(feature="std"), doc=" ```no_compile")]
+//! use encode_unicode::{Utf8Char, Utf16Char};
+//! use encode_unicode::notation::{self, Notation, CodepointRange};
+//!
+//! let bomb: Utf8Char = notation::parse("U+1F4A3").unwrap();
+//! assert_eq!(bomb, Utf8Char::from('💣'));
+//! let bomb: Utf16Char = notation::parse("\\N{U+1f4a3}").unwrap();
+//! assert_eq!(bomb, Utf16Char::from('💣'));
+//! assert_eq!(Notation('\n').to_string(), "U+000A");
+//!
+//! let uppercase: CodepointRange = "U+0041..U+005A".parse().unwrap();
+//! assert!(uppercase.contains('Q'));
+//! assert_eq!(uppercase.into_iter().count(), 26);
+//! assert_eq!(uppercase.to_string(), "U+0041..U+005A");
+//! ```
+
+use traits::CharExt;
+use errors::InvalidNotation;
+use errors::InvalidNotation::*;
+extern crate core;
+use self::core::{char, fmt, str};
+
+
+/// Parse the notation at the start of a `str`,
+/// and also return how many bytes it was.
+///
+/// The result can be a `char`, `Utf8Char` or `Utf16Char`.
+///
+/// # Examples
+///
+/// ```
+/// use encode_unicode::notation;
+/// use encode_unicode::error::InvalidNotation::*;
+/// use encode_unicode::error::InvalidCodepoint::*;
+///
+/// assert_eq!(notation::parse_start("U+00E6 LATIN SMALL LETTER AE"), Ok(('æ', 6)));
+/// assert_eq!(notation::parse_start("\\N{u+00e6}s"), Ok(('æ', 10)));
+/// assert_eq!(notation::parse_start::<char>("U+E6"), Err(Malformed));
+/// assert_eq!(notation::parse_start::<char>("U+110000"), Err(Codepoint(TooHigh)));
+/// ```
+pub fn parse_start<C:From<char>>(s: &str) -> Result<(C,usize),InvalidNotation> {
+    let bytes = s.as_bytes();
+    let braced = bytes.starts_with(b"\\N{");
+    let start = if braced {3} else {0};
+    if !bytes[start..].starts_with(b"U+")  &&  !bytes[start..].starts_with(b"u+") {
+        return Err(Malformed);
+    }
+    let mut value = 0;
+    let mut end = start+2;
+    while let Some(digit) = bytes.get(end).and_then(|&b| (b as char).to_digit(16) ) {
+        if end == start+2+6 {
+            return Err(Malformed);
+        }
+        value = value << 4  |  digit;
+        end += 1;
+    }
+    if end < start+2+4 {
+        return Err(Malformed);
+    }
+    if braced {
+        if bytes.get(end) != Some(&b'}') {
+            return Err(Malformed);
+        }
+        end += 1;
+    }
+    let c = char::from_u32_detailed(value).map_err(Codepoint)?;
+    Ok((C::from(c), end))
+}
+
+/// Parse a string that contains only the notation for a single codepoint.
+///
+/// The result can be a `char`, `Utf8Char` or `Utf16Char`.
+pub fn parse<C:From<char>>(s: &str) -> Result<C,InvalidNotation> {
+    match parse_start(s) {
+        Ok((c, len)) if len == s.len() => Ok(c),
+        Ok(_) => Err(Malformed),
+        Err(e) => Err(e),
+    }
+}
+
+
+/// Formats a codepoint in the `U+XXXX` notation.
+///
+/// With the alternate flag (`{:#}`) it is wrapped in `\N{` and `}`
+/// so that it can be used in Python and Perl string literals.
+///
+/// # Examples
+///
+#[cfg_attr(feature="std", doc=" ```")]
+#[cfg_attr(not(feature="std"), doc=" ```no_compile")]
+/// use encode_unicode::notation::Notation;
+/// use encode_unicode::Utf8Char;
+///
+/// assert_eq!(format!("{}", Notation('A')), "U+0041");
+/// assert_eq!(format!("{:#}", Notation('\u{10ffff}')), "\\N{U+10FFFF}");
+/// assert_eq!(Notation(Utf8Char::from('æ').to_char()).to_string(), "U+00E6");
+/// ```
+#[derive(Clone,Copy, Debug, PartialEq,Eq, PartialOrd,Ord, Hash)]
+pub struct Notation(pub char);
+impl fmt::Display for Notation {
+    fn fmt(&self,  fmtr: &mut fmt::Formatter) -> fmt::Result {
+        if fmtr.alternate() {
+            write!(fmtr, "\\N{{U+{:04X}}}", self.0 as u32)
+        } else {
+            write!(fmtr, "U+{:04X}", self.0 as u32)
+        }
+    }
+}
+
+
+/// An inclusive range of codepoints, such as `U+0041..U+005A`.
+///
+/// Iterating over it skips the surrogates, so a range can span them.
+/// A range where `last` is before `first` is empty.
+#[derive(Clone,Copy, Debug, PartialEq,Eq, Hash)]
+pub struct CodepointRange {
+    /// The first codepoint in the range.
+    pub first: char,
+    /// The last codepoint in the range, which is included.
+    pub last: char,
+}
+impl CodepointRange {
+    /// Check whether the range includes a codepoint.
+    pub fn contains(self,  c: char) -> bool {
+        self.first <= c  &&  c <= self.last
+    }
+}
+impl str::FromStr for CodepointRange {
+    type Err = InvalidNotation;
+    /// Parse a range in the form `U+0041..U+005A`,
+    /// or a single codepoint as a range of one.
+    ///
+    /// A range that ends before it starts is an error.
+    fn from_str(s: &str) -> Result<Self,InvalidNotation> {
+        let (start, len) = parse_start(s)?;
+        if len == s.len() {
+            return Ok(CodepointRange{ first: start,  last: start });
+        }
+        if !s[len..].starts_with("..") {
+            return Err(Malformed);
+        }
+        let end = parse(&s[len+2..])?;
+        if end < start {
+            return Err(Reversed);
+        }
+        Ok(CodepointRange{ first: start,  last: end })
+    }
+}
+impl fmt::Display for CodepointRange {
+    fn fmt(&self,  fmtr: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmtr, "{}..{}", Notation(self.first), Notation(self.last))
+    }
+}
+impl IntoIterator for CodepointRange {
+    type Item = char;
+    type IntoIter = Codepoints;
+    fn into_iter(self) -> Codepoints {
+        Codepoints{ next: self.first as u32,  last: self.last as u32 }
+    }
+}
+
+
+/// The iterator type returned by `CodepointRange::into_iter()`
+///
+/// See its documentation for details.
+#[derive(Clone, Debug)]
+pub struct Codepoints {
+    next: u32,
+    last: u32,
+}
+impl Iterator for Codepoints {
+    type Item = char;
+    fn next(&mut self) -> Option<char> {
+        if self.next > self.last {
+            return None;
+        }
+        // `next` starts at a `char` and skips the surrogates, so this never returns early
+        let c = char::from_u32(self.next)?;
+        self.next = match self.next {
+            0xd7ff => 0xe000,
+            _ => self.next + 1,
+        };
+        Some(c)
+    }
+    fn size_hint(&self) -> (usize,Option<usize>) {
+        let mut len = (self.last+1).saturating_sub(self.next);
+        if self.next < 0xd800  &&  self.last > 0xdfff {
+            len -= 0x800;
+        }
+        (len as usize, Some(len as usize))
+    }
+}
+impl ExactSizeIterator for Codepoints {}
@@ -0,0 +1,277 @@
+/* Copyright 2018 Torbjørn Birch Moltu
+ *
+ * Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+ * http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+ * http://opensource.org/licenses/MIT>, at your option. This file may not be
+ * copied, modified, or distributed except according to those terms.
+ */
+
+//! Percent-encoding of URIs and IRIs
+//! ([RFC 3986 section 2.1](https://tools.ietf.org/html/rfc3986#section-2.1)).
+//!
+//! Which ASCII characters are left as they are depends on the part of the
+//! URI, and is chosen with `PercentSet`.
+//! Non-ASCII characters are encoded as the percent-encoded bytes of their
+//! UTF-8 representation, except by `encode_iri()`.
+//!
+//! When decoding, each run of `%XX` escapes must be valid UTF-8,
+//! so that overlong sequences such as `%C0%AE` can't be used to sneak
+//! `.` or `/` past a check on the encoded form.
+//!
+//! # Examples
+//!
+//! ```
+//! use encode_unicode::percent::{self, PercentSet};
+//! use encode_unicode::error::{InvalidPercentEncoding, InvalidUtf8Slice, InvalidUtf8};
+//!
+//! assert_eq!(percent::encode("/blåbær/100%", PercentSet::Path), "/bl%C3%A5b%C3%A6r/100%25");
+//! assert_eq!(percent::encode("a=1&b", PercentSet::Form), "a%3D1%26b");
+//! assert_eq!(percent::decode("bl%c3%a5b%C3%A6r+syltet%C3%B8y", PercentSet::Form).unwrap(),
+//!            "blåbær syltetøy");
+//!
+//! let traversal = percent::decode("/files/%C0%AE%C0%AE/passwd", PercentSet::Path);
+//! assert_eq!(traversal, Err(InvalidPercentEncoding::Utf8(7, InvalidUtf8Slice::Utf8(InvalidUtf8::OverLong))));
+//! ```
+
+use utf8_char::Utf8Char;
+use traits::StrExt;
+use errors::InvalidPercentEncoding;
+use errors::InvalidPercentEncoding::*;
+extern crate core;
+use self::core::fmt::Write;
+use self::core::cmp;
+extern crate alloc;
+use self::alloc::string::String;
+use self::alloc::vec::Vec;
+
+
+/// Which ASCII characters to leave unencoded, depending on where in a URI
+/// the encoded text will be.
+///
+/// Letters, digits, `-`, `.`, `_`, `~` and `*` are never encoded,
+/// and `%` always is.
+#[derive(Clone,Copy, Debug, PartialEq,Eq, Hash)]
+pub enum PercentSet {
+    /// A path, where `/` separates segments and `:`, `@` and the
+    /// sub-delimiters `!$&'()*+,;=` are allowed.
+    /// `?` and `#` are encoded.
+    Path,
+    /// A key or value in a query, which also allows `/`, `?`, `:` and `@`,
+    /// but where `&`, `=` and `+` are encoded because they separate pairs or
+    /// might be decoded as spaces.
+    Query,
+    /// A user name or password, which allows the sub-delimiters.
+    /// `:` is encoded because it separates the two.
+    Userinfo,
+    /// `application/x-www-form-urlencoded`, which only allows `*` in
+    /// addition to the unreserved characters, and encodes spaces as `+`.
+    ///
+    /// This is also the only set for which decoding replaces `+` with a space.
+    Form,
+}
+impl PercentSet {
+    fn allows(self,  b: u8) -> bool {
+        match b {
+            _ if (b | 0x20).wrapping_sub(b'a') < 26  ||  b.wrapping_sub(b'0') < 10 => true,
+            b'-' | b'.' | b'_' | b'~' | b'*' => true,
+            b'!' | b'$' | b'\'' | b'(' | b')' | b',' | b';' => match self {
+                PercentSet::Path | PercentSet::Query | PercentSet::Userinfo => true,
+                PercentSet::Form => false,
+            },
+            b'&' | b'=' | b'+' => match self {
+                PercentSet::Path | PercentSet::Userinfo => true,
+                PercentSet::Query | PercentSet::Form => false,
+            },
+            b':' | b'@' => match self {
+                PercentSet::Path | PercentSet::Query => true,
+                PercentSet::Userinfo | PercentSet::Form => false,
+            },
+            b'/' => self == PercentSet::Path  ||  self == PercentSet::Query,
+            b'?' => self == PercentSet::Query,
+            _ => false,
+        }
+    }
+}
+
+
+fn encode_where(s: &str,  set: PercentSet,  iri: bool) -> String {
+    let mut encoded = String::with_capacity(s.len());
+    for u8c in s.utf8chars() {
+        let first = u8c.as_bytes()[0];
+        if first == b' '  &&  set == PercentSet::Form {
+            encoded.push('+');
+        } else if (u8c.is_ascii()  &&  set.allows(first))  ||  (!u8c.is_ascii()  &&  iri) {
+            encoded.push_str(u8c.as_str());
+        } else {
+            for b in u8c {
+                write!(encoded, "%{:02X}", b).unwrap();
+            }
+        }
+    }
+    encoded
+}
+
+/// Percent-encode the characters that are not allowed in a part of an URI,
+/// including all non-ASCII characters.
+///
+/// Escapes use uppercase hexadecimal digits, as RFC 3986 recommends.
+pub fn encode(s: &str,  set: PercentSet) -> String {
+    encode_where(s, set, false)
+}
+
+/// Percent-encode the ASCII characters that are not allowed in a part of an
+/// IRI, and leave non-ASCII characters unchanged.
+///
+/// # Examples
+///
+/// ```
+/// use encode_unicode::percent::{self, PercentSet};
+///
+/// assert_eq!(percent::encode_iri("/wiki/Ærø?", PercentSet::Path), "/wiki/Ærø%3F");
+/// assert_eq!(percent::encode("/wiki/Ærø?", PercentSet::Path), "/wiki/%C3%86r%C3%B8%3F");
+/// ```
+pub fn encode_iri(s: &str,  set: PercentSet) -> String {
+    encode_where(s, set, true)
+}
+
+
+/// Parse the two hexadecimal digits of an escape that starts at `start`.
+fn hex_escape(bytes: &[u8],  start: usize) -> Option<u8> {
+    if bytes.len() < start+3  ||  bytes[start] != b'%' {
+        return None;
+    }
+    let high = (bytes[start+1] as char).to_digit(16);
+    let low = (bytes[start+2] as char).to_digit(16);
+    match (high, low) {
+        (Some(high), Some(low)) => Some((high << 4 | low) as u8),
+        _ => None,
+    }
+}
+
+/// How many bytes a replacement character stands for when decoding lossily.
+///
+/// Like WHATWG's UTF-8 decoder, this is the longest prefix that could have
+/// started a valid sequence, or one byte.
+fn invalid_len(bytes: &[u8]) -> usize {
+    let (len, min, max) = match bytes[0] {
+        0xe0 => (3, 0xa0, 0xbf),
+        0xed => (3, 0x80, 0x9f),
+        0xf0 => (4, 0x90, 0xbf),
+        0xf4 => (4, 0x80, 0x8f),
+        b if b.wrapping_sub(0xc2) <= 0xdf-0xc2 => (2, 0x80, 0xbf),
+        b if b.wrapping_sub(0xe1) <= 0xef-0xe1 => (3, 0x80, 0xbf),
+        b if b.wrapping_sub(0xf1) <= 0xf3-0xf1 => (4, 0x80, 0xbf),
+        _ => return 1,
+    };
+    match bytes.get(1) {
+        Some(&b) if b.wrapping_sub(min) <= max-min => {},
+        _ => return 1,
+    }
+    2 + bytes[2..cmp::min(len, bytes.len())].iter().take_while(|&&b| b & 0xc0 == 0x80 ).count()
+}
+
+fn decode_where(s: &str,  set: PercentSet,  lossy: bool) -> Result<String,InvalidPercentEncoding> {
+    let bytes = s.as_bytes();
+    let mut decoded = String::with_capacity(s.len());
+    let mut run = Vec::new();
+    let mut copy_from = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' => {},
+            b'+' if set == PercentSet::Form => {
+                decoded.push_str(&s[copy_from..i]);
+                decoded.push(' ');
+                i += 1;
+                copy_from = i;
+                continue;
+            },
+            _ => {
+                i += 1;
+                continue;
+            },
+        }
+        let start = i;
+        run.clear();
+        while let Some(b) = hex_escape(bytes, i) {
+            run.push(b);
+            i += 3;
+        }
+        if run.is_empty() {
+            if !lossy {
+                return Err(Malformed(start));
+            }
+            // leave the `%` as it is
+            i += 1;
+            continue;
+        }
+        decoded.push_str(&s[copy_from..start]);
+        let mut pos = 0;
+        while pos < run.len() {
+            match Utf8Char::from_slice_start(&run[pos..]) {
+                Ok((u8c, len)) => {
+                    decoded.push_str(u8c.as_str());
+                    pos += len;
+                },
+                Err(_) if lossy => {
+                    decoded.push('\u{fffd}');
+                    pos += invalid_len(&run[pos..]);
+                },
+                Err(e) => return Err(Utf8(start + 3*pos, e)),
+            }
+        }
+        copy_from = i;
+    }
+    decoded.push_str(&s[copy_from..]);
+    Ok(decoded)
+}
+
+/// Decode percent-encoded UTF-8.
+///
+/// Hexadecimal digits can be in either case.
+/// `+` is only decoded as a space for `PercentSet::Form`;
+/// otherwise the set doesn't matter, and characters that should have been
+/// encoded are accepted.
+///
+/// # Errors
+///
+/// Fails if a `%` isn't followed by two hexadecimal digits,
+/// or if the decoded bytes aren't valid UTF-8.
+/// The position in the error is the byte index of the `%` that starts
+/// the offending escape or sequence.
+///
+/// # Examples
+///
+/// ```
+/// use encode_unicode::percent::{self, PercentSet};
+/// use encode_unicode::error::InvalidPercentEncoding::*;
+/// use encode_unicode::error::{InvalidUtf8Slice, InvalidCodepoint};
+///
+/// assert_eq!(percent::decode("%F0%9F%92%A3+%2B", PercentSet::Query).unwrap(), "💣++");
+/// assert_eq!(percent::decode("100%", PercentSet::Path), Err(Malformed(3)));
+/// assert_eq!(percent::decode("%41%ED%A0%80", PercentSet::Path),
+///            Err(Utf8(3, InvalidUtf8Slice::Codepoint(InvalidCodepoint::Utf16Reserved))));
+/// assert_eq!(percent::decode("%E2%82", PercentSet::Path), Err(Utf8(0, InvalidUtf8Slice::TooShort(3))));
+/// ```
+pub fn decode(s: &str,  set: PercentSet) -> Result<String,InvalidPercentEncoding> {
+    decode_where(s, set, false)
+}
+
+/// Decode percent-encoded UTF-8, replacing invalid sequences with U+FFFD
+/// and leaving a `%` that doesn't start an escape as it is.
+///
+/// # Examples
+///
+/// ```
+/// use encode_unicode::percent::{self, PercentSet};
+///
+/// assert_eq!(percent::decode_lossy("%C0%AE%C0%AE/100%", PercentSet::Path),
+///            "\u{fffd}\u{fffd}\u{fffd}\u{fffd}/100%");
+/// assert_eq!(percent::decode_lossy("%E2%82%41", PercentSet::Path), "\u{fffd}A");
+/// ```
+pub fn decode_lossy(s: &str,  set: PercentSet) -> String {
+    match decode_where(s, set, true) {
+        Ok(decoded) => decoded,
+        Err(_) => unreachable!(),
+    }
+}
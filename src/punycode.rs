@@ -0,0 +1,238 @@
+/* Copyright 2018 Torbjørn Birch Moltu
+ *
+ * Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+ * http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+ * http://opensource.org/licenses/MIT>, at your option. This file may not be
+ * copied, modified, or distributed except according to those terms.
+ */
+
+//! Punycode ([RFC 3492](https://tools.ietf.org/html/rfc3492)),
+//! the encoding of internationalized domain name labels.
+//!
+//! This module only converts between Unicode and Punycode, and doesn't do
+//! the normalization and validation that IDNA requires.
+//!
+//! # Examples
+//!
+//! ```
+//! use encode_unicode::punycode;
+//!
+//! assert_eq!(punycode::encode("bücher").unwrap(), "bcher-kva");
+//! assert_eq!(punycode::decode("bcher-kva").unwrap(), "bücher");
+//! assert_eq!(punycode::to_ascii_label("bücher").unwrap(), "xn--bcher-kva");
+//! assert_eq!(punycode::from_ascii_label("xn--bcher-kva").unwrap(), "bücher");
+//! ```
+
+use traits::CharExt;
+use utf8_char::Utf8Char;
+use errors::InvalidPunycode;
+use errors::InvalidPunycode::*;
+extern crate alloc;
+use self::alloc::string::String;
+use self::alloc::vec::Vec;
+
+
+const BASE: u32 = 36;
+const T_MIN: u32 = 1;
+const T_MAX: u32 = 26;
+const SKEW: u32 = 38;
+const DAMP: u32 = 700;
+const INITIAL_BIAS: u32 = 72;
+const INITIAL_N: u32 = 128;
+const DELIMITER: char = '-';
+
+
+fn adapt(mut delta: u32,  num_points: u32,  first_time: bool) -> u32 {
+    delta /= if first_time {DAMP} else {2};
+    delta += delta / num_points;
+    let mut k = 0;
+    while delta > ((BASE - T_MIN) * T_MAX) / 2 {
+        delta /= BASE - T_MIN;
+        k += BASE;
+    }
+    k + (((BASE - T_MIN + 1) * delta) / (delta + SKEW))
+}
+
+fn threshold(k: u32,  bias: u32) -> u32 {
+    if k <= bias {
+        T_MIN
+    } else if k >= bias + T_MAX {
+        T_MAX
+    } else {
+        k - bias
+    }
+}
+
+fn encode_digit(d: u32) -> char {
+    if d < 26 {
+        (b'a' + d as u8) as char
+    } else {
+        (b'0' + (d-26) as u8) as char
+    }
+}
+
+fn decode_digit(b: u8) -> Option<u32> {
+    if (b | 0x20).wrapping_sub(b'a') < 26 {
+        Some(((b | 0x20) - b'a') as u32)
+    } else if b.wrapping_sub(b'0') < 10 {
+        Some((b - b'0') as u32 + 26)
+    } else {
+        None
+    }
+}
+
+
+/// Encode a string as Punycode.
+///
+/// ASCII characters are copied to the start of the output, and
+/// followed by a `-` if there are any.
+///
+/// # Errors
+///
+/// Only returns `Overflow`, which requires strings of several thousand
+/// characters.
+pub fn encode(input: &str) -> Result<String,InvalidPunycode> {
+    let input: Vec<Utf8Char> = input.chars().map(|c| c.to_utf8() ).collect();
+    let mut output = String::with_capacity(input.len());
+    for &u8c in input.iter().filter(|u8c| u8c.is_ascii() ) {
+        output.push_str(&u8c);
+    }
+    let basic = output.len() as u32;
+    if basic > 0 {
+        output.push(DELIMITER);
+    }
+
+    let mut n = INITIAL_N;
+    let mut delta = 0u32;
+    let mut bias = INITIAL_BIAS;
+    let mut handled = basic;
+    while (handled as usize) < input.len() {
+        // find the next codepoint to insert
+        let m = input.iter()
+                     .map(|&u8c| u8c.to_char() as u32 )
+                     .filter(|&c| c >= n )
+                     .min().unwrap();
+        delta = (m - n).checked_mul(handled + 1)
+                            .and_then(|add| delta.checked_add(add) )
+                            .ok_or(Overflow)?;
+        n = m;
+        for &u8c in &input {
+            let c = u8c.to_char() as u32;
+            if c < n {
+                delta = delta.checked_add(1).ok_or(Overflow)?;
+            } else if c == n {
+                let mut q = delta;
+                let mut k = BASE;
+                loop {
+                    let t = threshold(k, bias);
+                    if q < t {
+                        break;
+                    }
+                    output.push(encode_digit(t + (q - t) % (BASE - t)));
+                    q = (q - t) / (BASE - t);
+                    k += BASE;
+                }
+                output.push(encode_digit(q));
+                bias = adapt(delta, handled + 1, handled == basic);
+                delta = 0;
+                handled += 1;
+            }
+        }
+        delta = delta.checked_add(1).ok_or(Overflow)?;
+        n += 1;
+    }
+    Ok(output)
+}
+
+/// Decode a Punycode string.
+///
+/// Uppercase digits are accepted, and the case of ASCII characters is
+/// preserved.
+///
+/// # Examples
+///
+/// ```
+/// use encode_unicode::punycode;
+/// use encode_unicode::error::InvalidPunycode::*;
+///
+/// assert_eq!(punycode::decode("fs8h").unwrap(), "💣");
+/// assert_eq!(punycode::decode("-with-SUPER-MONKEYS-pc58ag80a8qai00g7n9n").unwrap(),
+///            "安室奈美恵-with-SUPER-MONKEYS");
+/// assert_eq!(punycode::decode("bücher"), Err(NonBasic(1)));
+/// assert_eq!(punycode::decode("bcher-k_a"), Err(InvalidDigit(7)));
+/// assert_eq!(punycode::decode("bcher-kv"), Err(Truncated));
+/// assert_eq!(punycode::decode("99999999999"), Err(Overflow));
+/// ```
+pub fn decode(input: &str) -> Result<String,InvalidPunycode> {
+    if let Some(i) = input.bytes().position(|b| b >= 0x80 ) {
+        return Err(NonBasic(i));
+    }
+    let (mut output, mut pos) = match input.rfind(DELIMITER) {
+        Some(delimiter) => {
+            let basic: Vec<char> = input[..delimiter].chars().collect();
+            (basic, delimiter+1)
+        },
+        None => (Vec::new(), 0),
+    };
+
+    let bytes = input.as_bytes();
+    let mut n = INITIAL_N;
+    let mut i = 0u32;
+    let mut bias = INITIAL_BIAS;
+    while pos < bytes.len() {
+        let old_i = i;
+        let mut w = 1u32;
+        let mut k = BASE;
+        loop {
+            let digit = match bytes.get(pos) {
+                Some(&b) => decode_digit(b).ok_or(InvalidDigit(pos))?,
+                None => return Err(Truncated),
+            };
+            pos += 1;
+            i = digit.checked_mul(w).and_then(|add| i.checked_add(add) ).ok_or(Overflow)?;
+            let t = threshold(k, bias);
+            if digit < t {
+                break;
+            }
+            w = w.checked_mul(BASE - t).ok_or(Overflow)?;
+            k += BASE;
+        }
+        let length = output.len() as u32 + 1;
+        bias = adapt(i - old_i, length, old_i == 0);
+        n = n.checked_add(i / length).ok_or(Overflow)?;
+        i %= length;
+        let c = char::from_u32_detailed(n)?;
+        output.insert(i as usize, c);
+        i += 1;
+    }
+    Ok(output.into_iter().collect())
+}
+
+
+/// Convert a domain name label to its ASCII form.
+///
+/// Labels that contain only ASCII are returned unchanged,
+/// others are encoded as Punycode and prefixed with `xn--`.
+pub fn to_ascii_label(label: &str) -> Result<String,InvalidPunycode> {
+    if label.bytes().all(|b| b < 0x80 ) {
+        Ok(String::from(label))
+    } else {
+        encode(label).map(|encoded| String::from("xn--") + &encoded )
+    }
+}
+
+/// Convert a domain name label from its ASCII form.
+///
+/// Labels that start with `xn--` (in any case) are decoded as Punycode,
+/// others are returned unchanged.
+pub fn from_ascii_label(label: &str) -> Result<String,InvalidPunycode> {
+    if label.as_bytes().get(..4).is_some_and(|prefix| prefix.eq_ignore_ascii_case(b"xn--") ) {
+        decode(&label[4..]).map_err(|e| match e {
+            NonBasic(i) => NonBasic(i + 4),
+            InvalidDigit(i) => InvalidDigit(i + 4),
+            other => other,
+        })
+    } else {
+        Ok(String::from(label))
+    }
+}
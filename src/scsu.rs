@@ -0,0 +1,548 @@
+/* Copyright 2018 Torbjørn Birch Moltu
+ *
+ * Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+ * http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+ * http://opensource.org/licenses/MIT>, at your option. This file may not be
+ * copied, modified, or distributed except according to those terms.
+ */
+
+//! The Standard Compression Scheme for Unicode
+//! ([UTS #6](https://www.unicode.org/reports/tr6/)).
+//!
+//! SCSU encodes text from small alphabets with one byte per character by
+//! moving 128-character windows around the codespace,
+//! and falls back to UTF-16 for large ones such as Chinese.
+//! Any valid SCSU can be decoded, but the encoder only uses a
+//! simple strategy to choose between windows and modes, so other encoders
+//! might produce smaller output.
+//!
+//! # Examples
+//!
+#![cfg_attr(feature="std", doc=" ```")]
+#![cfg_attr(not(feature="std"), doc=" ```no_compile")]
+//! use encode_unicode::{scsu, Utf16Char};
+//!
+//! let text = "Всё хорошо";
+//! let encoded: Vec<u8> = scsu::encode(text.chars()).collect();
+//! assert_eq!(encoded.len(), 11);
+//! assert!(text.len() == 19  &&  text.chars().count() == 10);
+//!
+//! let decoded: Result<Vec<Utf16Char>,_> = scsu::decode(encoded).collect();
+//! let decoded: String = decoded.unwrap().into_iter().map(|u16c| u16c.to_char() ).collect();
+//! assert_eq!(decoded, text);
+//! ```
+
+use utf16_char::Utf16Char;
+use errors::InvalidScsu;
+use errors::InvalidScsu::*;
+extern crate core;
+use self::core::fmt;
+#[cfg(feature="std")]
+use std::io::{Read, Error as ioError};
+
+
+// tags in single-byte mode
+const SQ0: u8 = 0x01;
+const SDX: u8 = 0x0b;
+const SRS: u8 = 0x0c;// reserved
+const SQU: u8 = 0x0e;
+const SCU: u8 = 0x0f;
+const SC0: u8 = 0x10;
+const SD0: u8 = 0x18;
+// tags in Unicode mode
+const UC0: u8 = 0xe0;
+const UD0: u8 = 0xe8;
+const UQU: u8 = 0xf0;
+const UDX: u8 = 0xf1;
+const URS: u8 = 0xf2;// reserved
+
+/// The windows that can only be quoted from.
+static STATIC_WINDOWS: [u32; 8] = [
+    0x0000, 0x0080, 0x0100, 0x0300, 0x2000, 0x2080, 0x2100, 0x3000
+];
+/// The initial positions of the windows that can be moved.
+static DEFAULT_WINDOWS: [u32; 8] = [
+    0x0080, 0x00c0, 0x0400, 0x0600, 0x0900, 0x3040, 0x30a0, 0xff00
+];
+/// The offsets of window offset bytes 0xf9 to 0xff,
+/// for scripts that don't start at a multiple of 128.
+static SPECIAL_OFFSETS: [u32; 7] = [
+    0x00c0, 0x0250, 0x0370, 0x0530, 0x3040, 0x30a0, 0xff60
+];
+
+/// The offset an SDn or UDn tag moves a window to,
+/// or `None` if the offset byte is reserved.
+fn window_offset(byte: u8) -> Option<u32> {
+    if byte == 0 {
+        None
+    } else if byte < 0x68 {
+        Some(byte as u32 * 0x80)
+    } else if byte < 0xa8 {
+        Some(byte as u32 * 0x80 + 0xac00)
+    } else if byte >= 0xf9 {
+        Some(SPECIAL_OFFSETS[(byte-0xf9) as usize])
+    } else {
+        None
+    }
+}
+
+/// The window number and offset an SDX or UDX tag defines.
+fn extended_window(high: u8,  low: u8) -> (usize, u32) {
+    let offset = (((high & 0x1f) as u32) << 8) | low as u32;
+    ((high >> 5) as usize,  0x10000 + offset * 0x80)
+}
+
+/// Whether a character is better encoded as UTF-16 than in a window:
+/// CJK ideographs and Hangul syllables, which cover far more than 128
+/// characters and cannot be used as window offsets anyway.
+fn is_wide(c: u32) -> bool {
+    c.wrapping_sub(0x3400) < 0xe000-0x3400
+}
+
+fn in_window(c: u32,  offset: u32) -> bool {
+    c.wrapping_sub(offset) < 0x80
+}
+
+
+/// Encode characters as SCSU.
+///
+/// Accepts iterators of `char` or `Utf16Char`,
+/// and returns an iterator of bytes.
+///
+/// The iterator also implements `Read` (if the `std` feature isn't disabled).
+/// Reading will never produce an error, and calls to `.read()` and `.next()`
+/// can be mixed.
+///
+/// # Examples
+///
+#[cfg_attr(feature="std", doc=" ```")]
+#[cfg_attr(not(feature="std"), doc=" ```no_compile")]
+/// use encode_unicode::scsu;
+/// use std::io::Read;
+///
+/// // ASCII is unchanged
+/// assert_eq!(scsu::encode("plain".chars()).collect::<Vec<u8>>(), b"plain");
+/// // the Latin-1 supplement is in one of the default windows
+/// assert_eq!(scsu::encode("Ünïcødé".chars()).collect::<Vec<u8>>(),
+///            [0xdc, b'n', 0xef, b'c', 0xf8, b'd', 0xe9]);
+///
+/// let mut buf = [0; 16];
+/// let mut reader = scsu::encode("日本語".chars());
+/// assert_eq!(reader.read(&mut buf).unwrap(), 7);
+/// assert_eq!(&buf[..7], [0x0f, 0x65, 0xe5, 0x67, 0x2c, 0x8a, 0x9e]);
+/// ```
+pub fn encode<C:Into<Utf16Char>, I:IntoIterator<Item=C>>
+(iterable: I) -> ScsuEncoder<C, I::IntoIter> {
+    ScsuEncoder {
+        inner: iterable.into_iter(),
+        next: None,
+        started: false,
+        unicode_mode: false,
+        active: 0,
+        windows: DEFAULT_WINDOWS,
+        last_used: [0; 8],
+        uses: 0,
+        buf: [0; 4],
+        buf_pos: 0,
+        buf_len: 0,
+    }
+}
+
+/// The iterator type returned by `scsu::encode()`
+///
+/// See its documentation for details.
+#[derive(Clone)]
+pub struct ScsuEncoder<C:Into<Utf16Char>, I:Iterator<Item=C>> {
+    inner: I,
+    /// The character after the one that is being encoded,
+    /// which decides whether to switch or quote.
+    next: Option<u32>,
+    started: bool,
+    unicode_mode: bool,
+    active: usize,
+    windows: [u32; 8],
+    /// When each window was last used, for replacing the least recently used.
+    last_used: [u32; 8],
+    uses: u32,
+    buf: [u8; 4],
+    buf_pos: u8,
+    buf_len: u8,
+}
+impl<C:Into<Utf16Char>, I:Iterator<Item=C>> ScsuEncoder<C,I> {
+    fn push(&mut self,  byte: u8) {
+        self.buf[self.buf_len as usize] = byte;
+        self.buf_len += 1;
+    }
+    fn push_unit(&mut self,  unit: u16) {
+        let high = (unit >> 8) as u8;
+        if self.unicode_mode  &&  high.wrapping_sub(UC0) <= URS-UC0 {
+            self.push(UQU);
+        }
+        self.push(high);
+        self.push(unit as u8);
+    }
+    fn push_units(&mut self,  c: u32) {
+        let (first, second) = Utf16Char::from(char_from(c)).to_tuple();
+        self.push_unit(first);
+        if let Some(second) = second {
+            self.push_unit(second);
+        }
+    }
+    fn window_containing(&self,  c: u32) -> Option<usize> {
+        if in_window(c, self.windows[self.active]) {
+            Some(self.active)
+        } else {
+            self.windows.iter().position(|&offset| in_window(c, offset) )
+        }
+    }
+    fn use_window(&mut self,  window: usize) {
+        self.uses = self.uses.wrapping_add(1);
+        self.last_used[window] = self.uses;
+    }
+    /// Move the least recently used window to `c`, make it active and
+    /// encode `c` with it.
+    fn define_window(&mut self,  c: u32,  define_tag: u8,  extended_tag: u8) {
+        let (mut window, mut oldest) = (0, self.uses);
+        for (i, &used) in self.last_used.iter().enumerate() {
+            if self.uses.wrapping_sub(used) > self.uses.wrapping_sub(oldest) {
+                window = i;
+                oldest = used;
+            }
+        }
+        let offset = if c >= 0x10000 {
+            let index = (c - 0x10000) / 0x80;
+            self.push(extended_tag);
+            self.push(((window as u32) << 5 | index >> 8) as u8);
+            self.push(index as u8);
+            0x10000 + index * 0x80
+        } else {
+            let byte = match SPECIAL_OFFSETS.iter().position(|&offset| in_window(c, offset) ) {
+                Some(i) => 0xf9 + i as u8,
+                None if c < 0x3400 => (c / 0x80) as u8,
+                None => ((c - 0xac00) / 0x80) as u8,
+            };
+            self.push(define_tag + window as u8);
+            self.push(byte);
+            window_offset(byte).unwrap()
+        };
+        self.windows[window] = offset;
+        self.active = window;
+        self.use_window(window);
+        self.push(0x80 + (c - offset) as u8);
+    }
+    fn encode_char(&mut self,  c: u32) {
+        let next = self.next;
+        if self.unicode_mode {
+            if is_wide(c)  ||  next.into_iter().any(is_wide) {
+                self.push_units(c);
+                return;
+            }
+            self.unicode_mode = false;
+            if let Some(window) = self.window_containing(c) {
+                self.push(UC0 + window as u8);
+                self.active = window;
+                self.use_window(window);
+                self.push(0x80 + (c - self.windows[window]) as u8);
+                return;
+            } else if c >= 0x80  &&  !is_wide(c)
+            && STATIC_WINDOWS.iter().all(|&offset| !in_window(c, offset) ) {
+                self.define_window(c, UD0, UDX);
+                return;
+            }
+            let active = self.active;
+            self.push(UC0 + active as u8);
+        }
+
+        if c < 0x80 {
+            // NUL, tab, LF and CR are the only control characters that don't need quoting
+            if c >= 0x20  ||  c == 0  ||  c == 0x09  ||  c == 0x0a  ||  c == 0x0d {
+                self.push(c as u8);
+            } else {
+                self.push(SQ0);
+                self.push(c as u8);
+            }
+        } else if let Some(window) = self.window_containing(c) {
+            let offset = self.windows[window];
+            self.use_window(window);
+            if window == self.active {
+                // no tag needed
+            } else if next.into_iter().any(|next| in_window(next, offset) ) {
+                self.push(SC0 + window as u8);
+                self.active = window;
+            } else {
+                self.push(SQ0 + window as u8);
+            }
+            self.push(0x80 + (c - offset) as u8);
+        } else if is_wide(c) {
+            if next.into_iter().any(is_wide) {
+                self.push(SCU);
+                self.unicode_mode = true;
+            } else {
+                self.push(SQU);
+            }
+            self.push_units(c);
+        } else {
+            let quotable = STATIC_WINDOWS.iter().position(|&offset| in_window(c, offset) );
+            match quotable {
+                Some(window) if !next.into_iter().any(|next| in_window(next, STATIC_WINDOWS[window]) ) => {
+                    self.push(SQ0 + window as u8);
+                    self.push((c - STATIC_WINDOWS[window]) as u8);
+                },
+                _ => self.define_window(c, SD0, SDX),
+            }
+        }
+    }
+}
+impl<C:Into<Utf16Char>, I:Iterator<Item=C>> Iterator for ScsuEncoder<C,I> {
+    type Item = u8;
+    fn next(&mut self) -> Option<u8> {
+        if self.buf_pos == self.buf_len {
+            if !self.started {
+                self.started = true;
+                self.next = self.inner.next().map(|c| c.into().to_char() as u32 );
+            }
+            match self.next {
+                Some(c) => {
+                    self.next = self.inner.next().map(|c| c.into().to_char() as u32 );
+                    self.buf_pos = 0;
+                    self.buf_len = 0;
+                    self.encode_char(c);
+                },
+                None => return None,
+            }
+        }
+        self.buf_pos += 1;
+        Some(self.buf[self.buf_pos as usize - 1])
+    }
+    fn size_hint(&self) -> (usize,Option<usize>) {
+        // Every character needs between one and four bytes
+        let (min, max) = self.inner.size_hint();
+        let buffered = (self.buf_len - self.buf_pos) as usize;
+        let next = if self.started {self.next.map_or(0, |_| 1 )} else {0};
+        (min.saturating_add(next).saturating_add(buffered),
+         max.and_then(|max| max.checked_add(next) )
+            .and_then(|max| max.checked_mul(4) )
+            .and_then(|max| max.checked_add(buffered) ))
+    }
+}
+#[cfg(feature="std")]
+impl<C:Into<Utf16Char>, I:Iterator<Item=C>> Read for ScsuEncoder<C,I> {
+    /// Always returns `Ok`
+    fn read(&mut self,  buf: &mut[u8]) -> Result<usize, ioError> {
+        for (i, dst) in buf.iter_mut().enumerate() {
+            match self.next() {
+                Some(b) => *dst = b,
+                None    => return Ok(i),
+            }
+        }
+        Ok(buf.len())
+    }
+}
+impl<C:Into<Utf16Char>, I:Iterator<Item=C>> fmt::Debug for ScsuEncoder<C,I> {
+    fn fmt(&self,  fmtr: &mut fmt::Formatter) -> fmt::Result {
+        fmtr.debug_struct("ScsuEncoder")
+            .field("unicode_mode", &self.unicode_mode)
+            .field("active", &self.active)
+            .field("windows", &self.windows)
+            .finish()
+    }
+}
+
+fn char_from(c: u32) -> char {
+    // only called with values that came from a char or a valid window
+    unsafe{ self::core::char::from_u32_unchecked(c) }
+}
+
+
+/// Decode SCSU.
+///
+/// Returns an iterator of `Utf16Char`s, or errors with the position of the
+/// invalid sequence. Decoding can continue after an error.
+///
+/// # Examples
+///
+/// ```
+/// use encode_unicode::{scsu, Utf16Char};
+/// use encode_unicode::error::InvalidScsu::*;
+///
+/// // "Öl" with a quoted ö from the Latin-1 window
+/// let mut decoder = scsu::decode(vec![0x0f, 0x00, 0xd6, 0xe0, b'l']);
+/// assert_eq!(decoder.next(), Some(Ok(Utf16Char::from('Ö'))));
+/// assert_eq!(decoder.next(), Some(Ok(Utf16Char::from('l'))));
+/// assert_eq!(decoder.next(), None);
+///
+/// assert_eq!(scsu::decode(vec![b'a', 0x0c]).nth(1), Some(Err(ReservedTag(1))));
+/// assert_eq!(scsu::decode(vec![0x0e, 0xd8]).next(), Some(Err(Truncated(0))));
+/// ```
+pub fn decode<I:IntoIterator<Item=u8>>(bytes: I) -> ScsuDecoder<I::IntoIter> {
+    ScsuDecoder {
+        inner: bytes.into_iter(),
+        pos: 0,
+        unicode_mode: false,
+        active: 0,
+        windows: DEFAULT_WINDOWS,
+        lead: None,
+        queued: None,
+    }
+}
+
+/// The iterator type returned by `scsu::decode()`
+///
+/// See its documentation for details.
+#[derive(Clone)]
+pub struct ScsuDecoder<I:Iterator<Item=u8>> {
+    inner: I,
+    pos: usize,
+    unicode_mode: bool,
+    active: usize,
+    windows: [u32; 8],
+    /// A leading surrogate and its position.
+    lead: Option<(u16,usize)>,
+    /// A character that follows an unpaired leading surrogate.
+    queued: Option<Utf16Char>,
+}
+impl<I:Iterator<Item=u8>> ScsuDecoder<I> {
+    /// Extracts the source iterator.
+    ///
+    /// Note that it's not possible to resume decoding with it unless it's
+    /// at the start of a sequence and the encoder hasn't moved any windows.
+    pub fn into_inner(self) -> I {
+        self.inner
+    }
+    fn byte(&mut self,  start: usize) -> Result<u8,InvalidScsu> {
+        match self.inner.next() {
+            Some(b) => {
+                self.pos += 1;
+                Ok(b)
+            },
+            None => Err(Truncated(start)),
+        }
+    }
+    fn unit(&mut self,  start: usize) -> Result<u32,InvalidScsu> {
+        let high = self.byte(start)?;
+        let low = self.byte(start)?;
+        Ok((high as u32) << 8 | low as u32)
+    }
+    fn define(&mut self,  window: usize,  start: usize) -> Result<(),InvalidScsu> {
+        let byte = self.byte(start)?;
+        self.windows[window] = window_offset(byte).ok_or(ReservedWindowOffset(start))?;
+        self.active = window;
+        self.unicode_mode = false;
+        Ok(())
+    }
+    fn define_extended(&mut self,  start: usize) -> Result<(),InvalidScsu> {
+        let high = self.byte(start)?;
+        let low = self.byte(start)?;
+        let (window, offset) = extended_window(high, low);
+        self.windows[window] = offset;
+        self.active = window;
+        self.unicode_mode = false;
+        Ok(())
+    }
+    /// Read tags until a codepoint or unit.
+    fn codepoint(&mut self,  start: usize,  first: u8) -> Result<Option<u32>,InvalidScsu> {
+        let b = first;
+        if self.unicode_mode {
+            match b {
+                _ if b.wrapping_sub(UC0) < 8 => {
+                    self.active = (b - UC0) as usize;
+                    self.unicode_mode = false;
+                },
+                _ if b.wrapping_sub(UD0) < 8 => self.define((b - UD0) as usize, start)?,
+                UQU => return self.unit(start).map(Some),
+                UDX => self.define_extended(start)?,
+                URS => return Err(ReservedTag(start)),
+                _ => {
+                    let low = self.byte(start)?;
+                    return Ok(Some((b as u32) << 8 | low as u32));
+                },
+            }
+            Ok(None)
+        } else if b >= 0x80 {
+            Ok(Some(self.windows[self.active] + (b - 0x80) as u32))
+        } else if b >= 0x20  ||  b == 0  ||  b == 0x09  ||  b == 0x0a  ||  b == 0x0d {
+            Ok(Some(b as u32))
+        } else if b.wrapping_sub(SQ0) < 8 {
+            let window = (b - SQ0) as usize;
+            let quoted = self.byte(start)?;
+            if quoted < 0x80 {
+                Ok(Some(STATIC_WINDOWS[window] + quoted as u32))
+            } else {
+                Ok(Some(self.windows[window] + (quoted - 0x80) as u32))
+            }
+        } else if b.wrapping_sub(SC0) < 8 {
+            self.active = (b - SC0) as usize;
+            Ok(None)
+        } else if b.wrapping_sub(SD0) < 8 {
+            self.define((b - SD0) as usize, start)?;
+            Ok(None)
+        } else {
+            match b {
+                SDX => self.define_extended(start)?,
+                SQU => return self.unit(start).map(Some),
+                SCU => self.unicode_mode = true,
+                SRS => return Err(ReservedTag(start)),
+                _ => unreachable!(),
+            }
+            Ok(None)
+        }
+    }
+}
+impl<I:Iterator<Item=u8>> Iterator for ScsuDecoder<I> {
+    type Item = Result<Utf16Char,InvalidScsu>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(u16c) = self.queued.take() {
+            return Some(Ok(u16c));
+        }
+        loop {
+            let start = self.pos;
+            let first = match self.inner.next() {
+                Some(b) => b,
+                None => return self.lead.take().map(|(_,lead_pos)| Err(UnpairedSurrogate(lead_pos)) ),
+            };
+            self.pos += 1;
+            let c = match self.codepoint(start, first) {
+                Ok(Some(c)) => c,
+                Ok(None) => continue,
+                Err(e) => return Some(Err(e)),
+            };
+            if c & 0x1f_f800 != 0xd800 {
+                let u16c = Utf16Char::from(char_from(c));
+                return Some(match self.lead.take() {
+                    Some((_,lead_pos)) => {
+                        self.queued = Some(u16c);
+                        Err(UnpairedSurrogate(lead_pos))
+                    },
+                    None => Ok(u16c),
+                });
+            } else if c < 0xdc00 {
+                if let Some((_,lead_pos)) = self.lead.take() {
+                    self.lead = Some((c as u16, start));
+                    return Some(Err(UnpairedSurrogate(lead_pos)));
+                }
+                self.lead = Some((c as u16, start));
+            } else {
+                return Some(match self.lead.take() {
+                    Some((lead,_)) => Ok(unsafe{ Utf16Char::from_tuple_unchecked((lead, Some(c as u16))) }),
+                    None => Err(UnpairedSurrogate(start)),
+                });
+            }
+        }
+    }
+    fn size_hint(&self) -> (usize,Option<usize>) {
+        // Every character needs at least one byte, but tags might produce nothing.
+        let (_, max) = self.inner.size_hint();
+        let extra = self.queued.map_or(0, |_| 1 ) + self.lead.map_or(0, |_| 1 );
+        (self.queued.map_or(0, |_| 1 ),  max.and_then(|max| max.checked_add(extra) ))
+    }
+}
+impl<I:Iterator<Item=u8>> fmt::Debug for ScsuDecoder<I> {
+    fn fmt(&self,  fmtr: &mut fmt::Formatter) -> fmt::Result {
+        fmtr.debug_struct("ScsuDecoder")
+            .field("position", &self.pos)
+            .field("unicode_mode", &self.unicode_mode)
+            .field("active", &self.active)
+            .field("windows", &self.windows)
+            .finish()
+    }
+}
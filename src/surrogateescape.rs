@@ -0,0 +1,377 @@
+/* Copyright 2018 Torbjørn Birch Moltu
+ *
+ * Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+ * http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+ * http://opensource.org/licenses/MIT>, at your option. This file may not be
+ * copied, modified, or distributed except according to those terms.
+ */
+
+//! Decoding arbitrary bytes as UTF-8 the way Python's `surrogateescape`
+//! error handler ([PEP 383](https://www.python.org/dev/peps/pep-0383/)) does,
+//! and encoding the result back to the exact original bytes.
+//!
+//! Each byte that isn't part of a valid UTF-8 sequence becomes the lone
+//! surrogate U+DC80..U+DCFF with the byte as its lower eight bits.
+//! Such surrogates never come from valid UTF-8, so encoding turns them back
+//! into the bytes they came from, and everything else into UTF-8.
+//! Invalid bytes are always at least 0x80, so ASCII is never escaped.
+//!
+//! As lone surrogates cannot be stored in `str`, `char` or `Utf16Char`,
+//! the decoded text is produced as raw UTF-16 units or as
+//! [WTF-8](https://simonsapin.github.io/wtf-8/) bytes.
+//!
+//! # Examples
+//!
+#![cfg_attr(feature="std", doc=" ```")]
+#![cfg_attr(not(feature="std"), doc=" ```no_compile")]
+//! use encode_unicode::surrogateescape;
+//!
+//! let file_name = b"caf\xe9.txt";// Latin-1
+//! let utf16: Vec<u16> = surrogateescape::decode_utf16(file_name).collect();
+//! assert_eq!(utf16[3], 0xdce9);
+//! let restored: Result<Vec<u8>,_> = surrogateescape::encode_utf16(utf16).collect();
+//! assert_eq!(restored.unwrap(), file_name);
+//! ```
+
+use traits::{CharExt, U8UtfExt};
+use errors::InvalidSurrogateEscape;
+use errors::InvalidSurrogateEscape::*;
+
+
+/// Decode a character from the start of `bytes`,
+/// or find how many of the first bytes need to be escaped.
+fn decode_start(bytes: &[u8]) -> Result<(char,usize),usize> {
+    use errors::InvalidUtf8::*;
+    use errors::InvalidUtf8Slice::*;
+    match char::from_utf8_slice_start(bytes) {
+        Ok(decoded) => Ok(decoded),
+        Err(Utf8(FirstByte(_))) => Err(1),
+        // the byte that isn't a continuation byte might start a valid sequence
+        Err(Utf8(NotAContinuationByte(i))) => Err(i),
+        // the length isn't checked before the continuation bytes
+        Err(TooShort(_)) => Err(1 + bytes[1..].iter().take_while(|&&b| b >> 6 == 0b10 ).count()),
+        Err(Utf8(OverLong)) | Err(Codepoint(_)) => Err(bytes[0].extra_utf8_bytes().unwrap_or(0) + 1),
+    }
+}
+
+
+/// Decode bytes as UTF-8 to UTF-16, escaping invalid bytes as lone surrogates.
+///
+/// # Examples
+///
+#[cfg_attr(feature="std", doc=" ```")]
+#[cfg_attr(not(feature="std"), doc=" ```no_compile")]
+/// use encode_unicode::surrogateescape;
+///
+/// let decoded: Vec<u16> = surrogateescape::decode_utf16(b"\xf0\x9f\x92\xa3\xf0\x9f\x92").collect();
+/// assert_eq!(decoded, [0xd83d, 0xdca3, 0xdcf0, 0xdc9f, 0xdc92]);
+/// ```
+pub fn decode_utf16<'a>(input: &'a [u8]) -> EscapingUtf16<'a> {
+    EscapingUtf16{ bytes: input,  invalid: 0,  trail: None }
+}
+
+/// The iterator type returned by `surrogateescape::decode_utf16()`
+///
+/// See its documentation for details.
+#[derive(Clone, Debug)]
+pub struct EscapingUtf16<'a> {
+    bytes: &'a [u8],
+    /// How many of the remaining bytes are to be escaped.
+    invalid: usize,
+    /// The second half of a surrogate pair.
+    trail: Option<u16>,
+}
+impl<'a> EscapingUtf16<'a> {
+    /// The bytes that haven't been decoded yet.
+    ///
+    /// If the second unit of a surrogate pair hasn't been returned,
+    /// it is not included.
+    pub fn as_slice(&self) -> &'a [u8] {
+        self.bytes
+    }
+}
+impl<'a> Iterator for EscapingUtf16<'a> {
+    type Item = u16;
+    fn next(&mut self) -> Option<u16> {
+        if let Some(trail) = self.trail.take() {
+            return Some(trail);
+        }
+        if self.bytes.is_empty() {
+            return None;
+        }
+        if self.invalid == 0 {
+            match decode_start(self.bytes) {
+                Ok((c, len)) => {
+                    self.bytes = &self.bytes[len..];
+                    let (first, second) = c.to_utf16_tuple();
+                    self.trail = second;
+                    return Some(first);
+                },
+                Err(invalid) => self.invalid = invalid,
+            }
+        }
+        let byte = self.bytes[0];
+        self.bytes = &self.bytes[1..];
+        self.invalid -= 1;
+        Some(0xdc00 | byte as u16)
+    }
+    fn size_hint(&self) -> (usize,Option<usize>) {
+        // A four-byte sequence becomes two units, and an invalid byte one.
+        let trail = self.trail.map_or(0, |_| 1 );
+        (self.bytes.len()/4 + trail,  Some(self.bytes.len() + trail))
+    }
+}
+
+
+/// Decode bytes as UTF-8 to WTF-8, escaping invalid bytes as lone surrogates.
+///
+/// Valid sequences are passed through unchanged,
+/// and each invalid byte becomes three bytes.
+///
+/// # Examples
+///
+#[cfg_attr(feature="std", doc=" ```")]
+#[cfg_attr(not(feature="std"), doc=" ```no_compile")]
+/// use encode_unicode::surrogateescape;
+///
+/// let decoded: Vec<u8> = surrogateescape::decode_wtf8(b"\xc3\xa5\xff!").collect();
+/// assert_eq!(decoded, b"\xc3\xa5\xed\xb3\xbf!");
+/// ```
+pub fn decode_wtf8<'a>(input: &'a [u8]) -> EscapingWtf8<'a> {
+    EscapingWtf8{ bytes: input,  valid: 0,  invalid: 0,  escape: 0 }
+}
+
+/// The iterator type returned by `surrogateescape::decode_wtf8()`
+///
+/// See its documentation for details.
+#[derive(Clone, Debug)]
+pub struct EscapingWtf8<'a> {
+    bytes: &'a [u8],
+    /// How many of the remaining bytes are to be passed through.
+    valid: usize,
+    /// How many of the remaining bytes are to be escaped.
+    invalid: usize,
+    /// The bytes of an escape that haven't been returned yet, first byte lowest.
+    escape: u32,
+}
+impl<'a> EscapingWtf8<'a> {
+    /// The bytes that haven't been decoded yet.
+    ///
+    /// If an escape sequence was only partially returned from `next()`,
+    /// the rest of it is not included.
+    pub fn as_slice(&self) -> &'a [u8] {
+        self.bytes
+    }
+}
+impl<'a> Iterator for EscapingWtf8<'a> {
+    type Item = u8;
+    fn next(&mut self) -> Option<u8> {
+        if self.escape != 0 {
+            let byte = self.escape as u8;
+            self.escape >>= 8;
+            return Some(byte);
+        }
+        let byte = match self.bytes.first() {
+            Some(&byte) => byte,
+            None => return None,
+        };
+        if self.valid == 0  &&  self.invalid == 0 {
+            match decode_start(self.bytes) {
+                Ok((_, len)) => self.valid = len,
+                Err(invalid) => self.invalid = invalid,
+            }
+        }
+        self.bytes = &self.bytes[1..];
+        if self.valid != 0 {
+            self.valid -= 1;
+            return Some(byte);
+        }
+        self.invalid -= 1;
+        let escape = 0xdc00 | byte as u32;
+        self.escape = (0x80 | (escape & 0x3f)) << 8  |  (0x80 | (escape >> 6 & 0x3f));
+        Some(0xe0 | (escape >> 12) as u8)
+    }
+    fn size_hint(&self) -> (usize,Option<usize>) {
+        let escaping = 4 - self.escape.leading_zeros() as usize / 8;
+        let len = self.bytes.len();
+        (len + escaping,  len.checked_mul(3).and_then(|max| max.checked_add(escaping) ))
+    }
+}
+
+
+/// Encode UTF-16 as UTF-8, turning escaped bytes back into the original bytes.
+///
+/// Lone surrogates in the range U+DC80..U+DCFF are replaced by the byte
+/// they stand for, while other lone surrogates produce an error.
+/// A surrogate pair is always encoded as the character it represents,
+/// even if the trailing surrogate could also be an escaped byte.
+///
+/// # Examples
+///
+#[cfg_attr(feature="std", doc=" ```")]
+#[cfg_attr(not(feature="std"), doc=" ```no_compile")]
+/// use encode_unicode::surrogateescape;
+/// use encode_unicode::error::InvalidSurrogateEscape;
+///
+/// let encoded: Result<Vec<u8>,_> = surrogateescape::encode_utf16(vec![0x41, 0xdcff, 0xe5]).collect();
+/// assert_eq!(encoded, Ok(vec![b'A', 0xff, 0xc3, 0xa5]));
+///
+/// let encoded: Result<Vec<u8>,_> = surrogateescape::encode_utf16(vec![0x41, 0xdc41]).collect();
+/// assert_eq!(encoded, Err(InvalidSurrogateEscape::UnpairedSurrogate(1)));
+/// ```
+pub fn encode_utf16<I:IntoIterator<Item=u16>>(units: I) -> UnescapingUtf16<I::IntoIter> {
+    UnescapingUtf16{ inner: units.into_iter(),  pos: 0,  buffered: None,  continuation: 0 }
+}
+
+/// The iterator type returned by `surrogateescape::encode_utf16()`
+///
+/// See its documentation for details.
+#[derive(Clone, Debug)]
+pub struct UnescapingUtf16<I:Iterator<Item=u16>> {
+    inner: I,
+    /// The index of the next unit.
+    pos: usize,
+    /// A unit that followed a leading surrogate.
+    buffered: Option<u16>,
+    /// The continuation bytes of a character, first byte lowest.
+    continuation: u32,
+}
+impl<I:Iterator<Item=u16>> UnescapingUtf16<I> {
+    /// Extracts the source iterator.
+    ///
+    /// If a character was only partially returned from `next()`,
+    /// or a unit was read to find that a surrogate is unpaired,
+    /// these are lost.
+    pub fn into_inner(self) -> I {
+        self.inner
+    }
+    /// Return the first byte of what `unit` represents,
+    /// and store any continuation bytes.
+    fn unescape(&mut self,  unit: u16) -> Result<u8,InvalidSurrogateEscape> {
+        let start = self.pos;
+        self.pos += 1;
+        let c = match unit & 0xfc00 {
+            0xd800 => match self.inner.next() {
+                Some(trail) if trail & 0xfc00 == 0xdc00 => {
+                    self.pos += 1;
+                    unsafe{ char::from_utf16_tuple_unchecked((unit, Some(trail))) }
+                },
+                next => {
+                    self.buffered = next;
+                    return Err(UnpairedSurrogate(start));
+                },
+            },
+            0xdc00 if unit & 0xff80 == 0xdc80 => return Ok(unit as u8),
+            0xdc00 => return Err(UnpairedSurrogate(start)),
+            _ => unsafe{ char::from_utf16_tuple_unchecked((unit, None)) },
+        };
+        let (bytes, _) = c.to_utf8_array();
+        self.continuation = bytes[1] as u32  |  (bytes[2] as u32) << 8  |  (bytes[3] as u32) << 16;
+        Ok(bytes[0])
+    }
+}
+impl<I:Iterator<Item=u16>> Iterator for UnescapingUtf16<I> {
+    type Item = Result<u8,InvalidSurrogateEscape>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.continuation != 0 {
+            let byte = self.continuation as u8;
+            self.continuation >>= 8;
+            return Some(Ok(byte));
+        }
+        let unit = self.buffered.take().or_else(|| self.inner.next() );
+        unit.map(|unit| self.unescape(unit) )
+    }
+    fn size_hint(&self) -> (usize,Option<usize>) {
+        // A BMP character is at most three bytes, and a pair is four.
+        let (min, max) = self.inner.size_hint();
+        let buffered = self.buffered.map_or(0, |_| 1 );
+        let continuation = 4 - self.continuation.leading_zeros() as usize / 8;
+        (min.saturating_add(buffered + continuation),
+         max.and_then(|max| max.checked_add(buffered) )
+            .and_then(|max| max.checked_mul(3) )
+            .and_then(|max| max.checked_add(continuation) )
+        )
+    }
+}
+
+
+/// Encode WTF-8 as UTF-8, turning escaped bytes back into the original bytes.
+///
+/// Lone surrogates in the range U+DC80..U+DCFF are replaced by the byte
+/// they stand for, while other lone surrogates and invalid sequences
+/// produce an error.
+///
+/// # Examples
+///
+#[cfg_attr(feature="std", doc=" ```")]
+#[cfg_attr(not(feature="std"), doc=" ```no_compile")]
+/// use encode_unicode::surrogateescape;
+/// use encode_unicode::error::InvalidSurrogateEscape;
+///
+/// let encoded: Result<Vec<u8>,_> = surrogateescape::encode_wtf8(b"\xed\xb3\xbfok").collect();
+/// assert_eq!(encoded, Ok(b"\xffok".to_vec()));
+///
+/// let encoded: Result<Vec<u8>,_> = surrogateescape::encode_wtf8(b"ok\xed\xa0\x80").collect();
+/// assert_eq!(encoded, Err(InvalidSurrogateEscape::UnpairedSurrogate(2)));
+/// let encoded: Result<Vec<u8>,_> = surrogateescape::encode_wtf8(b"\xff").collect();
+/// assert_eq!(encoded, Err(InvalidSurrogateEscape::InvalidWtf8(0)));
+/// ```
+pub fn encode_wtf8<'a>(wtf8: &'a [u8]) -> UnescapingWtf8<'a> {
+    UnescapingWtf8{ bytes: wtf8,  pos: 0,  valid: 0 }
+}
+
+/// The iterator type returned by `surrogateescape::encode_wtf8()`
+///
+/// See its documentation for details.
+#[derive(Clone, Debug)]
+pub struct UnescapingWtf8<'a> {
+    bytes: &'a [u8],
+    /// The index of the next byte.
+    pos: usize,
+    /// How many of the remaining bytes are to be passed through.
+    valid: usize,
+}
+impl<'a> UnescapingWtf8<'a> {
+    /// The bytes that haven't been encoded yet.
+    pub fn as_slice(&self) -> &'a [u8] {
+        self.bytes
+    }
+}
+impl<'a> Iterator for UnescapingWtf8<'a> {
+    type Item = Result<u8,InvalidSurrogateEscape>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let byte = match self.bytes.first() {
+            Some(&byte) => byte,
+            None => return None,
+        };
+        let start = self.pos;
+        if self.valid != 0 {
+            self.valid -= 1;
+        } else if self.bytes.len() >= 3  &&  byte == 0xed
+        &&  self.bytes[1] & 0xe0 == 0xa0  &&  self.bytes[2] >> 6 == 0b10 {
+            let surrogate = 0xd000 | (self.bytes[1] as u16 & 0x3f) << 6 | (self.bytes[2] as u16 & 0x3f);
+            self.bytes = &self.bytes[3..];
+            self.pos += 3;
+            return Some(match surrogate & 0xff80 {
+                0xdc80 => Ok(surrogate as u8),
+                _ => Err(UnpairedSurrogate(start)),
+            });
+        } else {
+            match char::from_utf8_slice_start(self.bytes) {
+                Ok((_, len)) => self.valid = len - 1,
+                Err(_) => {
+                    self.bytes = &self.bytes[1..];
+                    self.pos += 1;
+                    return Some(Err(InvalidWtf8(start)));
+                },
+            }
+        }
+        self.bytes = &self.bytes[1..];
+        self.pos += 1;
+        Some(Ok(byte))
+    }
+    fn size_hint(&self) -> (usize,Option<usize>) {
+        let len = self.bytes.len();
+        (len/3,  Some(len))
+    }
+}
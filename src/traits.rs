@@ -12,11 +12,18 @@ use utf8_char::Utf8Char;
 use utf16_char::Utf16Char;
 use utf8_iterators::*;
 use utf16_iterators::*;
+use utf8mb3::{self, Utf8mb3Replacement, Utf8mb3Chars};
 use error::*;
 extern crate core;
 use self::core::{char, u32, mem};
 use self::core::ops::Not;
 use self::core::borrow::Borrow;
+#[cfg(feature="alloc")]
+extern crate alloc;
+#[cfg(feature="alloc")]
+use self::alloc::borrow::Cow;
+#[cfg(feature="alloc")]
+use self::alloc::string::String;
 #[cfg(feature="ascii")]
 extern crate ascii;
 #[cfg(feature="ascii")]
@@ -236,6 +243,21 @@ pub trait CharExt: Sized {
 
 
 
+/// Combine the bits of an UTF-8 sequence without validating the result,
+/// so that surrogates and too high values can be rejected before they
+/// become a `char`.
+fn utf8_codepoint(src: &[u8]) -> u32 {
+    if src.len() == 1 {
+        src[0] as u32
+    } else {
+        let mut c = src[0] as u32 & (0xff >> 2+src.len()-1);
+        for b in &src[1..] {
+            c = (c << 6)  |  (b & 0b0011_1111) as u32;
+        }
+        c
+    }
+}
+
 impl CharExt for char {
       /////////
      //UTF-8//
@@ -293,8 +315,7 @@ impl CharExt for char {
         } else if overlong(bytes[0], bytes[1]) {
             Err(Utf8(OverLong))
         } else {
-            let c = unsafe{ char::from_utf8_exact_slice_unchecked(bytes) };
-            match char::from_u32_detailed(c as u32) {
+            match char::from_u32_detailed(utf8_codepoint(bytes)) {
                 Ok(c) => Ok((c, bytes.len())),
                 Err(e) => Err(Codepoint(e)),
             }
@@ -314,22 +335,13 @@ impl CharExt for char {
         } else if overlong(utf8[0], utf8[1]) {
             Err(Utf8(OverLong))
         } else {
-            let c = unsafe{ char::from_utf8_exact_slice_unchecked(src) };
-            char::from_u32_detailed(c as u32)
+            char::from_u32_detailed(utf8_codepoint(src))
                  .map_err(|e| Codepoint(e) )
         }
     }
 
     unsafe fn from_utf8_exact_slice_unchecked(src: &[u8]) -> Self {
-        if src.len() == 1 {
-            src[0] as char
-        } else {
-            let mut c = src[0] as u32 & (0xff >> 2+src.len()-1);
-            for b in &src[1..] {
-                c = (c << 6)  |  (b & 0b0011_1111) as u32;
-            }
-            unsafe{ char::from_u32_unchecked(c) }
-        }
+        unsafe{ char::from_u32_unchecked(utf8_codepoint(src)) }
     }
 
 
@@ -442,6 +454,59 @@ pub trait StrExt: AsRef<str> {
     fn utf8char_indices(&self) -> Utf8CharIndices;
     /// Equivalent to `.char_indices()` but produces `Utf16Char`s.
     fn utf16char_indices(&self) -> Utf16CharIndices;
+
+    /// Find the first character that requires four bytes in UTF-8,
+    /// and return its byte index.
+    ///
+    /// These are the characters outside the Basic Multilingual Plane,
+    /// which MySQL's legacy `utf8` (`utf8mb3`) character set rejects.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use encode_unicode::{StrExt, Utf8Char};
+    ///
+    /// assert_eq!("æøå".find_utf8mb4(), None);
+    /// assert_eq!("a💣b🂠".find_utf8mb4(), Some((1, Utf8Char::from('💣'))));
+    /// ```
+    fn find_utf8mb4(&self) -> Option<(usize,Utf8Char)> {
+        let s = self.as_ref();
+        s.bytes().position(|b| b >= 0xf0 ).map(|i| {
+            let (u8c, _) = Utf8Char::from_str_start(&s[i..]).unwrap();
+            (i, u8c)
+        })
+    }
+
+    /// Replace, escape or remove all characters that require four bytes in
+    /// UTF-8, so that the string can be stored in a MySQL `utf8` (`utf8mb3`)
+    /// column.
+    ///
+    /// Doesn't allocate if there are no such characters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use encode_unicode::{StrExt, Utf8mb3Replacement};
+    ///
+    /// let s = "bomb: 💣!";
+    /// assert_eq!(s.to_utf8mb3(Utf8mb3Replacement::ReplacementCharacter), "bomb: \u{fffd}!");
+    /// assert_eq!(s.to_utf8mb3(Utf8mb3Replacement::Escape), "bomb: \\u{1f4a3}!");
+    /// assert_eq!(s.to_utf8mb3(Utf8mb3Replacement::Remove), "bomb: !");
+    /// assert_eq!("åæø".to_utf8mb3(Utf8mb3Replacement::Remove), "åæø");
+    /// ```
+    #[cfg(feature="alloc")]
+    fn to_utf8mb3<'a>(&'a self,  replacement: Utf8mb3Replacement) -> Cow<'a,str> {
+        let s = self.as_ref();
+        match s.find_utf8mb4() {
+            None => Cow::Borrowed(s),
+            Some((first, _)) => {
+                let mut string = String::with_capacity(s.len());
+                string.push_str(&s[..first]);
+                string.extend(s[first..].utf8chars().to_utf8mb3(replacement));
+                Cow::Owned(string)
+            }
+        }
+    }
 }
 
 impl StrExt for str {
@@ -571,6 +636,26 @@ pub trait IterExt: Iterator+Sized {
     /// assert_eq!(units, flat_map);
     /// ```
     fn to_units(self) -> Utf16CharSplitter<Self::Item,Self> where Self::Item: Borrow<Utf16Char>;
+
+    /// Replaces, escapes or removes `Utf8Char`s that require four bytes,
+    /// which MySQL's legacy `utf8` (`utf8mb3`) character set cannot store.
+    ///
+    /// Combine it with `.to_bytes()` to stream the result as UTF-8.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(feature="std", doc=" ```")]
+    #[cfg_attr(not(feature="std"), doc=" ```no_compile")]
+    /// use encode_unicode::{IterExt, StrExt, Utf8mb3Replacement};
+    ///
+    /// let bytes: Vec<u8> = "💣 bomb 💣".utf8chars()
+    ///     .to_utf8mb3(Utf8mb3Replacement::Escape)
+    ///     .to_bytes()
+    ///     .collect();
+    /// assert_eq!(bytes, b"\\u{1f4a3} bomb \\u{1f4a3}");
+    /// ```
+    fn to_utf8mb3(self,  replacement: Utf8mb3Replacement) -> Utf8mb3Chars<Self::Item,Self>
+    where Self::Item: Borrow<Utf8Char>;
 }
 
 impl<I:Iterator> IterExt for I {
@@ -580,4 +665,8 @@ impl<I:Iterator> IterExt for I {
     fn to_units(self) -> Utf16CharSplitter<Self::Item,Self> where Self::Item: Borrow<Utf16Char> {
         iter_units(self)
     }
+    fn to_utf8mb3(self,  replacement: Utf8mb3Replacement) -> Utf8mb3Chars<Self::Item,Self>
+    where Self::Item: Borrow<Utf8Char> {
+        utf8mb3::to_utf8mb3(self, replacement)
+    }
 }
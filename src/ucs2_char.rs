@@ -0,0 +1,315 @@
+/* Copyright 2018 Torbjørn Birch Moltu
+ *
+ * Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+ * http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+ * http://opensource.org/licenses/MIT>, at your option. This file may not be
+ * copied, modified, or distributed except according to those terms.
+ */
+
+use utf8_char::Utf8Char;
+use utf16_char::Utf16Char;
+use traits::CharExt;
+use errors::{InvalidCodepoint, NonBmpError, Ucs2EncodeError};
+extern crate core;
+use self::core::{char, fmt, iter};
+#[cfg(feature="alloc")]
+use self::core::iter::FromIterator;
+#[cfg(feature="alloc")]
+extern crate alloc;
+#[cfg(feature="alloc")]
+use self::alloc::vec::Vec;
+
+
+/// An unicode codepoint in the Basic Multilingual Plane,
+/// stored as a single UTF-16 unit.
+///
+/// This is the UCS-2 encoding which predates UTF-16 and surrogate pairs,
+/// and which some systems (such as SIM cards and old Windows APIs) are still
+/// limited to.
+/// Unlike `Utf16Char`, it can only be created from codepoints below 0x10000,
+/// and never from a surrogate.
+#[derive(Default, Clone,Copy, PartialEq,Eq, PartialOrd,Ord, Hash)]
+pub struct Ucs2Char {
+    unit: u16,
+}
+
+/// What to do with codepoints outside the Basic Multilingual Plane when
+/// encoding as UCS-2.
+#[derive(Clone,Copy, Debug, PartialEq,Eq, Hash)]
+pub enum Ucs2Substitution {
+    /// Stop and return an error.
+    Reject,
+    /// Write this character instead.
+    Replace(Ucs2Char),
+    /// Leave it out.
+    Skip,
+}
+
+
+  /////////////////////
+ //conversion traits//
+/////////////////////
+impl From<Ucs2Char> for char {
+    fn from(uc: Ucs2Char) -> char {
+        unsafe{ char::from_u32_unchecked(uc.unit as u32) }
+    }
+}
+impl From<Ucs2Char> for Utf16Char {
+    fn from(uc: Ucs2Char) -> Utf16Char {
+        unsafe{ Utf16Char::from_tuple_unchecked((uc.unit, None)) }
+    }
+}
+impl From<Ucs2Char> for Utf8Char {
+    fn from(uc: Ucs2Char) -> Utf8Char {
+        Utf8Char::from(Utf16Char::from(uc))
+    }
+}
+impl From<Ucs2Char> for u16 {
+    fn from(uc: Ucs2Char) -> u16 {
+        uc.unit
+    }
+}
+
+#[cfg(feature="alloc")]
+impl Extend<Ucs2Char> for Vec<u16> {
+    fn extend<I:IntoIterator<Item=Ucs2Char>>(&mut self,  iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        for uc in iter {
+            self.push(uc.unit);
+        }
+    }
+}
+#[cfg(feature="alloc")]
+impl<'a> Extend<&'a Ucs2Char> for Vec<u16> {
+    fn extend<I:IntoIterator<Item=&'a Ucs2Char>>(&mut self,  iter: I) {
+        self.extend(iter.into_iter().cloned())
+    }
+}
+#[cfg(feature="alloc")]
+impl FromIterator<Ucs2Char> for Vec<u16> {
+    fn from_iter<I:IntoIterator<Item=Ucs2Char>>(iter: I) -> Self {
+        let mut vec = Vec::new();
+        vec.extend(iter);
+        vec
+    }
+}
+#[cfg(feature="alloc")]
+impl<'a> FromIterator<&'a Ucs2Char> for Vec<u16> {
+    fn from_iter<I:IntoIterator<Item=&'a Ucs2Char>>(iter: I) -> Self {
+        Self::from_iter(iter.into_iter().cloned())
+    }
+}
+
+
+  /////////////////////////////////////////////////////////
+ //Genaral traits that cannot be derived to emulate char//
+/////////////////////////////////////////////////////////
+impl fmt::Debug for Ucs2Char {
+    fn fmt(&self,  fmtr: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&self.to_char(), fmtr)
+    }
+}
+impl fmt::Display for Ucs2Char {
+    fn fmt(&self,  fmtr: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&Utf8Char::from(*self), fmtr)
+    }
+}
+
+
+  ///////////////////////////////////////////////////////
+ //pub impls that should be together for nicer rustdoc//
+///////////////////////////////////////////////////////
+impl Ucs2Char {
+    /// Create an `Ucs2Char` from a `char` in the Basic Multilingual Plane.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use encode_unicode::Ucs2Char;
+    /// use encode_unicode::error::NonBmpError;
+    ///
+    /// assert_eq!(Ucs2Char::from_char('‽').map(|uc| uc.to_unit() ), Ok(0x203d));
+    /// assert_eq!(Ucs2Char::from_char('💣'), Err(NonBmpError));
+    /// ```
+    pub fn from_char(c: char) -> Result<Self,NonBmpError> {
+        if (c as u32) <= 0xff_ff {
+            Ok(Ucs2Char{ unit: c as u16 })
+        } else {
+            Err(NonBmpError)
+        }
+    }
+    /// Create an `Ucs2Char` from an `Utf16Char` that is a single unit.
+    pub fn from_utf16char(utf16: Utf16Char) -> Result<Self,NonBmpError> {
+        match utf16.to_tuple() {
+            (first, None) => Ok(Ucs2Char{ unit: first }),
+            (_, Some(_)) => Err(NonBmpError),
+        }
+    }
+    /// Create an `Ucs2Char` from an `Utf8Char` of at most three bytes.
+    pub fn from_utf8char(utf8: Utf8Char) -> Result<Self,NonBmpError> {
+        if utf8.len() < 4 {
+            Ok(Ucs2Char{ unit: Utf16Char::from(utf8).to_tuple().0 })
+        } else {
+            Err(NonBmpError)
+        }
+    }
+    /// Validate and store an UCS-2 unit.
+    ///
+    /// # Errors
+    /// Surrogates are not valid codepoints, and return
+    /// `InvalidCodepoint::Utf16Reserved`.
+    pub fn from_unit(u: u16) -> Result<Self,InvalidCodepoint> {
+        char::from_u32_detailed(u as u32).map(|_| Ucs2Char{ unit: u } )
+    }
+    /// Create an `Ucs2Char` without checking that the unit isn't a surrogate.
+    ///
+    /// # Safety
+    /// The unit must not be in the range `0xd800..0xe000`.
+    pub unsafe fn from_unit_unchecked(u: u16) -> Self {
+        Ucs2Char{ unit: u }
+    }
+    /// U+FFFD REPLACEMENT CHARACTER, which is suitable for `Ucs2Substitution::Replace`.
+    pub fn replacement_character() -> Self {
+        Ucs2Char{ unit: 0xfffd }
+    }
+
+    /// Encode as much of a `str` as fits into `dst`, as UCS-2.
+    ///
+    /// Returns the number of bytes read from `src` and the number of units
+    /// written to `dst`. If `dst` is too short, encoding stops before the
+    /// first character that doesn't fit.
+    ///
+    /// # Errors
+    /// If `substitution` is `Reject`, encoding stops at the first codepoint
+    /// outside the Basic Multilingual Plane, and the error tells how far it got.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use encode_unicode::{Ucs2Char, Ucs2Substitution};
+    ///
+    /// let mut buf = [0; 8];
+    /// let sub = Ucs2Substitution::Replace(Ucs2Char::replacement_character());
+    /// assert_eq!(Ucs2Char::encode_str("a💣b", &mut buf, sub), Ok((6, 3)));
+    /// assert_eq!(&buf[..3], &[0x61, 0xfffd, 0x62]);
+    /// assert_eq!(Ucs2Char::encode_str("a💣b", &mut buf, Ucs2Substitution::Skip), Ok((6, 2)));
+    /// assert_eq!(Ucs2Char::encode_str("abc", &mut buf[..2], Ucs2Substitution::Skip), Ok((2, 2)));
+    ///
+    /// let err = Ucs2Char::encode_str("a💣b", &mut buf, Ucs2Substitution::Reject).unwrap_err();
+    /// assert_eq!((err.valid_up_to(), err.units_written()), (1, 1));
+    /// ```
+    pub fn encode_str(src: &str,  dst: &mut[u16],  substitution: Ucs2Substitution)
+    -> Result<(usize,usize),Ucs2EncodeError> {
+        let chars = src.char_indices().map(|(i, c)| (i, Ucs2Char::from_char(c)) );
+        encode(chars, src.len(), dst, substitution)
+    }
+    /// Encode as much of a slice of `char`s as fits into `dst`, as UCS-2.
+    ///
+    /// Works like `encode_str()`, except that positions are `char` indexes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use encode_unicode::{Ucs2Char, Ucs2Substitution};
+    ///
+    /// let mut buf = [0; 8];
+    /// let sub = Ucs2Substitution::Replace(Ucs2Char::replacement_character());
+    /// assert_eq!(Ucs2Char::encode_chars(&['a', '💣', 'b'], &mut buf, sub), Ok((3, 3)));
+    /// assert_eq!(&buf[..3], &[0x61, 0xfffd, 0x62]);
+    /// let err = Ucs2Char::encode_chars(&['a', '💣'], &mut buf, Ucs2Substitution::Reject).unwrap_err();
+    /// assert_eq!((err.valid_up_to(), err.units_written()), (1, 1));
+    /// ```
+    pub fn encode_chars(src: &[char],  dst: &mut[u16],  substitution: Ucs2Substitution)
+    -> Result<(usize,usize),Ucs2EncodeError> {
+        let chars = src.iter().enumerate().map(|(i, &c)| (i, Ucs2Char::from_char(c)) );
+        encode(chars, src.len(), dst, substitution)
+    }
+    /// Encode as much UTF-16 as fits into `dst`, as UCS-2.
+    ///
+    /// Works like `encode_str()`, except that positions are unit indexes,
+    /// and that unpaired surrogates are substituted or rejected the same way
+    /// as surrogate pairs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use encode_unicode::{Ucs2Char, Ucs2Substitution};
+    ///
+    /// let mut buf = [0; 8];
+    /// let utf16 = [0x61, 0xd83d, 0xdca3, 0xdca3, 0x62];
+    /// let sub = Ucs2Substitution::Replace(Ucs2Char::replacement_character());
+    /// assert_eq!(Ucs2Char::encode_utf16(&utf16, &mut buf, sub), Ok((5, 4)));
+    /// assert_eq!(&buf[..4], &[0x61, 0xfffd, 0xfffd, 0x62]);
+    /// assert_eq!(Ucs2Char::encode_utf16(&utf16, &mut buf, Ucs2Substitution::Skip), Ok((5, 2)));
+    /// let err = Ucs2Char::encode_utf16(&utf16[2..], &mut buf, Ucs2Substitution::Reject).unwrap_err();
+    /// assert_eq!((err.valid_up_to(), err.units_written()), (0, 0));
+    /// ```
+    pub fn encode_utf16(src: &[u16],  dst: &mut[u16],  substitution: Ucs2Substitution)
+    -> Result<(usize,usize),Ucs2EncodeError> {
+        let mut read = 0;
+        let units = iter::from_fn(|| {
+            if read == src.len() {
+                return None;
+            }
+            let start = read;
+            let result = match Utf16Char::from_slice_start(&src[start..]) {
+                Ok((u16c, len)) => {
+                    read += len;
+                    Ucs2Char::from_utf16char(u16c)
+                },
+                Err(_) => {// an unpaired surrogate
+                    read += 1;
+                    Err(NonBmpError)
+                },
+            };
+            Some((start, result))
+        });
+        encode(units, src.len(), dst, substitution)
+    }
+
+    /// The number of UTF-8 bytes this character needs.
+    ///
+    /// Is between 1 and 3 (inclusive).
+    pub fn len_utf8(self) -> usize {
+        if self.unit < 0x80 {1} else if self.unit < 0x08_00 {2} else {3}
+    }
+    /// Checks that the codepoint is an ASCII character.
+    #[inline]
+    pub fn is_ascii(&self) -> bool {
+        self.unit <= 127
+    }
+
+    /// Convert from UCS-2 to UTF-32.
+    pub fn to_char(self) -> char {
+        self.into()
+    }
+    /// Get the UTF-16 unit.
+    pub fn to_unit(self) -> u16 {
+        self.unit
+    }
+}
+
+/// The loop of the `Ucs2Char::encode_*()` methods,
+/// where `src` has the position of each codepoint and whether it's in the BMP.
+fn encode<I:Iterator<Item=(usize,Result<Ucs2Char,NonBmpError>)>>
+(src: I,  src_len: usize,  dst: &mut[u16],  substitution: Ucs2Substitution)
+-> Result<(usize,usize),Ucs2EncodeError> {
+    let mut written = 0;
+    for (read, result) in src {
+        let uc = match (result, substitution) {
+            (Ok(uc), _) => uc,
+            (Err(NonBmpError), Ucs2Substitution::Replace(uc)) => uc,
+            (Err(NonBmpError), Ucs2Substitution::Skip) => continue,
+            (Err(NonBmpError), Ucs2Substitution::Reject) => {
+                return Err(Ucs2EncodeError::new(read, written));
+            }
+        };
+        match dst.get_mut(written) {
+            Some(unit) => *unit = uc.unit,
+            None => return Ok((read, written)),
+        }
+        written += 1;
+    }
+    Ok((src_len, written))
+}
@@ -15,8 +15,12 @@ use self::core::{hash,fmt,cmp};
 use self::core::borrow::Borrow;
 use self::core::ops::Deref;
 use self::core::str::FromStr;
-#[cfg(feature="std")]
+#[cfg(feature="alloc")]
 use self::core::iter::FromIterator;
+#[cfg(feature="alloc")]
+extern crate alloc;
+#[cfg(feature="alloc")]
+use self::alloc::vec::Vec;
 #[cfg(feature="std")]
 #[allow(deprecated)]
 use std::ascii::AsciiExt;
@@ -119,7 +123,7 @@ impl IntoIterator for Utf16Char {
     }
 }
 
-#[cfg(feature="std")]
+#[cfg(feature="alloc")]
 impl Extend<Utf16Char> for Vec<u16> {
     fn extend<I:IntoIterator<Item=Utf16Char>>(&mut self,  iter: I) {
         let iter = iter.into_iter();
@@ -132,13 +136,13 @@ impl Extend<Utf16Char> for Vec<u16> {
         }
     }
 }
-#[cfg(feature="std")]
+#[cfg(feature="alloc")]
 impl<'a> Extend<&'a Utf16Char> for Vec<u16> {
     fn extend<I:IntoIterator<Item=&'a Utf16Char>>(&mut self,  iter: I) {
         self.extend(iter.into_iter().cloned())
     }
 }
-#[cfg(feature="std")]
+#[cfg(feature="alloc")]
 impl FromIterator<Utf16Char> for Vec<u16> {
     fn from_iter<I:IntoIterator<Item=Utf16Char>>(iter: I) -> Self {
         let mut vec = Vec::new();
@@ -146,7 +150,7 @@ impl FromIterator<Utf16Char> for Vec<u16> {
         return vec;
     }
 }
-#[cfg(feature="std")]
+#[cfg(feature="alloc")]
 impl<'a> FromIterator<&'a Utf16Char> for Vec<u16> {
     fn from_iter<I:IntoIterator<Item=&'a Utf16Char>>(iter: I) -> Self {
         Self::from_iter(iter.into_iter().cloned())
@@ -366,6 +370,28 @@ impl Utf16Char {
     pub unsafe fn from_tuple_unchecked(utf16: (u16,Option<u16>)) -> Self {
         Utf16Char{ units: [utf16.0, utf16.1.unwrap_or(0)] }
     }
+    /// Encode a `char` in a constant expression.
+    ///
+    /// `From<char>` is faster at run time, and the `utf16char!()` macro
+    /// guarantees that the conversion happens at compile time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use encode_unicode::Utf16Char;
+    ///
+    /// const BOMB: Utf16Char = Utf16Char::new_const('💣');
+    /// assert_eq!(BOMB.to_tuple(), (0xd83d, Some(0xdca3)));
+    /// ```
+    pub const fn new_const(c: char) -> Self {
+        let c = c as u32;
+        if c < 0x1_00_00 {
+            Utf16Char{ units: [c as u16, 0] }
+        } else {
+            let c = c - 0x1_00_00;
+            Utf16Char{ units: [0xd8_00 | (c >> 10) as u16,  0xdc_00 | (c & 0x3_ff) as u16] }
+        }
+    }
 
     /// The number of units this character is made up of.
     ///
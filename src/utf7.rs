@@ -0,0 +1,253 @@
+/* Copyright 2018 Torbjørn Birch Moltu
+ *
+ * Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+ * http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+ * http://opensource.org/licenses/MIT>, at your option. This file may not be
+ * copied, modified, or distributed except according to those terms.
+ */
+
+//! UTF-7 ([RFC 2152](https://tools.ietf.org/html/rfc2152)) and the modified
+//! UTF-7 that IMAP uses for mailbox names
+//! ([RFC 3501 section 5.1.3](https://tools.ietf.org/html/rfc3501#section-5.1.3)).
+//!
+//! Both encode characters that cannot be written directly as base64 of
+//! their UTF-16 units, but differ in which characters are written directly,
+//! which character starts a base64 sequence (`+` or `&`),
+//! and in one base64 digit (`/` or `,`).
+//!
+//! # Examples
+//!
+//! ```
+//! use encode_unicode::utf7;
+//!
+//! assert_eq!(utf7::encode("Hi Mom -☺-!"), "Hi Mom -+Jjo--+ACE");
+//! assert_eq!(utf7::decode("Hi Mom -+Jjo--!").unwrap(), "Hi Mom -☺-!");
+//!
+//! assert_eq!(utf7::encode_imap("~peter/mail/台北/日本語"), "~peter/mail/&U,BTFw-/&ZeVnLIqe-");
+//! assert_eq!(utf7::decode_imap("&Jjo-&-").unwrap(), "☺&");
+//! ```
+
+use utf16_char::Utf16Char;
+use traits::StrExt;
+use errors::InvalidUtf7;
+use errors::InvalidUtf7::*;
+extern crate alloc;
+use self::alloc::string::String;
+
+
+#[derive(Clone,Copy)]
+struct Variant {
+    shift: u8,
+    digit63: u8,
+    imap: bool,
+}
+const UTF7: Variant = Variant{ shift: b'+',  digit63: b'/',  imap: false };
+const IMAP: Variant = Variant{ shift: b'&',  digit63: b',',  imap: true };
+
+impl Variant {
+    fn is_direct(self,  u16c: Utf16Char) -> bool {
+        let unit = u16c.to_tuple().0;
+        if u16c.len() != 1  ||  unit >= 0x7f  ||  unit == self.shift as u16 {
+            false
+        } else if self.imap {
+            unit >= 0x20
+        } else {
+            // RFC 2152 set D and the allowed whitespace
+            let b = unit as u8;
+            (b | 0x20).wrapping_sub(b'a') < 26
+            || b.wrapping_sub(b'0') < 10
+            || b"'(),-./:? \t\r\n".contains(&b)
+        }
+    }
+    fn digit(self,  value: u32) -> char {
+        let value = value as u8;
+        (if value < 26 {
+            b'A' + value
+        } else if value < 52 {
+            b'a' + (value-26)
+        } else if value < 62 {
+            b'0' + (value-52)
+        } else if value == 62 {
+            b'+'
+        } else {
+            self.digit63
+        }) as char
+    }
+    fn value(self,  digit: u8) -> Option<u32> {
+        if digit.wrapping_sub(b'A') < 26 {
+            Some((digit - b'A') as u32)
+        } else if digit.wrapping_sub(b'a') < 26 {
+            Some((digit - b'a') as u32 + 26)
+        } else if digit.wrapping_sub(b'0') < 10 {
+            Some((digit - b'0') as u32 + 52)
+        } else if digit == b'+' {
+            Some(62)
+        } else if digit == self.digit63 {
+            Some(63)
+        } else {
+            None
+        }
+    }
+
+    fn encode(self,  s: &str) -> String {
+        let mut encoded = String::with_capacity(s.len());
+        let mut chars = s.utf16chars().peekable();
+        while let Some(u16c) = chars.next() {
+            if self.is_direct(u16c) {
+                encoded.push(u16c.to_char());
+                continue;
+            } else if u16c.to_tuple().0 == self.shift as u16 {
+                encoded.push(self.shift as char);
+                encoded.push('-');
+                continue;
+            }
+            encoded.push(self.shift as char);
+            let mut bits = 0u32;
+            let mut bit_count = 0;
+            let mut next = Some(u16c);
+            while let Some(u16c) = next {
+                for unit in u16c {
+                    bits = (bits << 16) | unit as u32;
+                    bit_count += 16;
+                    while bit_count >= 6 {
+                        bit_count -= 6;
+                        encoded.push(self.digit((bits >> bit_count) & 0x3f));
+                    }
+                }
+                next = match chars.peek() {
+                    Some(&u16c) if !self.is_direct(u16c)
+                                && u16c.to_tuple().0 != self.shift as u16 => Some(u16c),
+                    _ => None,
+                };
+                if next.is_some() {
+                    chars.next();
+                }
+            }
+            if bit_count != 0 {
+                encoded.push(self.digit((bits << (6-bit_count)) & 0x3f));
+            }
+            let needs_terminator = match chars.peek() {
+                _ if self.imap => true,
+                Some(&u16c) => {
+                    let unit = u16c.to_tuple().0;
+                    unit == '-' as u16  ||  (unit < 0x80 && self.value(unit as u8).is_some())
+                },
+                None => false,
+            };
+            if needs_terminator {
+                encoded.push('-');
+            }
+        }
+        encoded
+    }
+
+    fn decode(self,  s: &str) -> Result<String,InvalidUtf7> {
+        let bytes = s.as_bytes();
+        let mut decoded = String::with_capacity(bytes.len());
+        let mut i = 0;
+        while i < bytes.len() {
+            let b = bytes[i];
+            if b != self.shift {
+                if b >= 0x80  ||  (self.imap && (b < 0x20 || b == 0x7f)) {
+                    return Err(UnexpectedCharacter(i));
+                }
+                decoded.push(b as char);
+                i += 1;
+                continue;
+            }
+            let start = i;
+            i += 1;
+            if bytes.get(i) == Some(&b'-') {
+                decoded.push(self.shift as char);
+                i += 1;
+                continue;
+            }
+            let mut bits = 0u32;
+            let mut bit_count = 0;
+            let mut leading = None;
+            let mut shifted = 0;
+            while let Some(value) = bytes.get(i).and_then(|&b| self.value(b) ) {
+                bits = (bits << 6) | value;
+                bit_count += 6;
+                i += 1;
+                if bit_count < 16 {
+                    continue;
+                }
+                bit_count -= 16;
+                let unit = (bits >> bit_count) as u16;
+                bits &= (1 << bit_count) - 1;
+                shifted += 1;
+                let u16c = match (leading.take(), unit & 0xfc00) {
+                    (None, 0xd800) => {
+                        leading = Some(unit);
+                        continue;
+                    },
+                    (Some(first), _) => Utf16Char::from_tuple((first, Some(unit))),
+                    (None, _) => Utf16Char::from_tuple((unit, None)),
+                };
+                match u16c {
+                    Ok(_) if self.imap && unit.wrapping_sub(0x20) < 0x7f-0x20 => {
+                        return Err(DirectlyEncodable(start));
+                    },
+                    Ok(u16c) => decoded.push(u16c.to_char()),
+                    Err(_) => return Err(UnpairedSurrogate(start)),
+                }
+            }
+            if leading.is_some() {
+                return Err(UnpairedSurrogate(start));
+            } else if shifted == 0 {
+                return Err(EmptyShift(start));
+            } else if bit_count >= 6  ||  bits != 0 {
+                return Err(InvalidPadding(start));
+            }
+            if bytes.get(i) == Some(&b'-') {
+                i += 1;
+            } else if self.imap {
+                return Err(UnterminatedShift(i));
+            }
+        }
+        Ok(decoded)
+    }
+}
+
+
+/// Encode a string as UTF-7.
+///
+/// Only letters, digits, whitespace and the characters `'(),-./:?`
+/// are written directly; everything else is base64-encoded.
+pub fn encode(s: &str) -> String {
+    UTF7.encode(s)
+}
+
+/// Decode an UTF-7 string.
+///
+/// Any ASCII character except `+` is accepted outside of shifted sequences,
+/// and the `-` that terminates them is optional.
+pub fn decode(s: &str) -> Result<String,InvalidUtf7> {
+    UTF7.decode(s)
+}
+
+/// Encode a string as IMAP modified UTF-7.
+pub fn encode_imap(s: &str) -> String {
+    IMAP.encode(s)
+}
+
+/// Decode an IMAP modified UTF-7 string.
+///
+/// This is strict and rejects unterminated shifted sequences and
+/// base64-encoded printable ASCII characters.
+///
+/// # Examples
+///
+/// ```
+/// use encode_unicode::utf7;
+/// use encode_unicode::error::InvalidUtf7::*;
+///
+/// assert_eq!(utf7::decode_imap("INBOX/&ZeVnLIqe"), Err(UnterminatedShift(15)));
+/// assert_eq!(utf7::decode_imap("a&AGE-"), Err(DirectlyEncodable(1)));
+/// assert_eq!(utf7::decode_imap("&2D3cqA-"), Ok(String::from("💨")));
+/// assert_eq!(utf7::decode_imap("&2D0-"), Err(UnpairedSurrogate(0)));
+/// ```
+pub fn decode_imap(s: &str) -> Result<String,InvalidUtf7> {
+    IMAP.decode(s)
+}
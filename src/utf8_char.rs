@@ -6,7 +6,8 @@
  * copied, modified, or distributed except according to those terms.
  */
 
-use errors::{FromStrError, EmptyStrError, InvalidUtf8Slice, InvalidUtf8Array};
+use errors::{FromStrError, EmptyStrError, InvalidUtf8Slice, InvalidUtf8Array, InvalidEscape};
+use escape::{self, EscapeStyle, Utf8CharEscape};
 use utf8_iterators::Utf8Iterator;
 use traits::{CharExt, U8UtfExt};
 use utf16_char::Utf16Char;
@@ -15,8 +16,14 @@ use self::core::{hash, fmt, str, ptr};
 use self::core::borrow::Borrow;
 use self::core::ops::Deref;
 use self::core::mem::transmute;
-#[cfg(feature="std")]
+#[cfg(feature="alloc")]
 use self::core::iter::FromIterator;
+#[cfg(feature="alloc")]
+extern crate alloc;
+#[cfg(feature="alloc")]
+use self::alloc::vec::Vec;
+#[cfg(feature="alloc")]
+use self::alloc::string::String;
 #[cfg(feature="std")]
 #[allow(deprecated)]
 use std::ascii::AsciiExt;
@@ -126,7 +133,7 @@ impl IntoIterator for Utf8Char {
     }
 }
 
-#[cfg(feature="std")]
+#[cfg(feature="alloc")]
 impl Extend<Utf8Char> for Vec<u8> {
     fn extend<I:IntoIterator<Item=Utf8Char>>(&mut self,  iter: I) {
         let iter = iter.into_iter();
@@ -142,25 +149,25 @@ impl Extend<Utf8Char> for Vec<u8> {
         }
     }
 }
-#[cfg(feature="std")]
+#[cfg(feature="alloc")]
 impl<'a> Extend<&'a Utf8Char> for Vec<u8> {
     fn extend<I:IntoIterator<Item=&'a Utf8Char>>(&mut self,  iter: I) {
         self.extend(iter.into_iter().cloned())
     }
 }
-#[cfg(feature="std")]
+#[cfg(feature="alloc")]
 impl Extend<Utf8Char> for String {
     fn extend<I:IntoIterator<Item=Utf8Char>>(&mut self,  iter: I) {
         unsafe { self.as_mut_vec().extend(iter) }
     }
 }
-#[cfg(feature="std")]
+#[cfg(feature="alloc")]
 impl<'a> Extend<&'a Utf8Char> for String {
     fn extend<I:IntoIterator<Item=&'a Utf8Char>>(&mut self,  iter: I) {
         self.extend(iter.into_iter().cloned())
     }
 }
-#[cfg(feature="std")]
+#[cfg(feature="alloc")]
 impl FromIterator<Utf8Char> for String {
     fn from_iter<I:IntoIterator<Item=Utf8Char>>(iter: I) -> String {
         let mut string = String::new();
@@ -168,19 +175,19 @@ impl FromIterator<Utf8Char> for String {
         return string;
     }
 }
-#[cfg(feature="std")]
+#[cfg(feature="alloc")]
 impl<'a> FromIterator<&'a Utf8Char> for String {
     fn from_iter<I:IntoIterator<Item=&'a Utf8Char>>(iter: I) -> String {
         iter.into_iter().cloned().collect()
     }
 }
-#[cfg(feature="std")]
+#[cfg(feature="alloc")]
 impl FromIterator<Utf8Char> for Vec<u8> {
     fn from_iter<I:IntoIterator<Item=Utf8Char>>(iter: I) -> Self {
         iter.into_iter().collect::<String>().into_bytes()
     }
 }
-#[cfg(feature="std")]
+#[cfg(feature="alloc")]
 impl<'a> FromIterator<&'a Utf8Char> for Vec<u8> {
     fn from_iter<I:IntoIterator<Item=&'a Utf8Char>>(iter: I) -> Self {
         iter.into_iter().cloned().collect::<String>().into_bytes()
@@ -354,6 +361,65 @@ impl Utf8Char {
     pub unsafe fn from_array_unchecked(utf8: [u8;4]) -> Self {
         Utf8Char{ bytes: utf8 }
     }
+    /// Encode a `char` in a constant expression.
+    ///
+    /// `From<char>` is faster at run time, and the `utf8char!()` macro
+    /// guarantees that the conversion happens at compile time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use encode_unicode::Utf8Char;
+    ///
+    /// const E_ACUTE: Utf8Char = Utf8Char::from_char_const('é');
+    /// assert_eq!(E_ACUTE, Utf8Char::from('é'));
+    /// match Utf8Char::from('é') {
+    ///     E_ACUTE => {},
+    ///     _ => panic!("constants can be used as patterns"),
+    /// }
+    /// ```
+    pub const fn from_char_const(c: char) -> Self {
+        let c = c as u32;
+        let utf8 = if c < 0x80 {
+            [c as u8, 0, 0, 0]
+        } else if c < 0x8_00 {
+            [0xc0 | (c >> 6) as u8,  0x80 | (c & 0x3f) as u8,  0, 0]
+        } else if c < 0x1_00_00 {
+            [0xe0 | (c >> 12) as u8,  0x80 | (c >> 6 & 0x3f) as u8,  0x80 | (c & 0x3f) as u8,  0]
+        } else {
+            [0xf0 | (c >> 18) as u8,  0x80 | (c >> 12 & 0x3f) as u8,
+             0x80 | (c >> 6 & 0x3f) as u8,  0x80 | (c & 0x3f) as u8]
+        };
+        Utf8Char{ bytes: utf8 }
+    }
+    /// Parse an escape sequence at the start of a `str`,
+    /// and also return how many bytes it was.
+    ///
+    /// A `Utf16` escape of a leading surrogate must be followed by an escape
+    /// of a trailing one, and `Utf8Bytes` escapes must form a complete sequence.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use encode_unicode::{Utf8Char, EscapeStyle};
+    /// use encode_unicode::error::{InvalidEscape, InvalidCodepoint, InvalidUtf16Tuple};
+    ///
+    /// let bomb = Utf8Char::from('💣');
+    /// assert_eq!(Utf8Char::from_escape_start("\\u{1F4A3}!", EscapeStyle::Braced), Ok((bomb,9)));
+    /// assert_eq!(Utf8Char::from_escape_start("\\U0001f4a3", EscapeStyle::Universal), Ok((bomb,10)));
+    /// assert_eq!(Utf8Char::from_escape_start("\\ud83d\\udca3", EscapeStyle::Utf16), Ok((bomb,12)));
+    /// assert_eq!(Utf8Char::from_escape_start("\\xf0\\x9f\\x92\\xa3\\x21", EscapeStyle::Utf8Bytes), Ok((bomb,16)));
+    ///
+    /// assert_eq!(Utf8Char::from_escape_start("\\ud83d", EscapeStyle::Universal),
+    ///            Err(InvalidEscape::Codepoint(InvalidCodepoint::Utf16Reserved)));
+    /// assert_eq!(Utf8Char::from_escape_start("\\ud83d", EscapeStyle::Utf16),
+    ///            Err(InvalidEscape::Utf16(InvalidUtf16Tuple::MissingSecond)));
+    /// assert_eq!(Utf8Char::from_escape_start("\\x{41}", EscapeStyle::Braced), Err(InvalidEscape::NotAnEscape));
+    /// assert_eq!(Utf8Char::from_escape_start("\\u{}", EscapeStyle::Braced), Err(InvalidEscape::Malformed));
+    /// ```
+    pub fn from_escape_start(src: &str,  style: EscapeStyle) -> Result<(Self,usize),InvalidEscape> {
+        escape::from_escape_start(src, style)
+    }
 
     /// The number of bytes this character needs.
     ///
@@ -463,4 +529,23 @@ impl Utf8Char {
     pub fn as_str(&self) -> &str {
         self.deref()
     }
+    /// Iterate over the escape sequence for this codepoint in a chosen style.
+    ///
+    /// The codepoint is always escaped, even if it's printable ASCII.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use encode_unicode::{Utf8Char, EscapeStyle};
+    ///
+    /// let bomb = Utf8Char::from('💣');
+    /// assert_eq!(bomb.escape(EscapeStyle::Braced).to_string(), "\\u{1f4a3}");
+    /// assert_eq!(bomb.escape(EscapeStyle::Universal).to_string(), "\\U0001f4a3");
+    /// assert_eq!(bomb.escape(EscapeStyle::Utf16).to_string(), "\\ud83d\\udca3");
+    /// assert_eq!(bomb.escape(EscapeStyle::Utf8Bytes).to_string(), "\\xf0\\x9f\\x92\\xa3");
+    /// assert_eq!(Utf8Char::from('A').escape(EscapeStyle::Universal).collect::<String>(), "\\u0041");
+    /// ```
+    pub fn escape(self,  style: EscapeStyle) -> Utf8CharEscape {
+        escape::escape(self, style)
+    }
 }
@@ -0,0 +1,108 @@
+/* Copyright 2018 Torbjørn Birch Moltu
+ *
+ * Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+ * http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+ * http://opensource.org/licenses/MIT>, at your option. This file may not be
+ * copied, modified, or distributed except according to those terms.
+ */
+
+//! Removing the four-byte characters that MySQL's legacy `utf8` can't store.
+
+use utf8_char::Utf8Char;
+extern crate core;
+use self::core::char::EscapeUnicode;
+use self::core::borrow::Borrow;
+use self::core::fmt;
+
+
+/// What to do with characters that need four bytes in UTF-8.
+///
+/// MySQL's `utf8` (also known as `utf8mb3`) character set only supports
+/// sequences of up to three bytes, which means that characters outside the
+/// Basic Multilingual Plane, such as most emoji, are rejected.
+#[derive(Clone,Copy, Debug, PartialEq,Eq, Hash)]
+pub enum Utf8mb3Replacement {
+    /// Replace each such character with U+FFFD REPLACEMENT CHARACTER.
+    ReplacementCharacter,
+    /// Replace each such character with a `\u{1f4a3}` escape,
+    /// as produced by `char.escape_unicode()`.
+    Escape,
+    /// Drop these characters.
+    Remove,
+}
+
+
+// Is not re-exported, `IterExt::to_utf8mb3()` is the public constructor.
+pub fn to_utf8mb3<U:Borrow<Utf8Char>, I:Iterator<Item=U>>
+(iter: I,  replace_with: Utf8mb3Replacement) -> Utf8mb3Chars<U,I> {
+    Utf8mb3Chars{ inner: iter,  replacement: replace_with,  escape: None }
+}
+
+/// The iterator type returned by `IterExt::to_utf8mb3()`
+///
+/// See its documentation for details.
+#[derive(Clone)]
+pub struct Utf8mb3Chars<U:Borrow<Utf8Char>, I:Iterator<Item=U>> {
+    inner: I,
+    replacement: Utf8mb3Replacement,
+    escape: Option<EscapeUnicode>,
+}
+impl<U:Borrow<Utf8Char>, I:Iterator<Item=U>> Utf8mb3Chars<U,I> {
+    /// Extracts the source iterator.
+    ///
+    /// If an escape sequence was only partially returned from `next()`,
+    /// the rest of it is lost.
+    pub fn into_inner(self) -> I {
+        self.inner
+    }
+}
+impl<U:Borrow<Utf8Char>, I:Iterator<Item=U>> Iterator for Utf8mb3Chars<U,I> {
+    type Item = Utf8Char;
+    fn next(&mut self) -> Option<Utf8Char> {
+        if let Some(c) = self.escape.as_mut().and_then(|escape| escape.next() ) {
+            return Some(Utf8Char::from(c));
+        }
+        self.escape = None;
+        for u8c in self.inner.by_ref() {
+            let u8c = *u8c.borrow();
+            if u8c.len() != 4 {
+                return Some(u8c);
+            }
+            match self.replacement {
+                Utf8mb3Replacement::ReplacementCharacter => {
+                    return Some(Utf8Char::from('\u{fffd}'));
+                },
+                Utf8mb3Replacement::Escape => {
+                    let mut escape = u8c.to_char().escape_unicode();
+                    let first = escape.next().map(Utf8Char::from);
+                    self.escape = Some(escape);
+                    return first;
+                },
+                Utf8mb3Replacement::Remove => {},
+            }
+        }
+        None
+    }
+    fn size_hint(&self) -> (usize,Option<usize>) {
+        // An escape is at most 10 characters: `\u{10ffff}`
+        let (min, max) = self.inner.size_hint();
+        let escaping = self.escape.as_ref().map_or(0, |escape| escape.len() );
+        let min = match self.replacement {
+            Utf8mb3Replacement::Remove => 0,
+            _ => min,
+        };
+        let max = match self.replacement {
+            Utf8mb3Replacement::Escape => max.and_then(|max| max.checked_mul(10) ),
+            _ => max,
+        };
+        (min.saturating_add(escaping),  max.and_then(|max| max.checked_add(escaping) ))
+    }
+}
+impl<U:Borrow<Utf8Char>, I:Iterator<Item=U>+fmt::Debug> fmt::Debug for Utf8mb3Chars<U,I> {
+    fn fmt(&self,  fmtr: &mut fmt::Formatter) -> fmt::Result {
+        fmtr.debug_struct("Utf8mb3Chars")
+            .field("inner", &self.inner)
+            .field("replacement", &self.replacement)
+            .finish()
+    }
+}
@@ -0,0 +1,113 @@
+/* Copyright 2018 Torbjørn Birch Moltu
+ *
+ * Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+ * http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+ * http://opensource.org/licenses/MIT>, at your option. This file may not be
+ * copied, modified, or distributed except according to those terms.
+ */
+
+//! Tests for the fixed-capacity string types.
+
+#![cfg(feature="std")]
+
+extern crate encode_unicode;
+use encode_unicode::{ArrayUtf8String, ArrayUtf16String, Utf8Char, Utf16Char, StrExt};
+use encode_unicode::error::CapacityError;
+use std::fmt::Write;
+
+#[test]
+fn utf8_push() {
+    let mut s = ArrayUtf8String::<4>::new();
+    assert!(s.is_empty());
+    assert_eq!(s.capacity(), 4);
+    s.push(Utf8Char::from('a')).unwrap();
+    s.push(Utf8Char::from('æ')).unwrap();
+    assert_eq!(s.push(Utf8Char::from('€')), Err(CapacityError));
+    assert_eq!(s, "aæ");
+    s.push(Utf8Char::from('b')).unwrap();
+    assert_eq!(s.remaining_capacity(), 0);
+    assert_eq!(s.push(Utf8Char::from('c')), Err(CapacityError));
+    assert_eq!(s.pop(), Some(Utf8Char::from('b')));
+    assert_eq!(s.pop(), Some(Utf8Char::from('æ')));
+    assert_eq!(s.as_bytes(), b"a");
+    s.clear();
+    assert_eq!(s.pop(), None);
+}
+
+#[test]
+fn utf8_push_str() {
+    let mut s: ArrayUtf8String<5> = "ab".parse().unwrap();
+    assert_eq!(s.push_str("c💣"), Err(CapacityError));
+    assert_eq!(s, "ab", "unchanged after an error");
+    s.push_str("cd").unwrap();
+    assert_eq!("abcdef".parse::<ArrayUtf8String<5>>(), Err(CapacityError));
+    assert_eq!(ArrayUtf8String::<0>::new().push_str(""), Ok(()));
+}
+
+#[test]
+fn utf8_write() {
+    let mut s = ArrayUtf8String::<8>::new();
+    write!(s, "12-ø").unwrap();
+    assert_eq!(s.to_string(), "12-ø");
+    assert!(write!(s, "long").is_err());
+    assert_eq!(format!("{:?}", s), "\"12-ø\"");
+    assert_eq!(s.to_uppercase(), "12-Ø", "derefs to str");
+}
+
+#[test]
+fn utf8_extend() {
+    let chars: Vec<Utf8Char> = "hello".utf8chars().collect();
+    let s: ArrayUtf8String<5> = chars.iter().collect();
+    assert_eq!(s, "hello");
+    let mut s = ArrayUtf8String::<6>::new();
+    s.extend(chars.iter().cloned());
+    assert_eq!(s.try_extend(&chars), Err(CapacityError));
+    assert_eq!(s, "helloh", "keeps what fit");
+    assert_eq!(ArrayUtf8String::<4>::try_from_iter(&chars), Err(CapacityError));
+    let copy = s;
+    assert_eq!(copy, ArrayUtf8String::<16>::try_from_iter(chars.iter().chain(&chars[..1])).unwrap());
+}
+
+#[test]
+#[should_panic]
+fn utf8_collect_overflow() {
+    let _: ArrayUtf8String<4> = "hello".utf8chars().collect();
+}
+
+#[test]
+fn utf16_push() {
+    let mut s = ArrayUtf16String::<3>::new();
+    s.push(Utf16Char::from('a')).unwrap();
+    s.push(Utf16Char::from('💣')).unwrap();
+    assert_eq!(s.push(Utf16Char::from('b')), Err(CapacityError));
+    assert_eq!(&*s, &[0x61, 0xd83d, 0xdca3]);
+    assert_eq!(s.pop(), Some(Utf16Char::from('💣')));
+    assert_eq!(s.push(Utf16Char::from('💣')), Ok(()));
+    s.clear();
+    assert!(s.is_empty());
+    assert_eq!(s.pop(), None);
+}
+
+#[test]
+fn utf16_push_str() {
+    let mut s: ArrayUtf16String<4> = "æ".parse().unwrap();
+    assert_eq!(s.push_str("ab💣"), Err(CapacityError));
+    assert_eq!(s.as_slice(), &[0xe6], "unchanged after an error");
+    s.push_str("💣").unwrap();
+    assert_eq!(s.to_string(), "æ💣");
+    assert_eq!(format!("{:?}", s), "\"æ💣\"");
+    assert!(write!(s, "ab").is_err());
+    write!(s, "a").unwrap();
+    assert_eq!(s.len(), 4);
+}
+
+#[test]
+fn utf16_extend() {
+    let chars: Vec<Utf16Char> = "a💣b".utf16chars().collect();
+    let s: ArrayUtf16String<4> = chars.iter().collect();
+    assert_eq!(s.to_string(), "a💣b");
+    let mut s = ArrayUtf16String::<2>::new();
+    assert_eq!(s.try_extend(chars.iter().cloned()), Err(CapacityError));
+    assert_eq!(s.to_string(), "a");
+    assert_eq!(s, ArrayUtf16String::<8>::try_from_iter(&chars[..1]).unwrap());
+}
@@ -0,0 +1,144 @@
+/* Copyright 2018 Torbjørn Birch Moltu
+ *
+ * Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+ * http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+ * http://opensource.org/licenses/MIT>, at your option. This file may not be
+ * copied, modified, or distributed except according to those terms.
+ */
+
+//! Tests for the C API, run with `cargo test --features capi`.
+
+#![cfg(feature="capi")]
+
+extern crate encode_unicode;
+use encode_unicode::capi::*;
+use encode_unicode::capi::Status::*;
+use std::ptr;
+
+static HEADER: &str = include_str!("../include/encode_unicode.h");
+
+#[test]
+fn header_matches() {
+    let statuses = [
+        ("EU_STATUS_OK", Ok), ("EU_STATUS_TOO_LONG_SEQUENCE", TooLongSequence),
+        ("EU_STATUS_CONTINUATION_BYTE", ContinuationByte), ("EU_STATUS_NOT_A_CONTINUATION_BYTE", NotAContinuationByte),
+        ("EU_STATUS_OVER_LONG", OverLong), ("EU_STATUS_UTF16_RESERVED", Utf16Reserved), ("EU_STATUS_TOO_HIGH", TooHigh),
+        ("EU_STATUS_TOO_SHORT", TooShort), ("EU_STATUS_FIRST_LOW_SURROGATE", FirstLowSurrogate),
+        ("EU_STATUS_MISSING_SECOND", MissingSecond), ("EU_STATUS_SECOND_NOT_LOW_SURROGATE", SecondNotLowSurrogate),
+        ("EU_STATUS_BUFFER_TOO_SMALL", BufferTooSmall), ("EU_STATUS_NULL_POINTER", NullPointer),
+    ];
+    for &(name, status) in &statuses {
+        let declaration = format!("    {} = {}", name, status as i32);
+        assert!(HEADER.contains(&declaration), "{} is missing", declaration);
+    }
+    assert_eq!(HEADER.matches(" = ").count(), statuses.len());
+    let functions = ["eu_utf8_decode(", "eu_utf16_decode(", "eu_utf8_validate(", "eu_utf16_validate(",
+                     "eu_utf8_to_utf16(", "eu_utf16_to_utf8("];
+    for function in &functions {
+        assert!(HEADER.contains(&format!("enum eu_status {}", function)), "{} is missing", function);
+    }
+    assert_eq!(HEADER.matches("enum eu_status eu_").count(), functions.len());
+}
+
+/// Compile a C program that includes the header and link it with the
+/// static library built by `capi/`, so that the declarations must match what
+/// is exported.
+#[test]
+#[cfg(unix)]
+fn header_compiles() {
+    use std::env;
+    use std::path::Path;
+    use std::process::Command;
+    let cc = env::var("CC").unwrap_or_else(|_| String::from("cc") );
+    if Command::new(&cc).arg("--version").output().is_err() {
+        eprintln!("skipping because there is no C compiler {:?}", cc);
+        return;
+    }
+    let manifest = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("capi");
+    let cargo = env::var("CARGO").unwrap_or_else(|_| String::from("cargo") );
+    let built = Command::new(cargo)
+        .arg("build").arg("--manifest-path").arg(manifest.join("capi/Cargo.toml"))
+        .arg("--target-dir").arg(dir.join("target"))
+        .output().unwrap();
+    assert!(built.status.success(), "{}", String::from_utf8_lossy(&built.stderr));
+    let exe = dir.join("header");
+    let compiled = Command::new(&cc)
+        .args(["-std=c99", "-Wall", "-Werror", "-I"]).arg(manifest.join("include"))
+        .arg(manifest.join("tests/capi_header.c"))
+        .arg(dir.join("target/debug/libencode_unicode.a"))
+        .args(["-lpthread", "-ldl", "-lm", "-o"]).arg(&exe)
+        .status().unwrap();
+    assert!(compiled.success());
+    assert_eq!(Command::new(&exe).status().unwrap().code(), Some(0), "see tests/capi_header.c");
+}
+
+#[test]
+fn decode() {
+    let (mut c, mut n) = (0, 0);
+    unsafe {
+        assert_eq!(eu_utf8_decode(b"\xf0\x9f\x92\xa3!".as_ptr(), 5, &mut c, &mut n), Ok);
+        assert_eq!((c, n), (0x1f4a3, 4));
+        assert_eq!(eu_utf8_decode(b"\xe2\x82".as_ptr(), 2, &mut c, &mut n), TooShort);
+        assert_eq!(n, 3);
+        assert_eq!(eu_utf8_decode(b"\xe2\x82!".as_ptr(), 3, &mut c, &mut n), NotAContinuationByte);
+        assert_eq!(n, 2);
+        assert_eq!(eu_utf8_decode(b"\xc0\xae".as_ptr(), 2, &mut c, &mut n), OverLong);
+        assert_eq!(eu_utf8_decode(b"\x80".as_ptr(), 1, &mut c, &mut n), ContinuationByte);
+        assert_eq!(eu_utf8_decode(b"\xf8".as_ptr(), 1, &mut c, &mut n), TooLongSequence);
+        assert_eq!(eu_utf8_decode(b"\xed\xa0\x80".as_ptr(), 3, &mut c, &mut n), Utf16Reserved);
+        assert_eq!(eu_utf8_decode(b"\xf4\x90\x80\x80".as_ptr(), 4, &mut c, &mut n), TooHigh);
+        assert_eq!(eu_utf8_decode(ptr::null(), 0, &mut c, &mut n), TooShort);
+        assert_eq!(eu_utf8_decode(ptr::null(), 1, &mut c, &mut n), NullPointer);
+        assert_eq!(eu_utf8_decode(b"a".as_ptr(), 1, ptr::null_mut(), &mut n), NullPointer);
+
+        assert_eq!(eu_utf16_decode([0xd83d, 0xdca3].as_ptr(), 2, &mut c, &mut n), Ok);
+        assert_eq!((c, n), (0x1f4a3, 2));
+        assert_eq!(eu_utf16_decode([0xdca3].as_ptr(), 1, &mut c, &mut n), FirstLowSurrogate);
+        assert_eq!(eu_utf16_decode([0xd83d].as_ptr(), 1, &mut c, &mut n), MissingSecond);
+        assert_eq!(eu_utf16_decode([0xd83d, 0x41].as_ptr(), 2, &mut c, &mut n), SecondNotLowSurrogate);
+        assert_eq!(eu_utf16_decode(ptr::null(), 0, &mut c, &mut n), TooShort);
+    }
+}
+
+#[test]
+fn validate() {
+    let mut valid = 99;
+    unsafe {
+        assert_eq!(eu_utf8_validate("æ💣".as_ptr(), 6, &mut valid), Ok);
+        assert_eq!(valid, 6);
+        assert_eq!(eu_utf8_validate(b"ab\xc3\xa6\xc3".as_ptr(), 5, &mut valid), TooShort);
+        assert_eq!(valid, 4);
+        assert_eq!(eu_utf16_validate([0x41, 0xd83d, 0xdca3, 0xdca3].as_ptr(), 4, &mut valid), FirstLowSurrogate);
+        assert_eq!(valid, 3);
+        assert_eq!(eu_utf16_validate(ptr::null(), 0, &mut valid), Ok);
+        assert_eq!(valid, 0);
+    }
+}
+
+#[test]
+fn transcode() {
+    let (mut read, mut written) = (0, 0);
+    let mut utf16 = [0u16; 4];
+    let mut utf8 = [0u8; 8];
+    unsafe {
+        let src = "aæ💣";
+        assert_eq!(eu_utf8_to_utf16(src.as_ptr(), src.len(), utf16.as_mut_ptr(), 4, &mut read, &mut written), Ok);
+        assert_eq!((read, written), (7, 4));
+        assert_eq!(utf16, [0x61, 0xe6, 0xd83d, 0xdca3]);
+        assert_eq!(eu_utf8_to_utf16(src.as_ptr(), src.len(), utf16.as_mut_ptr(), 3, &mut read, &mut written), BufferTooSmall);
+        assert_eq!((read, written), (3, 2));
+        assert_eq!(eu_utf8_to_utf16(b"a\xff".as_ptr(), 2, utf16.as_mut_ptr(), 4, &mut read, &mut written), TooLongSequence);
+        assert_eq!((read, written), (1, 1));
+        assert_eq!(eu_utf8_to_utf16(src.as_ptr(), src.len(), ptr::null_mut(), 0, &mut read, &mut written), BufferTooSmall);
+
+        assert_eq!(eu_utf16_to_utf8(utf16.as_ptr(), 4, utf8.as_mut_ptr(), 8, &mut read, &mut written), Ok);
+        assert_eq!((read, written), (4, 7));
+        assert_eq!(&utf8[..7], src.as_bytes());
+        assert_eq!(eu_utf16_to_utf8(utf16.as_ptr(), 4, utf8.as_mut_ptr(), 6, &mut read, &mut written), BufferTooSmall);
+        assert_eq!((read, written), (2, 3));
+        assert_eq!(eu_utf16_to_utf8(utf16.as_ptr(), 3, utf8.as_mut_ptr(), 8, &mut read, &mut written), MissingSecond);
+        assert_eq!((read, written), (2, 3));
+        assert_eq!(eu_utf16_to_utf8(utf16.as_ptr(), 4, ptr::null_mut(), 8, &mut read, &mut written), NullPointer);
+    }
+}
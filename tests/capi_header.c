@@ -0,0 +1,48 @@
+/* Copyright 2018 Torbjørn Birch Moltu
+ *
+ * Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+ * http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+ * http://opensource.org/licenses/MIT>, at your option. This file may not be
+ * copied, modified, or distributed except according to those terms.
+ */
+
+/* Calls every function through include/encode_unicode.h,
+ * linked against the static library from capi/ by the header_compiles test in capi.rs.
+ * Returns the line of the first failed check. */
+
+#include <string.h>
+#include "encode_unicode.h"
+
+#define CHECK(condition) if (!(condition)) return __LINE__
+
+int main(void) {
+    uint32_t codepoint = 0;
+    size_t consumed = 0, valid_up_to = 0, read = 0, written = 0;
+    const uint8_t utf8[] = "a\xc3\xa6\xf0\x9f\x92\xa3";
+    const uint16_t utf16[] = {0x61, 0xe6, 0xd83d, 0xdca3};
+    uint16_t utf16_dst[4];
+    uint8_t utf8_dst[8];
+
+    CHECK(eu_utf8_decode(utf8 + 3, 4, &codepoint, &consumed) == EU_STATUS_OK);
+    CHECK(codepoint == 0x1f4a3  &&  consumed == 4);
+    CHECK(eu_utf8_decode(utf8 + 3, 2, &codepoint, &consumed) == EU_STATUS_TOO_SHORT);
+    CHECK(eu_utf8_decode(NULL, 1, &codepoint, &consumed) == EU_STATUS_NULL_POINTER);
+    CHECK(eu_utf16_decode(utf16 + 2, 2, &codepoint, &consumed) == EU_STATUS_OK);
+    CHECK(codepoint == 0x1f4a3  &&  consumed == 2);
+    CHECK(eu_utf16_decode(utf16 + 3, 1, &codepoint, &consumed) == EU_STATUS_FIRST_LOW_SURROGATE);
+
+    CHECK(eu_utf8_validate(utf8, 6, &valid_up_to) == EU_STATUS_TOO_SHORT);
+    CHECK(valid_up_to == 3);
+    CHECK(eu_utf16_validate(utf16, 3, &valid_up_to) == EU_STATUS_MISSING_SECOND);
+    CHECK(valid_up_to == 2);
+
+    CHECK(eu_utf8_to_utf16(utf8, 7, utf16_dst, 4, &read, &written) == EU_STATUS_OK);
+    CHECK(read == 7  &&  written == 4);
+    CHECK(memcmp(utf16_dst, utf16, sizeof utf16) == 0);
+    CHECK(eu_utf16_to_utf8(utf16, 4, utf8_dst, 6, &read, &written) == EU_STATUS_BUFFER_TOO_SMALL);
+    CHECK(read == 2  &&  written == 3);
+    CHECK(eu_utf16_to_utf8(utf16, 4, utf8_dst, 8, &read, &written) == EU_STATUS_OK);
+    CHECK(read == 4  &&  written == 7);
+    CHECK(memcmp(utf8_dst, utf8, 7) == 0);
+    return 0;
+}
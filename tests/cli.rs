@@ -0,0 +1,156 @@
+/* Copyright 2018 Torbjørn Birch Moltu
+ *
+ * Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+ * http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+ * http://opensource.org/licenses/MIT>, at your option. This file may not be
+ * copied, modified, or distributed except according to those terms.
+ */
+
+//! Tests for the `encode-unicode` binary.
+
+#![cfg(feature="std")]
+
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+fn run(args: &[&str],  stdin: &[u8]) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_encode-unicode"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(stdin).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn inspect() {
+    let output = run(&["inspect"], b"A\xc3\xa6\xf0\x9f\x92\xa3\n\xff\xc0\xae\xe2\x82");
+    assert!(output.status.success());
+    let expected = "  offset  codepoint  UTF-8        UTF-16     ASCII  char
+       0  U+0041     41           0041       yes    'A'
+       1  U+00E6     c3 a6        00e6       no     'æ'
+       3  U+1F4A3    f0 9f 92 a3  d83d dca3  no     '💣'
+       7  U+000A     0a           000a       yes    '\\n'
+       8  invalid    ff                             Utf8(FirstByte(TooLongSeqence))
+       9  invalid    c0 ae                          Utf8(OverLong)
+      11  invalid    e2 82                          TooShort(3)
+";
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), expected);
+}
+
+#[test]
+fn inspect_file() {
+    let from_stdin = run(&["inspect", "-"], include_bytes!("../README.md"));
+    let from_file = run(&["inspect", concat!(env!("CARGO_MANIFEST_DIR"), "/README.md")], b"");
+    assert!(from_file.status.success());
+    assert_eq!(from_stdin.stdout, from_file.stdout);
+    let missing = run(&["inspect", "does/not/exist"], b"");
+    assert_eq!(missing.status.code(), Some(1));
+    assert!(missing.stdout.is_empty());
+}
+
+#[test]
+fn usage() {
+    let invalid: &[&[&str]] = &[
+        &[], &["inspect", "a", "b"], &["nonsense"], &["transcode", "a", "b"],
+        &["validate", "--encoding"], &["transcode", "--to", "latin1"], &["validate", "--lossy"],
+    ];
+    for args in invalid {
+        let output = run(args, b"");
+        assert_eq!(output.status.code(), Some(2));
+        assert!(String::from_utf8(output.stderr).unwrap().contains("Usage: "));
+    }
+}
+
+#[test]
+fn validate() {
+    let valid = run(&["validate"], "blåbær\n".as_bytes());
+    assert!(valid.status.success());
+    assert!(valid.stderr.is_empty());
+
+    let output = run(&["validate", "-"], b"ab\r\nc\td\xc0\xaee\xff\n");
+    assert_eq!(output.status.code(), Some(1));
+    let expected = "<stdin>:2:4: error: the sequence contains too many zeros and could be shorter (at byte 7)
+  |
+2 | c\td\u{fffd}e\u{fffd}
+  |  \t ^
+<stdin>:2:6: error: the first byte is greater than 239 (UTF-8 sequences cannot be longer than four bytes) (at byte 10)
+  |
+2 | c\td\u{fffd}e\u{fffd}
+  |  \t   ^
+";
+    assert_eq!(String::from_utf8(output.stderr).unwrap(), expected);
+}
+
+#[test]
+fn validate_utf16() {
+    let output = run(&["validate", "--encoding", "UTF-16BE"], b"\x00a\x00\n\xdc\x00\x00b\x00");
+    assert_eq!(output.status.code(), Some(1));
+    let expected = "<stdin>:2:1: error: the first unit is a trailing surrogate (at byte 4)
+  |
+2 | \u{fffd}b\u{fffd}
+  | ^
+<stdin>:2:3: error: the input ends in the middle of a unit (at byte 8)
+  |
+2 | \u{fffd}b\u{fffd}
+  |   ^
+";
+    assert_eq!(String::from_utf8(output.stderr).unwrap(), expected);
+}
+
+#[test]
+fn validate_files() {
+    let readme = concat!(env!("CARGO_MANIFEST_DIR"), "/README.md");
+    assert!(run(&["validate", readme, readme], b"").status.success());
+    let missing = run(&["validate", readme, "does/not/exist"], b"");
+    assert_eq!(missing.status.code(), Some(1));
+    assert!(String::from_utf8(missing.stderr).unwrap().starts_with("encode-unicode: does/not/exist: "));
+}
+
+#[test]
+fn transcode() {
+    let text = "A\u{e6}\u{1f4a3}";
+    let utf16be = run(&["transcode", "--to", "utf-16be"], text.as_bytes());
+    assert!(utf16be.status.success());
+    assert_eq!(utf16be.stdout, b"\x00A\x00\xe6\xd8\x3d\xdc\xa3");
+    let utf32le = run(&["transcode", "--from", "utf-16be", "--to", "utf-32le"], &utf16be.stdout);
+    assert_eq!(utf32le.stdout, b"A\0\0\0\xe6\0\0\0\xa3\xf4\x01\0");
+    let utf8 = run(&["transcode", "--from", "utf32le"], &utf32le.stdout);
+    assert_eq!(utf8.stdout, text.as_bytes());
+}
+
+#[test]
+fn transcode_invalid() {
+    let strict = run(&["transcode", "--to", "utf-16le"], b"a\xe2\x82b");
+    assert_eq!(strict.status.code(), Some(1));
+    assert!(strict.stdout.is_empty());
+    assert!(String::from_utf8(strict.stderr).unwrap().starts_with("<stdin>:1:2: error: "));
+
+    let lossy = run(&["transcode", "--lossy", "--to", "utf-16le"], b"a\xe2\x82b");
+    assert!(lossy.status.success());
+    assert_eq!(lossy.stdout, b"a\0\xfd\xff\x62\0");
+    let utf32 = run(&["transcode", "--lossy", "--from", "utf-32be"], b"\0\0\xd8\0\0\0\0A\0");
+    assert_eq!(utf32.stdout, "\u{fffd}A\u{fffd}".as_bytes());
+}
+
+#[test]
+fn truncated_sequence_before_valid() {
+    let output = run(&["inspect"], b"a\xe2A\xf0\x9f\x92");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().skip(1).collect();
+    assert_eq!(lines, [
+        "       0  U+0061     61           0061       yes    'a'",
+        "       1  invalid    e2                             Utf8(NotAContinuationByte(1))",
+        "       2  U+0041     41           0041       yes    'A'",
+        "       3  invalid    f0 9f 92                       TooShort(4)",
+    ]);
+
+    let lossy = run(&["transcode", "--lossy"], b"a\xe2A\xe2\x82");
+    assert!(lossy.status.success());
+    assert_eq!(lossy.stdout, "a\u{fffd}A\u{fffd}".as_bytes());
+    let strict = run(&["validate"], b"a\xe2A");
+    assert!(String::from_utf8(strict.stderr).unwrap().contains("\n1 | a\u{fffd}A\n"));
+}
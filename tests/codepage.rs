@@ -0,0 +1,118 @@
+/* Copyright 2018 Torbjørn Birch Moltu
+ *
+ * Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+ * http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+ * http://opensource.org/licenses/MIT>, at your option. This file may not be
+ * copied, modified, or distributed except according to those terms.
+ */
+
+//! Tests for the single-byte legacy code pages.
+
+#![cfg(feature="std")]
+
+extern crate encode_unicode;
+use encode_unicode::{CodePage, Utf8Char, Utf16Char};
+use encode_unicode::CodePage::*;
+use encode_unicode::error::Unmappable;
+use std::io::{Read, ErrorKind};
+
+const ALL: &[CodePage] = &[
+    Latin1, Windows1252, Iso8859_2, Iso8859_3, Iso8859_4, Iso8859_5, Iso8859_6, Iso8859_7,
+    Iso8859_8, Iso8859_9, Iso8859_10, Iso8859_11, Iso8859_13, Iso8859_14, Iso8859_15, Iso8859_16,
+];
+
+#[test]
+fn roundtrips() {
+    for &code_page in ALL {
+        for byte in 0..256u16 {
+            let byte = byte as u8;
+            match code_page.decode(byte) {
+                Ok(u8c) => {
+                    assert_eq!(code_page.encode(u8c), Ok(byte), "{:?} {:?}", code_page, u8c);
+                    assert_eq!(code_page.decode_utf16(byte), Ok(Utf16Char::from(u8c)));
+                },
+                Err(e) => {
+                    assert_eq!(e, Unmappable::Byte(byte));
+                    assert_eq!(code_page.decode_utf16(byte), Err(e));
+                },
+            }
+        }
+    }
+}
+
+#[test]
+fn ascii_is_unchanged() {
+    for &code_page in ALL {
+        for byte in 0..128 {
+            assert_eq!(code_page.decode(byte), Ok(Utf8Char::from(byte as char)));
+            assert_eq!(code_page.encode(byte as char), Ok(byte));
+        }
+    }
+}
+
+#[test]
+fn known_characters() {
+    assert_eq!(Windows1252.decode(0x93), Ok(Utf8Char::from('“')));
+    assert_eq!(Windows1252.decode(0x9f), Ok(Utf8Char::from('Ÿ')));
+    assert_eq!(Windows1252.decode(0xe6), Ok(Utf8Char::from('æ')));
+    assert_eq!(Iso8859_2.decode(0xb9), Ok(Utf8Char::from('š')));
+    assert_eq!(Iso8859_7.decode(0xe1), Ok(Utf8Char::from('α')));
+    assert_eq!(Iso8859_15.decode(0xa4), Ok(Utf8Char::from('€')));
+    assert_eq!(Iso8859_11.decode(0xa1), Ok(Utf8Char::from('ก')));
+    assert_eq!(Iso8859_6.decode(0xa1), Err(Unmappable::Byte(0xa1)));
+    assert_eq!(Iso8859_2.encode(Utf16Char::from('ł')), Ok(0xb3));
+    assert_eq!(Latin1.encode('\u{ff}'), Ok(0xff));
+    assert_eq!(Latin1.encode('\u{100}'), Err(Unmappable::Char('\u{100}')));
+    assert_eq!(Windows1252.encode('\u{81}'), Err(Unmappable::Char('\u{81}')));
+    assert_eq!(Iso8859_5.encode('💣'), Err(Unmappable::Char('💣')));
+}
+
+#[test]
+fn read_splits_characters() {
+    let bytes: Vec<u8> = (0x20..0x100).map(|b| b as u8 ).collect();
+    let expected: String = bytes.iter().map(|&b| Windows1252.decode(b).unwrap_or(Utf8Char::from('?')) ).collect();
+    let replaced: Vec<u8> = bytes.iter()
+        .map(|&b| if Windows1252.decode(b).is_ok() {b} else {b'?'} )
+        .collect();
+    for buf_len in 1..6 {
+        let mut decoder = Windows1252.decode_bytes(replaced.iter().cloned());
+        let mut decoded = Vec::new();
+        let mut buf = vec![0; buf_len];
+        loop {
+            match decoder.read(&mut buf).unwrap() {
+                0 => break,
+                n => decoded.extend_from_slice(&buf[..n]),
+            }
+        }
+        assert_eq!(String::from_utf8(decoded).unwrap(), expected);
+    }
+}
+
+#[test]
+fn read_errors() {
+    let mut decoder = Windows1252.decode_bytes(vec![0xe9, 0x90, b'a']);
+    let mut buf = [0; 8];
+    assert_eq!(decoder.read(&mut buf).unwrap(), 2);
+    assert_eq!(&buf[..2], "é".as_bytes());
+    assert_eq!(decoder.read(&mut buf).unwrap_err().kind(), ErrorKind::InvalidData);
+    assert_eq!(decoder.read(&mut buf).unwrap(), 1);
+    assert_eq!(decoder.read(&mut buf).unwrap(), 0);
+
+    let mut encoder = Iso8859_7.encode_chars("αβЖγ".chars());
+    assert_eq!(encoder.read(&mut buf).unwrap(), 2);
+    assert_eq!(&buf[..2], [0xe1, 0xe2]);
+    assert_eq!(encoder.read(&mut buf).unwrap_err().kind(), ErrorKind::InvalidData);
+    assert_eq!(encoder.read(&mut buf).unwrap(), 1);
+    assert_eq!(buf[0], 0xe3);
+    let mut encoder = Iso8859_7.encode_chars("α😀γ".chars());
+    assert_eq!(encoder.read(&mut buf).unwrap(), 1);
+    assert_eq!(encoder.read(&mut buf).unwrap_err().kind(), ErrorKind::InvalidData);
+    assert_eq!(encoder.read(&mut buf).unwrap(), 1);
+}
+
+#[test]
+fn encode_utf8chars() {
+    let chars: Vec<Utf8Char> = "Zażółć".chars().map(Utf8Char::from).collect();
+    let encoded: Result<Vec<u8>,_> = Iso8859_2.encode_chars(chars).collect();
+    assert_eq!(encoded.unwrap(), [b'Z', b'a', 0xbf, 0xf3, 0xb3, 0xe6]);
+}
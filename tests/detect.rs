@@ -0,0 +1,177 @@
+/* Copyright 2018 Torbjørn Birch Moltu
+ *
+ * Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+ * http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+ * http://opensource.org/licenses/MIT>, at your option. This file may not be
+ * copied, modified, or distributed except according to those terms.
+ */
+
+//! Tests for detecting encodings and the converting reader.
+
+#![cfg(feature="std")]
+
+extern crate encode_unicode;
+use encode_unicode::detect::{sniff, SniffReader, Encoding, Confidence};
+use encode_unicode::detect::Encoding::*;
+use std::io::{Read, ErrorKind};
+
+fn encode(s: &str,  encoding: Encoding) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    match encoding {
+        Utf8 => bytes.extend_from_slice(s.as_bytes()),
+        Utf16Le => for u in s.encode_utf16() {bytes.push(u as u8); bytes.push((u >> 8) as u8);},
+        Utf16Be => for u in s.encode_utf16() {bytes.push((u >> 8) as u8); bytes.push(u as u8);},
+        Utf32Le => for c in s.chars() {for i in 0..4 {bytes.push((c as u32 >> (8*i)) as u8);}},
+        Utf32Be => for c in s.chars() {for i in (0..4).rev() {bytes.push((c as u32 >> (8*i)) as u8);}},
+    }
+    bytes
+}
+
+const ALL: &[Encoding] = &[Utf8, Utf16Le, Utf16Be, Utf32Le, Utf32Be];
+
+#[test]
+fn boms() {
+    for &encoding in ALL {
+        let mut bytes = encoding.bom().to_vec();
+        assert_eq!(sniff(&bytes), (encoding, encoding.bom().len(), Confidence::Certain));
+        bytes.extend_from_slice(&encode("abc", encoding));
+        assert_eq!(sniff(&bytes), (encoding, encoding.bom().len(), Confidence::Certain));
+    }
+    // a BOM decides even if the rest doesn't match
+    assert_eq!(sniff(b"\xfe\xffa\0"), (Utf16Be, 2, Confidence::Certain));
+}
+
+#[test]
+fn zero_patterns() {
+    for &encoding in &[Utf16Le, Utf16Be, Utf32Le, Utf32Be] {
+        for s in &["plain ascii", "æøå and ascii", "Ελληνικά text", "💣 bomb"] {
+            let bytes = encode(s, encoding);
+            assert_eq!(sniff(&bytes), (encoding, 0, Confidence::High), "{:?} {:?}", s, encoding);
+            // cut in the middle of a character
+            assert_eq!(sniff(&bytes[..bytes.len()-1]).0, encoding, "{:?} {:?}", s, encoding);
+        }
+    }
+}
+
+#[test]
+fn shorter_than_a_unit() {
+    assert_eq!(sniff(b"\0"), (Utf8, 0, Confidence::Medium));
+    assert_eq!(sniff(b"ab\0"), (Utf8, 0, Confidence::Medium));
+    assert_eq!(sniff(b"a\0"), (Utf16Le, 0, Confidence::High));
+    assert_eq!(sniff(b"\0a"), (Utf16Be, 0, Confidence::High));
+    assert_eq!(sniff(b"a\0\0"), (Utf16Le, 0, Confidence::High));
+    assert_eq!(sniff(b"\0\0\0"), (Utf8, 0, Confidence::Medium));
+}
+
+#[test]
+fn utf8() {
+    assert_eq!(sniff(b""), (Utf8, 0, Confidence::Medium));
+    assert_eq!(sniff(b"ascii only"), (Utf8, 0, Confidence::Medium));
+    assert_eq!(sniff("non-ascii: ø".as_bytes()), (Utf8, 0, Confidence::High));
+    assert_eq!(sniff(&"ø".as_bytes()[..1]), (Utf8, 0, Confidence::Medium));
+    assert_eq!(sniff(b"\xff\xff"), (Utf8, 0, Confidence::Low));
+    assert_eq!(sniff(b"\xed\xa0\x80 surrogate"), (Utf8, 0, Confidence::Low));
+    assert_eq!(sniff(b"caf\xe9 au lait"), (Utf8, 0, Confidence::Low));
+    // NUL in UTF-8 at both parities isn't UTF-16
+    assert_eq!(sniff(b"a\0\0b"), (Utf8, 0, Confidence::Medium));
+}
+
+#[test]
+fn utf8_with_some_errors() {
+    let mut bytes = "Blåbærsyltetøy, Ελληνικά og 日本語".as_bytes().to_vec();
+    bytes.insert(10, 0xff);
+    assert_eq!(sniff(&bytes), (Utf8, 0, Confidence::Medium));
+    // scanning continues past the invalid byte
+    bytes.extend_from_slice(b"\xc0\xaf");
+    assert_eq!(sniff(&bytes), (Utf8, 0, Confidence::Medium));
+    bytes.extend_from_slice(b"\xe2\x82\xe2\x82\xe2\x82");
+    assert_eq!(sniff(&bytes), (Utf8, 0, Confidence::Low));
+    // starting in the middle of a character
+    assert_eq!(sniff(&"\u{80}æøå æøå æøå æøå æøå".as_bytes()[1..]), (Utf8, 0, Confidence::Medium));
+    assert_eq!(sniff(b"\xe9t\xe9 \xe0 l'h\xf4tel"), (Utf8, 0, Confidence::Low));
+}
+
+#[test]
+fn reader_converts() {
+    let text = "Blåbærsyltetøy 💣 with a long tail ".repeat(50);
+    for &encoding in ALL {
+        for &with_bom in &[false, true] {
+            let mut bytes = if with_bom {encoding.bom().to_vec()} else {Vec::new()};
+            bytes.extend_from_slice(&encode(&text, encoding));
+            let mut reader = SniffReader::new(&bytes[..]).unwrap();
+            assert_eq!(reader.encoding(), encoding);
+            let mut converted = Vec::new();
+            let mut buf = [0; 3];
+            loop {
+                match reader.read(&mut buf).unwrap() {
+                    0 => break,
+                    n => converted.extend_from_slice(&buf[..n]),
+                }
+            }
+            assert_eq!(String::from_utf8(converted).unwrap(), text, "{:?}", encoding);
+        }
+    }
+}
+
+#[test]
+fn reader_errors() {
+    let mut reader = SniffReader::new(&b"\xff\xfea\0\x00\xd8b\0"[..]).unwrap();
+    let mut buf = [0; 8];
+    assert_eq!(reader.read(&mut buf).unwrap(), 1);
+    assert_eq!(reader.read(&mut buf).unwrap_err().kind(), ErrorKind::InvalidData);
+
+    let mut reader = SniffReader::new(&b"\xfe\xff\0a\0"[..]).unwrap();
+    assert_eq!(reader.read(&mut buf).unwrap(), 1);
+    assert_eq!(reader.read(&mut buf).unwrap_err().kind(), ErrorKind::InvalidData);
+}
+
+#[test]
+fn reader_validates_utf8() {
+    let mut reader = SniffReader::new(&b"caf\xe9 au lait"[..]).unwrap();
+    assert_eq!((reader.encoding(), reader.confidence()), (Utf8, Confidence::Low));
+    let mut buf = [0; 16];
+    assert_eq!(reader.read(&mut buf).unwrap(), 3);
+    assert_eq!(reader.read(&mut buf).unwrap_err().kind(), ErrorKind::InvalidData);
+    assert_eq!(reader.read(&mut buf).unwrap(), 8);
+    assert_eq!(&buf[..8], b" au lait");
+    assert_eq!(reader.read(&mut buf).unwrap(), 0);
+
+    let mut reader = SniffReader::new(&b"\xef\xbb\xbfab\xe2\x82"[..]).unwrap();
+    assert_eq!(reader.read(&mut buf).unwrap(), 2);
+    assert_eq!(reader.read(&mut buf).unwrap_err().kind(), ErrorKind::InvalidData);
+    assert_eq!(reader.read(&mut buf).unwrap(), 0);
+
+    let mut text = String::new();
+    let error = SniffReader::new(&b"\xed\xa0\x80"[..]).unwrap().read_to_string(&mut text).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+}
+
+/// Returns the bytes of a string one at a time.
+struct Trickle<'a>(&'a [u8]);
+impl<'a> Read for Trickle<'a> {
+    fn read(&mut self,  buf: &mut[u8]) -> std::io::Result<usize> {
+        match (self.0.split_first(), buf.first_mut()) {
+            (Some((&b, rest)), Some(dst)) => {
+                *dst = b;
+                self.0 = rest;
+                Ok(1)
+            },
+            _ => Ok(0),
+        }
+    }
+}
+
+#[test]
+fn reader_keeps_partial_utf8_sequences() {
+    let text = "æ💣".repeat(300);
+    let mut reader = SniffReader::new(Trickle(text.as_bytes())).unwrap();
+    let mut converted = Vec::new();
+    let mut buf = [0; 5];
+    loop {
+        match reader.read(&mut buf).unwrap() {
+            0 => break,
+            n => converted.extend_from_slice(&buf[..n]),
+        }
+    }
+    assert_eq!(String::from_utf8(converted).unwrap(), text);
+}
@@ -117,8 +117,110 @@ fn overlong_utf8() {
     }
 }
 
+#[test]
+fn invalid_codepoint_utf8() {
+    use encode_unicode::error::InvalidCodepoint::*;
+    for &(bytes, error) in &[([0xed,0xa0,0x80,0], Utf16Reserved), ([0xed,0xbf,0xbf,0], Utf16Reserved),
+                             ([0xf4,0x90,0x80,0x80], TooHigh), ([0xf7,0xbf,0xbf,0xbf], TooHigh)] {
+        let len = if bytes[3] == 0 {3} else {4};
+        assert_eq!(char::from_utf8_slice_start(&bytes[..len]), Err(InvalidUtf8Slice::Codepoint(error)));
+        assert_eq!(char::from_utf8_array(bytes), Err(InvalidUtf8Array::Codepoint(error)));
+    }
+}
+
 #[test]
 fn from_str_start() {
     assert_eq!(Utf8Char::from_str_start(""), Err(EmptyStrError));
     assert_eq!(Utf16Char::from_str_start(""), Err(EmptyStrError));
 }
+
+#[test]
+fn ucs2() {
+    assert_eq!(Ucs2Char::from_unit(0xd800), Err(InvalidCodepoint::Utf16Reserved));
+    assert_eq!(Ucs2Char::from_unit(0xdfff), Err(InvalidCodepoint::Utf16Reserved));
+    for &c in &['\u{10000}', '💣', '\u{10ffff}'] {
+        assert_eq!(Ucs2Char::from_char(c), Err(NonBmpError));
+        assert_eq!(Ucs2Char::from_utf8char(c.to_utf8()), Err(NonBmpError));
+        assert_eq!(Ucs2Char::from_utf16char(c.to_utf16()), Err(NonBmpError));
+    }
+    let mut buf = [0; 4];
+    let err = Ucs2Char::encode_str("ab\u{10000}c", &mut buf, Ucs2Substitution::Reject);
+    assert_eq!(err.map_err(|e| (e.valid_up_to(), e.units_written()) ), Err((2, 2)));
+    let err = Ucs2Char::encode_str("💣", &mut buf[..0], Ucs2Substitution::Reject);
+    assert_eq!(err.map_err(|e| (e.valid_up_to(), e.units_written()) ), Err((0, 0)));
+    let err = Ucs2Char::encode_chars(&['a', 'b', '\u{10ffff}'], &mut buf, Ucs2Substitution::Reject);
+    assert_eq!(err.map_err(|e| (e.valid_up_to(), e.units_written()) ), Err((2, 2)));
+}
+
+#[test]
+fn ucs2_lone_surrogates() {
+    let reject = Ucs2Substitution::Reject;
+    let replace = Ucs2Substitution::Replace(Ucs2Char::replacement_character());
+    let mut buf = [0; 8];
+    for &(src, valid_up_to) in &[(&[0xd800][..], 0), (&[0x41, 0xdc00][..], 1),
+                                 (&[0x41, 0xd800, 0x42][..], 1), (&[0xd800, 0xd800, 0xdc00][..], 0)] {
+        let err = Ucs2Char::encode_utf16(src, &mut buf, reject).unwrap_err();
+        assert_eq!((err.valid_up_to(), err.units_written()), (valid_up_to, valid_up_to), "{:x?}", src);
+    }
+    let src = [0xdc00, 0x41, 0xd800, 0xd83d, 0xdca3, 0xd800];
+    assert_eq!(Ucs2Char::encode_utf16(&src, &mut buf, replace), Ok((6, 5)));
+    assert_eq!(&buf[..5], &[0xfffd, 0x41, 0xfffd, 0xfffd, 0xfffd]);
+    assert_eq!(Ucs2Char::encode_utf16(&src, &mut buf, Ucs2Substitution::Skip), Ok((6, 1)));
+    assert_eq!(buf[0], 0x41);
+    let err = Ucs2Char::encode_utf16(&src[1..], &mut buf, reject).unwrap_err();
+    assert_eq!((err.valid_up_to(), err.units_written()), (1, 1));
+    // a pair is one character, so it is either rejected or replaced as a whole
+    assert_eq!(Ucs2Char::encode_utf16(&src[3..5], &mut buf, replace), Ok((2, 1)));
+    assert_eq!(Ucs2Char::encode_utf16(&src[3..5], &mut buf[..0], replace), Ok((0, 0)));
+}
+
+#[test]
+fn error_sources() {
+    use core::error::Error;
+    let slice = InvalidUtf8Slice::Utf8(InvalidUtf8::FirstByte(InvalidUtf8FirstByte::ContinuationByte));
+    let utf8 = slice.source().unwrap();
+    assert_eq!(utf8.to_string(), "the first byte is a continuation of a previous sequence");
+    let first_byte = utf8.source().unwrap();
+    assert_eq!(first_byte.to_string(), InvalidUtf8FirstByte::ContinuationByte.to_string());
+    assert!(first_byte.source().is_none());
+    assert!(InvalidUtf8Slice::TooShort(2).source().is_none());
+    assert_eq!(slice.to_string(),
+               "the sequence is invalid UTF-8: the first byte is a continuation of a previous sequence");
+    let codepoint = InvalidUtf8Array::Codepoint(InvalidCodepoint::TooHigh);
+    assert!(codepoint.source().unwrap().downcast_ref::<InvalidCodepoint>().is_some());
+
+    fn boxed(bytes: &[u8]) -> Result<Utf8Char, Box<dyn Error>> {
+        Ok(Utf8Char::from_slice_start(bytes)?.0)
+    }
+    assert_eq!(boxed(b"\xc3\xa6").unwrap(), Utf8Char::from('æ'));
+    assert_eq!(boxed(b"\xc3").unwrap_err().to_string(), InvalidUtf8Slice::TooShort(2).to_string());
+}
+
+#[test]
+fn decode_error() {
+    use std::io;
+    let utf16 = DecodeError::Utf16(0, Utf16Char::from_slice_start(&[0xdc00]).unwrap_err());
+    assert_eq!(utf16, DecodeError::Utf16(0, InvalidUtf16Slice::FirstLowSurrogate));
+    assert_eq!(utf16.position(), Some(0));
+    assert_eq!(utf16.to_string(), "invalid UTF-16: the first unit is a trailing surrogate");
+
+    let utf7 = DecodeError::from(InvalidUtf7::EmptyShift(3));
+    assert_eq!(utf7.position(), Some(3));
+    assert_eq!(DecodeError::from(InvalidPunycode::InvalidDigit(5)).position(), Some(5));
+    assert_eq!(DecodeError::from(InvalidPunycode::Overflow).position(), None);
+    assert_eq!(DecodeError::from(InvalidCodepoint::TooHigh).position(), None);
+    let ucs2 = Ucs2Char::encode_str("ab💣", &mut [0; 4], Ucs2Substitution::Reject).unwrap_err();
+    assert_eq!(DecodeError::from(ucs2).position(), Some(2));
+
+    let error = io::Error::from(DecodeError::Utf8(7, InvalidUtf8Slice::TooShort(3)));
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    assert_eq!(error.to_string(), "invalid UTF-8: the slice is shorter than the sequence");
+    let inner = error.into_inner().unwrap().downcast::<DecodeError>().unwrap();
+    assert_eq!(*inner, DecodeError::Utf8(7, InvalidUtf8Slice::TooShort(3)));
+
+    fn read_error() -> io::Result<()> {
+        Err(DecodeError::from(InvalidJsonString::Unescaped(1)))?;
+        Ok(())
+    }
+    assert_eq!(read_error().unwrap_err().kind(), io::ErrorKind::InvalidData);
+}
@@ -0,0 +1,109 @@
+/* Copyright 2018 Torbjørn Birch Moltu
+ *
+ * Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+ * http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+ * http://opensource.org/licenses/MIT>, at your option. This file may not be
+ * copied, modified, or distributed except according to those terms.
+ */
+
+//! Tests for escape sequences of programming languages.
+
+#![cfg(feature="std")]
+
+extern crate encode_unicode;
+use encode_unicode::{Utf8Char, EscapeStyle};
+use encode_unicode::EscapeStyle::*;
+use encode_unicode::error::InvalidEscape;
+use encode_unicode::error::InvalidEscape::{NotAnEscape, Malformed, Codepoint, Utf8};
+use encode_unicode::error::{InvalidCodepoint, InvalidUtf16Tuple, InvalidUtf8, InvalidUtf8Slice, InvalidUtf8FirstByte};
+
+static STYLES: [EscapeStyle; 4] = [Braced, Universal, Utf16, Utf8Bytes];
+
+#[test]
+fn roundtrips() {
+    for c in (0..0x11_00_00).filter_map(std::char::from_u32) {
+        let u8c = Utf8Char::from(c);
+        for &style in &STYLES {
+            let escape = u8c.escape(style);
+            assert_eq!(escape.len(), escape.as_str().len());
+            let s = escape.to_string();
+            assert_eq!(Utf8Char::from_escape_start(&s, style), Ok((u8c, s.len())), "{:?}", s);
+            let upper: String = s.chars()
+                .map(|c| if ('a'..='f').contains(&c) {((c as u8) - 0x20) as char} else {c} )
+                .collect();
+            assert_eq!(Utf8Char::from_escape_start(&upper, style), Ok((u8c, s.len())), "{:?}", upper);
+        }
+    }
+}
+
+#[test]
+fn like_other_languages() {
+    let c = Utf8Char::from('\u{7}');
+    assert_eq!(c.escape(Braced).to_string(), "\\u{7}");
+    assert_eq!(c.escape(Universal).to_string(), "\\u0007");
+    assert_eq!(c.escape(Utf16).to_string(), "\\u0007");
+    assert_eq!(c.escape(Utf8Bytes).to_string(), "\\x07");
+    let c = Utf8Char::from('\u{10ffff}');
+    assert_eq!(c.escape(Braced).to_string(), "\\u{10ffff}");
+    assert_eq!(c.escape(Universal).to_string(), "\\U0010ffff");
+    assert_eq!(c.escape(Utf16).to_string(), "\\udbff\\udfff");
+    assert_eq!(c.escape(Utf8Bytes).to_string(), "\\xf4\\x8f\\xbf\\xbf");
+    let mut iter = Utf8Char::from('æ').escape(Braced);
+    assert_eq!(iter.next(), Some('\\'));
+    assert_eq!(iter.size_hint(), (5, Some(5)));
+    assert_eq!(format!("{:?}", iter), "\"u{e6}\"");
+}
+
+#[test]
+fn trailing_text() {
+    assert_eq!(Utf8Char::from_escape_start("\\u{41}}", Braced), Ok((Utf8Char::from('A'), 6)));
+    assert_eq!(Utf8Char::from_escape_start("\\u00411", Universal), Ok((Utf8Char::from('A'), 6)));
+    assert_eq!(Utf8Char::from_escape_start("\\u0041\\u0042", Utf16), Ok((Utf8Char::from('A'), 6)));
+    assert_eq!(Utf8Char::from_escape_start("\\x41\\x42", Utf8Bytes), Ok((Utf8Char::from('A'), 4)));
+    assert_eq!(Utf8Char::from_escape_start("\\xc3\\xa6\\xc3", Utf8Bytes), Ok((Utf8Char::from('æ'), 8)));
+}
+
+#[test]
+fn not_escapes() {
+    for &style in &STYLES {
+        for s in &["", "\\", "u0041", "\\n", "\\\\u0041", " \\u0041"] {
+            assert_eq!(Utf8Char::from_escape_start(s, style), Err(NotAnEscape), "{:?}", s);
+        }
+    }
+    assert_eq!(Utf8Char::from_escape_start("\\U00000041", Utf16), Err(NotAnEscape));
+    assert_eq!(Utf8Char::from_escape_start("\\U{41}", Braced), Err(NotAnEscape));
+    assert_eq!(Utf8Char::from_escape_start("\\u0041", Utf8Bytes), Err(NotAnEscape));
+}
+
+#[test]
+fn malformed() {
+    for s in &["\\u", "\\u0041", "\\u{", "\\u{}", "\\u{41", "\\u{0000041}", "\\u{4g}", "\\u{+41}", "\\u{ 41}"] {
+        assert_eq!(Utf8Char::from_escape_start(s, Braced), Err(Malformed), "{:?}", s);
+    }
+    for s in &["\\u", "\\u004", "\\u004g", "\\u+041", "\\u{41}"] {
+        assert_eq!(Utf8Char::from_escape_start(s, Universal), Err(Malformed), "{:?}", s);
+        assert_eq!(Utf8Char::from_escape_start(s, Utf16), Err(Malformed), "{:?}", s);
+    }
+    assert_eq!(Utf8Char::from_escape_start("\\U0000004", Universal), Err(Malformed));
+    assert_eq!(Utf8Char::from_escape_start("\\U{41}", Universal), Err(Malformed));
+    assert_eq!(Utf8Char::from_escape_start("\\ud83d\\udcz3", Utf16), Err(Malformed));
+    for s in &["\\x", "\\x4", "\\x4g", "\\x{41}"] {
+        assert_eq!(Utf8Char::from_escape_start(s, Utf8Bytes), Err(Malformed), "{:?}", s);
+    }
+}
+
+#[test]
+fn invalid_codepoints() {
+    assert_eq!(Utf8Char::from_escape_start("\\u{110000}", Braced), Err(Codepoint(InvalidCodepoint::TooHigh)));
+    assert_eq!(Utf8Char::from_escape_start("\\u{dfff}", Braced), Err(Codepoint(InvalidCodepoint::Utf16Reserved)));
+    assert_eq!(Utf8Char::from_escape_start("\\Uffffffff", Universal), Err(Codepoint(InvalidCodepoint::TooHigh)));
+    assert_eq!(Utf8Char::from_escape_start("\\ud800", Universal), Err(Codepoint(InvalidCodepoint::Utf16Reserved)));
+    assert_eq!(Utf8Char::from_escape_start("\\udca3", Utf16), Err(InvalidEscape::Utf16(InvalidUtf16Tuple::FirstIsTrailingSurrogate)));
+    assert_eq!(Utf8Char::from_escape_start("\\ud83d\\n", Utf16), Err(InvalidEscape::Utf16(InvalidUtf16Tuple::MissingSecond)));
+    assert_eq!(Utf8Char::from_escape_start("\\ud83d\\u0041", Utf16), Err(InvalidEscape::Utf16(InvalidUtf16Tuple::InvalidSecond)));
+    assert_eq!(Utf8Char::from_escape_start("\\x80", Utf8Bytes), Err(Utf8(InvalidUtf8Slice::Utf8(InvalidUtf8::FirstByte(InvalidUtf8FirstByte::ContinuationByte)))));
+    assert_eq!(Utf8Char::from_escape_start("\\xc3", Utf8Bytes), Err(Utf8(InvalidUtf8Slice::TooShort(2))));
+    assert_eq!(Utf8Char::from_escape_start("\\xc3\\x41", Utf8Bytes), Err(Utf8(InvalidUtf8Slice::Utf8(InvalidUtf8::NotAContinuationByte(1)))));
+    assert_eq!(Utf8Char::from_escape_start("\\xc1\\x81", Utf8Bytes), Err(Utf8(InvalidUtf8Slice::Utf8(InvalidUtf8::OverLong))));
+    assert_eq!(Utf8Char::from_escape_start("\\xed\\xa0\\x80", Utf8Bytes), Err(Utf8(InvalidUtf8Slice::Codepoint(InvalidCodepoint::Utf16Reserved))));
+}
@@ -0,0 +1,101 @@
+/* Copyright 2018 Torbjørn Birch Moltu
+ *
+ * Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+ * http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+ * http://opensource.org/licenses/MIT>, at your option. This file may not be
+ * copied, modified, or distributed except according to those terms.
+ */
+
+//! Tests for NUL-terminated strings.
+
+#![cfg(feature="std")]
+
+extern crate encode_unicode;
+use encode_unicode::ffi::{U8CString, U8CStr, U16CString, U16CStr, U32CString, U32CStr, WideCString};
+use encode_unicode::error::InvalidCString::*;
+use encode_unicode::error::{InvalidUtf8Slice, InvalidUtf8, InvalidUtf16Slice, InvalidCodepoint};
+use std::borrow::ToOwned;
+
+#[test]
+fn roundtrips() {
+    let all: String = (1..0x11_00_00).filter_map(std::char::from_u32)
+        .filter(|&c| (c as u32) % 37 < 2  ||  (c as u32) < 0x100 )
+        .collect();
+    let utf8: U8CString = all.parse().unwrap();
+    assert_eq!(utf8.as_slice(), all.as_bytes());
+    assert_eq!(utf8.to_str(), Ok(&all[..]));
+    assert_eq!(utf8.to_string_lossy(), all);
+    let utf16: U16CString = all.parse().unwrap();
+    assert_eq!(utf16.as_slice(), &all.encode_utf16().collect::<Vec<u16>>()[..]);
+    assert_eq!(utf16.to_string(), Ok(all.clone()));
+    assert_eq!(utf16.to_string_lossy(), all);
+    let utf32: U32CString = all.parse().unwrap();
+    assert_eq!(utf32.len(), all.chars().count());
+    assert_eq!(utf32.to_string(), Ok(all.clone()));
+    assert_eq!(utf32.to_string_lossy(), all);
+    let wide: WideCString = all.parse().unwrap();
+    assert_eq!(wide.to_string(), Ok(all));
+}
+
+#[test]
+fn nul() {
+    assert_eq!("æ\0".parse::<U16CString>(), Err(InteriorNul(2)));
+    assert_eq!("\0".parse::<U32CString>(), Err(InteriorNul(0)));
+    assert_eq!("ab\0".parse::<U8CString>(), Err(InteriorNul(2)));
+    assert_eq!(U8CString::from_vec(b"\xff\0".to_vec()), Err(InteriorNul(1)));
+    assert_eq!(U16CString::from_vec(vec![1, 0, 2]), Err(InteriorNul(1)));
+    assert_eq!(U32CString::from_vec(vec![0]), Err(InteriorNul(0)));
+    let empty: U16CString = "".parse().unwrap();
+    assert!(empty.is_empty());
+    assert_eq!(empty.as_slice_with_nul(), &[0]);
+    let s = U16CString::from_vec(vec![0xd800, 1]).unwrap();
+    assert_eq!(s.as_slice_with_nul(), &[0xd800, 1, 0]);
+    assert_eq!(s.clone().into_vec(), [0xd800, 1]);
+    assert_eq!(s.into_vec_with_nul(), [0xd800, 1, 0]);
+    assert_eq!(InteriorNul(3).position(), 3);
+}
+
+#[test]
+fn from_slices() {
+    assert_eq!(U16CStr::from_slice_with_nul(&[0x41, 0]).unwrap().as_slice(), &[0x41]);
+    assert_eq!(U16CStr::from_slice_with_nul(&[0x41, 0, 0]).unwrap_err(), InteriorNul(1));
+    assert_eq!(U16CStr::from_slice_with_nul(&[0x41]).unwrap_err(), MissingNul(1));
+    assert_eq!(U32CStr::from_slice_with_nul(&[]).unwrap_err(), MissingNul(0));
+    let borrowed = U32CStr::from_slice_with_nul(&[0x1f4a3, 0]).unwrap();
+    let owned: U32CString = borrowed.to_owned();
+    assert_eq!(&*owned, borrowed);
+    assert_eq!(format!("{:?}", owned), "[128163]");
+}
+
+#[test]
+fn from_pointers() {
+    let units = [0x62u16, 0x3f, 0, 0x41];
+    let s = unsafe{ U16CStr::from_ptr(units.as_ptr(), 4) }.unwrap();
+    assert_eq!(s.to_string(), Ok(String::from("b?")));
+    assert_eq!(s.as_ptr(), units.as_ptr());
+    assert_eq!(unsafe{ U16CStr::from_ptr(units.as_ptr(), 3) }.unwrap().len(), 2);
+    assert_eq!(unsafe{ U16CStr::from_ptr(units.as_ptr(), 2) }, Err(MissingNul(2)));
+    assert_eq!(unsafe{ U32CStr::from_ptr([0u32].as_ptr(), 0) }, Err(MissingNul(0)));
+    let bytes = b"C\0";
+    let s = unsafe{ U8CStr::from_ptr(bytes.as_ptr(), 10) }.unwrap();
+    assert_eq!(s.to_str(), Ok("C"));
+    assert_eq!(s.as_slice_with_nul(), bytes);
+}
+
+#[test]
+fn invalid() {
+    let s = U16CString::from_vec(vec![0x41, 0xdca3, 0x42, 0xd83d]).unwrap();
+    assert_eq!(s.to_string(), Err(Utf16(1, InvalidUtf16Slice::FirstLowSurrogate)));
+    assert_eq!(s.to_string_lossy(), "A\u{fffd}B\u{fffd}");
+    let s = U16CString::from_vec(vec![0xd83d]).unwrap();
+    assert_eq!(s.to_string(), Err(Utf16(0, InvalidUtf16Slice::MissingSecond)));
+    let s = U8CString::from_vec(b"A\xc0\xaeB\xe2\x82".to_vec()).unwrap();
+    assert_eq!(s.to_str(), Err(Utf8(1, InvalidUtf8Slice::Utf8(InvalidUtf8::OverLong))));
+    assert_eq!(s.to_string_lossy(), "A\u{fffd}\u{fffd}B\u{fffd}");
+    let s = U8CStr::from_slice_with_nul(b"ab\xe2\x82\0").unwrap();
+    assert_eq!(s.to_string(), Err(Utf8(2, InvalidUtf8Slice::TooShort(3))));
+    assert_eq!(Utf8(2, InvalidUtf8Slice::TooShort(3)).position(), 2);
+    let s = U32CString::from_vec(vec![0x41, 0xd800, 0x110000]).unwrap();
+    assert_eq!(s.to_string(), Err(Codepoint(1, InvalidCodepoint::Utf16Reserved)));
+    assert_eq!(s.to_string_lossy(), "A\u{fffd}\u{fffd}");
+}
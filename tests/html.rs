@@ -0,0 +1,77 @@
+/* Copyright 2018 Torbjørn Birch Moltu
+ *
+ * Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+ * http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+ * http://opensource.org/licenses/MIT>, at your option. This file may not be
+ * copied, modified, or distributed except according to those terms.
+ */
+
+//! Tests for HTML and XML character references.
+
+#![cfg(feature="std")]
+
+extern crate encode_unicode;
+use encode_unicode::html::{escape, unescape, entity};
+use encode_unicode::error::InvalidCharRef::*;
+use encode_unicode::error::InvalidCodepoint::*;
+
+#[test]
+fn escape_roundtrips() {
+    let all: String = (0..0x11_00_00).filter_map(std::char::from_u32)
+        .filter(|&c| (c as u32) % 61 < 2  ||  (c as u32) < 0x100 )
+        .collect();
+    let ascii = escape(&all, |c| (c as u32) < 0x80 );
+    assert!(ascii.bytes().all(|b| b < 0x80 ));
+    assert_eq!(unescape(&ascii).unwrap(), all);
+    let unchanged = escape(&all, |_| true );
+    assert_eq!(unescape(&unchanged).unwrap(), all);
+    assert_eq!(unchanged.len(), all.len() + 4 + 3 + 3 + 5 + 4);
+}
+
+#[test]
+fn markup_is_always_escaped() {
+    assert_eq!(escape("<a href=\"x\" title='&'>", |_| true ),
+               "&lt;a href=&quot;x&quot; title=&#39;&amp;&#39;&gt;");
+    assert_eq!(escape("<a>", |_| false ), "&lt;&#x61;&gt;");
+}
+
+#[test]
+fn numeric() {
+    assert_eq!(unescape("&#0;&#65;&#x41;&#X41;&#x0041;&#0000065;&#xFfFf;&#x10FFFF;").unwrap(),
+               "\u{0}AAAAA\u{ffff}\u{10ffff}");
+    assert_eq!(unescape("a&#xdfff;"), Err(Codepoint(1, Utf16Reserved)));
+    assert_eq!(unescape("a&#55296;"), Err(Codepoint(1, Utf16Reserved)));
+    assert_eq!(unescape("&#x110000;"), Err(Codepoint(0, TooHigh)));
+    assert_eq!(unescape("&#99999999999999999999;"), Err(Codepoint(0, TooHigh)));
+    for s in &["&#", "&#;", "&#x", "&#a;", "&#12a;", "&#x12g;", "&#-1;", "&#+1;", "&# 1;", "&#65 "] {
+        assert_eq!(unescape(s), Err(Malformed(0)), "{:?}", s);
+    }
+    assert_eq!(Codepoint(3, TooHigh).position(), 3);
+}
+
+#[test]
+fn named() {
+    assert_eq!(unescape("&amp;&AMP;&lt;&apos;&quot;&nbsp;&euro;&hearts;").unwrap(), "&&<'\"\u{a0}€♥");
+    assert_eq!(unescape("&NotEqualTilde;&fjlig;").unwrap(), "\u{2242}\u{338}fj");
+    assert_eq!(entity("Amp"), None);
+    assert_eq!(entity("amp"), Some("&"));
+    assert_eq!(entity(""), None);
+}
+
+#[test]
+fn without_semicolon() {
+    assert_eq!(unescape("&amp &lt&gt &copy2018 &notit; &eacutex").unwrap(), "& <> ©2018 ¬it; éx");
+    // only some are recognized without it
+    assert_eq!(unescape("&euro &hearts").unwrap(), "&euro &hearts");
+    // the longest match
+    assert_eq!(unescape("&notin; &notin").unwrap(), "∉ ¬in");
+}
+
+#[test]
+fn plain_ampersands() {
+    for s in &["&", "a & b", "&&", "&;", "& amp;", "?a=1&b=2", "&ø;", "&-;"] {
+        assert_eq!(unescape(s).unwrap(), *s);
+    }
+    assert_eq!(unescape("x &unknown; y"), Err(UnknownEntity(2)));
+    assert_eq!(unescape("&Euro;"), Err(UnknownEntity(0)));
+}
@@ -0,0 +1,75 @@
+/* Copyright 2018 Torbjørn Birch Moltu
+ *
+ * Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+ * http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+ * http://opensource.org/licenses/MIT>, at your option. This file may not be
+ * copied, modified, or distributed except according to those terms.
+ */
+
+//! Tests for escaping and unescaping JSON strings.
+
+#![cfg(feature="std")]
+
+extern crate encode_unicode;
+use encode_unicode::json::{escape, escape_non_ascii, unescape};
+use encode_unicode::error::InvalidJsonString::*;
+use encode_unicode::error::InvalidUtf16Tuple::*;
+
+#[test]
+fn like_python() {
+    // json.dumps(s, ensure_ascii=...)[1:-1]
+    let s = "\u{0}\u{1f} \"\\/ \u{2028}";
+    assert_eq!(escape_non_ascii(s), "\\u0000\\u001f \\\"\\\\/ \\u2028");
+    assert_eq!(escape(s), "\\u0000\\u001f \\\"\\\\/ \u{2028}");
+    let s = "Blåbær 💣\u{8}\u{c}";
+    assert_eq!(escape_non_ascii(s), "Bl\\u00e5b\\u00e6r \\ud83d\\udca3\\b\\f");
+    assert_eq!(escape(s), "Blåbær 💣\\b\\f");
+}
+
+#[test]
+fn roundtrips() {
+    let mut all = String::new();
+    for c in (0..0x11_00_00).filter_map(std::char::from_u32).filter(|c| (*c as u32) % 97 < 3  ||  (*c as u32) < 0x100 ) {
+        all.push(c);
+    }
+    assert_eq!(unescape(&escape(&all)).unwrap(), all);
+    let ascii = escape_non_ascii(&all);
+    assert!(ascii.bytes().all(|b| b < 0x80 ));
+    assert_eq!(unescape(&ascii).unwrap(), all);
+}
+
+#[test]
+fn short_escapes() {
+    assert_eq!(unescape("\\\"\\\\\\/\\b\\f\\n\\r\\t").unwrap(), "\"\\/\u{8}\u{c}\n\r\t");
+    for (i, c) in "'0aeUvx\u{e6} ".char_indices() {
+        let s = format!("ab\\{}", c);
+        assert_eq!(unescape(&s), Err(InvalidEscape(2)), "{:?} at {}", c, i);
+    }
+    assert_eq!(unescape("ab\\"), Err(InvalidEscape(2)));
+}
+
+#[test]
+fn bad_hex() {
+    for s in &["\\u", "\\u123", "\\u123g", "\\u+123", "\\u-123", "\\u 123"] {
+        assert_eq!(unescape(s), Err(InvalidEscape(0)), "{:?}", s);
+    }
+    assert_eq!(unescape("\\ud83d\\udcz3"), Err(InvalidEscape(6)));
+}
+
+#[test]
+fn unescaped() {
+    assert_eq!(unescape("a\"b"), Err(Unescaped(1)));
+    assert_eq!(unescape("æ\n"), Err(Unescaped(2)));
+    assert_eq!(unescape("\u{7f}\u{80}\u{2028}"), Ok(String::from("\u{7f}\u{80}\u{2028}")));
+}
+
+#[test]
+fn surrogates() {
+    assert_eq!(unescape("\\ud83d\\udca3\\ud800\\udc00\\udbff\\udfff").unwrap(), "💣\u{10000}\u{10ffff}");
+    assert_eq!(unescape("æ\\ud83d"), Err(Utf16(2, MissingSecond)));
+    assert_eq!(unescape("æ\\ud83d\\n"), Err(Utf16(2, MissingSecond)));
+    assert_eq!(unescape("æ\\ud83d\\ud83d\\udca3"), Err(Utf16(2, InvalidSecond)));
+    assert_eq!(unescape("\\ud83d💣"), Err(Utf16(0, MissingSecond)));
+    assert_eq!(unescape("💣\\udca3"), Err(Utf16(4, FirstIsTrailingSurrogate)));
+    assert_eq!(Utf16(4, InvalidSecond).position(), 4);
+}
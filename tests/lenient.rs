@@ -0,0 +1,110 @@
+/* Copyright 2018 Torbjørn Birch Moltu
+ *
+ * Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+ * http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+ * http://opensource.org/licenses/MIT>, at your option. This file may not be
+ * copied, modified, or distributed except according to those terms.
+ */
+
+//! Tests for decoding UTF-8 with accepted deviations.
+
+#![cfg(feature="std")]
+
+extern crate encode_unicode;
+use encode_unicode::lenient::{Leniency, Deviation};
+use encode_unicode::lenient::Deviation::*;
+use encode_unicode::error::InvalidUtf8Slice;
+use encode_unicode::error::InvalidUtf8Slice::*;
+use encode_unicode::error::InvalidUtf8::{self, FirstByte, NotAContinuationByte};
+use encode_unicode::error::InvalidUtf8FirstByte::*;
+use encode_unicode::error::InvalidCodepoint;
+use encode_unicode::CharExt;
+
+const ALL: Leniency = Leniency{ overlong: true,  surrogates: true,  beyond_unicode: true };
+
+type Decoded = Vec<(usize,Result<u32,InvalidUtf8Slice>)>;
+
+fn decode(leniency: Leniency,  bytes: &[u8])  ->  (Decoded, Vec<(usize,Deviation)>) {
+    let mut deviations = Vec::new();
+    let decoded = leniency.decode(bytes, |pos, dev| deviations.push((pos, dev)) ).collect();
+    (decoded, deviations)
+}
+
+#[test]
+fn strict_is_like_char() {
+    let strict = Leniency::default();
+    for &last in &[0x00, 0x3f, 0x80, 0x8f, 0x90, 0xbf, 0xc0, 0xff] {
+        for first in 0..256u32 {
+            for second in 0..256u32 {
+                let bytes = [first as u8, second as u8, 0x80, last];
+                let expected = char::from_utf8_slice_start(&bytes)
+                    .map(|(c,len)| (c as u32, len) );
+                let mut decoder = strict.decode(&bytes, |_,_| panic!("reported a deviation") );
+                let (pos, result) = decoder.next().unwrap();
+                assert_eq!(pos, 0);
+                let len = 4 - decoder.as_slice().len();
+                assert_eq!(result.map(|value| (value, len) ), expected, "{:?}", bytes);
+            }
+        }
+    }
+}
+
+#[test]
+fn overlong() {
+    let accept = Leniency{ overlong: true, ..Leniency::default() };
+    let (decoded, deviations) = decode(accept, b"\xc0\x80\xe0\x80\xaf\xf0\x80\x80\x80a");
+    assert_eq!(decoded, [(0, Ok(0)), (2, Ok(0x2f)), (5, Ok(0)), (9, Ok(b'a' as u32))]);
+    assert_eq!(deviations, [(0, OverLong), (2, OverLong), (5, OverLong)]);
+    // an overlong surrogate is still a surrogate
+    let (decoded, _) = decode(accept, b"\xf0\x8d\xa0\x80");
+    assert_eq!(decoded[0], (0, Err(Codepoint(InvalidCodepoint::Utf16Reserved))));
+    let (decoded, deviations) = decode(ALL, b"\xf0\x8d\xa0\x80");
+    assert_eq!(decoded, [(0, Ok(0xd800))]);
+    assert_eq!(deviations, [(0, OverLong), (0, Surrogate)]);
+}
+
+#[test]
+fn surrogates() {
+    let accept = Leniency{ surrogates: true, ..Leniency::default() };
+    // CESU-8 pair, lone leading, lone trailing, reversed pair
+    let bytes = b"\xed\xa0\x80\xed\xb0\x80\xed\xaf\xbf-\xed\xbf\xbf\xed\xbf\xbf\xed\xa0\x80";
+    let (decoded, deviations) = decode(accept, bytes);
+    assert_eq!(decoded, [(0, Ok(0x10000)), (6, Ok(0xdbff)), (9, Ok(b'-' as u32)),
+                         (10, Ok(0xdfff)), (13, Ok(0xdfff)), (16, Ok(0xd800))]);
+    assert_eq!(deviations, [(0, SurrogatePair), (6, Surrogate), (10, Surrogate), (13, Surrogate),
+                            (16, Surrogate)]);
+    // the proper encoding is accepted too
+    let (decoded, deviations) = decode(accept, "💣".as_bytes());
+    assert_eq!(decoded, [(0, Ok(0x1f4a3))]);
+    assert_eq!(deviations, []);
+}
+
+#[test]
+fn beyond_unicode() {
+    let accept = Leniency{ beyond_unicode: true, ..Leniency::default() };
+    let bytes = b"\xf4\x90\x80\x80\xf7\xbf\xbf\xbf\xf8\x88\x80\x80\x80\xfd\xbf\xbf\xbf\xbf\xbf";
+    let (decoded, deviations) = decode(accept, bytes);
+    assert_eq!(decoded, [(0, Ok(0x110000)), (4, Ok(0x1fffff)), (8, Ok(0x200000)), (13, Ok(0x7fffffff))]);
+    assert_eq!(deviations, [(0, TooHigh), (4, TooHigh), (8, LongSequence), (8, TooHigh),
+                            (13, LongSequence), (13, TooHigh)]);
+    let (decoded, _) = decode(Leniency::default(), b"\xf8\x88\x80\x80\x80");
+    assert_eq!(decoded[0], (0, Err(Utf8(FirstByte(TooLongSeqence)))));
+    // overlong five-byte sequences need both
+    let (decoded, _) = decode(accept, b"\xf8\x80\x80\x80\xaf");
+    assert_eq!(decoded[0], (0, Err(Utf8(InvalidUtf8::OverLong))));
+    let (decoded, deviations) = decode(ALL, b"\xf8\x80\x80\x80\xaf\xfe\xff");
+    assert_eq!(decoded, [(0, Ok(0x2f)), (5, Err(Utf8(FirstByte(TooLongSeqence)))),
+                         (6, Err(Utf8(FirstByte(TooLongSeqence))))]);
+    assert_eq!(deviations, [(0, OverLong), (0, LongSequence)]);
+}
+
+#[test]
+fn errors_continue_with_next_byte() {
+    let (decoded, deviations) = decode(ALL, b"\xe2\x82\xfc\x80a\x80");
+    assert_eq!(decoded, [(0, Err(Utf8(NotAContinuationByte(2)))), (1, Err(Utf8(FirstByte(ContinuationByte)))),
+                         (2, Err(TooShort(6))), (3, Err(Utf8(FirstByte(ContinuationByte)))),
+                         (4, Ok(b'a' as u32)), (5, Err(Utf8(FirstByte(ContinuationByte))))]);
+    assert_eq!(deviations, []);
+    let (decoded, _) = decode(ALL, b"\xe2\x82");
+    assert_eq!(decoded, [(0, Err(TooShort(3))), (1, Err(Utf8(FirstByte(ContinuationByte))))]);
+}
@@ -0,0 +1,50 @@
+/* Copyright 2018 Torbjørn Birch Moltu
+ *
+ * Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+ * http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+ * http://opensource.org/licenses/MIT>, at your option. This file may not be
+ * copied, modified, or distributed except according to those terms.
+ */
+
+//! Tests for the `const fn` constructors and literal macros.
+
+#![cfg(feature="std")]
+
+#[macro_use]
+extern crate encode_unicode;
+use encode_unicode::{Utf8Char, Utf16Char};
+
+#[test]
+fn same_as_from() {
+    for c in (0..0x11_00_00).filter_map(std::char::from_u32) {
+        assert_eq!(Utf8Char::from_char_const(c), Utf8Char::from(c), "{:?}", c);
+        assert_eq!(Utf16Char::new_const(c), Utf16Char::from(c), "{:?}", c);
+    }
+}
+
+#[test]
+fn in_patterns() {
+    const A: Utf8Char = utf8char!('A');
+    const AE: Utf8Char = utf8char!('æ');
+    const BOMB: Utf16Char = utf16char!('💣');
+    let kind = |c: char| match Utf8Char::from(c) {
+        A => 1,
+        AE => 2,
+        _ => 0,
+    };
+    assert_eq!((kind('A'), kind('æ'), kind('Æ')), (1, 2, 0));
+    assert!(matches!(Utf16Char::from('💣'), BOMB));
+}
+
+#[test]
+fn utf16_strings() {
+    const EDGES: &str = "\u{0}\u{7f}\u{80}\u{7ff}\u{800}\u{d7ff}\u{e000}\u{ffff}\u{10000}\u{10ffff}";
+    const UTF16: &[u16] = utf16!(EDGES);
+    let expected: Vec<u16> = EDGES.encode_utf16().collect();
+    assert_eq!(UTF16, &expected[..]);
+    let mut nul = expected.clone();
+    nul.push(0);
+    assert_eq!(utf16!(EDGES, nul), &nul[..]);
+    assert_eq!(utf16!("", nul), &[0]);
+    assert_eq!(utf16!("C:\\Windows\\"), &"C:\\Windows\\".encode_utf16().collect::<Vec<u16>>()[..]);
+}
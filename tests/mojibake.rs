@@ -0,0 +1,57 @@
+/* Copyright 2018 Torbjørn Birch Moltu
+ *
+ * Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+ * http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+ * http://opensource.org/licenses/MIT>, at your option. This file may not be
+ * copied, modified, or distributed except according to those terms.
+ */
+
+//! Tests for detecting and repairing double-encoded UTF-8.
+
+#![cfg(feature="std")]
+
+extern crate encode_unicode;
+use encode_unicode::mojibake::{is_double_encoded, fix_double_encoding};
+use encode_unicode::CodePage;
+use std::borrow::Cow;
+
+/// Decode the UTF-8 of `s` the way a misconfigured program would.
+fn double_encode(s: &str) -> String {
+    s.bytes().map(|b| match CodePage::Windows1252.decode(b) {
+        Ok(u8c) => u8c.to_char(),
+        Err(_) => b as char,
+    }).collect()
+}
+
+#[test]
+fn repairs() {
+    for s in &["é", "Blåbærsyltetøy", "“quotes” and ‘apostrophes’", "Ærøskøbing Álvarez Ñoño",
+               "Москва", "日本語", "💣 and ascii", "\u{80}\u{81}\u{9d}\u{7ff}\u{800}\u{ffff}"] {
+        let damaged = double_encode(s);
+        assert!(is_double_encoded(&damaged), "{:?}", damaged);
+        assert_eq!(fix_double_encoding(&damaged), *s);
+        // only one layer is removed
+        let twice = double_encode(&damaged);
+        assert_eq!(fix_double_encoding(&twice), damaged);
+    }
+}
+
+#[test]
+fn leaves_ambiguous_alone() {
+    for s in &["", "ascii only", "é", "Café", "Ã", "Ã©é", "Ã© 日本", "Ã\u{2000}", "\u{c3}\u{a9}\u{a9}", "ðŸ’"] {
+        assert!(!is_double_encoded(s), "{:?}", s);
+        match fix_double_encoding(s) {
+            Cow::Borrowed(b) => assert_eq!(b, *s),
+            Cow::Owned(o) => panic!("{:?} was changed to {:?}", s, o),
+        }
+    }
+}
+
+#[test]
+fn rejects_invalid_utf8() {
+    // overlong, encoded surrogate and too high
+    assert!(!is_double_encoded("À¯"));
+    assert!(!is_double_encoded("í\u{a0}\u{80}"));
+    assert!(!is_double_encoded("ô\u{90}\u{80}\u{80}"));
+    assert!(is_double_encoded("ô\u{8f}¿¿"));
+}
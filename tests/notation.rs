@@ -0,0 +1,90 @@
+/* Copyright 2018 Torbjørn Birch Moltu
+ *
+ * Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+ * http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+ * http://opensource.org/licenses/MIT>, at your option. This file may not be
+ * copied, modified, or distributed except according to those terms.
+ */
+
+//! Tests for the `U+XXXX` notation.
+
+#![cfg(feature="std")]
+
+extern crate encode_unicode;
+use encode_unicode::{Utf8Char, Utf16Char};
+use encode_unicode::notation::{parse, parse_start, Notation, CodepointRange};
+use encode_unicode::error::InvalidNotation::*;
+use encode_unicode::error::InvalidCodepoint::*;
+
+#[test]
+fn roundtrips() {
+    for c in (0..0x11_00_00).filter_map(std::char::from_u32) {
+        let s = Notation(c).to_string();
+        assert_eq!(parse(&s), Ok(c), "{:?}", s);
+        assert_eq!(parse(&s.to_lowercase()), Ok(Utf8Char::from(c)));
+        assert_eq!(parse(&format!("{:#}", Notation(c))), Ok(Utf16Char::from(c)));
+    }
+}
+
+#[test]
+fn formatting() {
+    assert_eq!(Notation('\0').to_string(), "U+0000");
+    assert_eq!(Notation('\u{ffff}').to_string(), "U+FFFF");
+    assert_eq!(Notation('\u{10000}').to_string(), "U+10000");
+    assert_eq!(format!("{:#}", Notation('A')), "\\N{U+0041}");
+    let range = CodepointRange{ first: '\u{e000}',  last: '\u{10ffff}' };
+    assert_eq!(range.to_string(), "U+E000..U+10FFFF");
+}
+
+#[test]
+fn malformed() {
+    for s in &["", "U", "U+", "U+41", "U+041", "U+0000041", "U+0041 ", " U+0041", "U-0041",
+               "u+004g", "0x0041", "U+{41}", "\\N{U+0041", "\\N{0041}", "\\N{U+0041 }", "\\n{U+0041}"] {
+        assert_eq!(parse::<char>(s), Err(Malformed), "{:?}", s);
+    }
+    assert_eq!(parse_start::<char>("U+00411"), Ok(('\u{411}', 7)));
+    assert_eq!(parse_start::<char>("U+0041-"), Ok(('A', 6)));
+}
+
+#[test]
+fn invalid_codepoints() {
+    assert_eq!(parse::<char>("U+D800"), Err(Codepoint(Utf16Reserved)));
+    assert_eq!(parse::<char>("\\N{U+DFFF}"), Err(Codepoint(Utf16Reserved)));
+    assert_eq!(parse::<char>("U+110000"), Err(Codepoint(TooHigh)));
+    assert_eq!(parse::<char>("U+FFFFFF"), Err(Codepoint(TooHigh)));
+    assert_eq!(parse::<char>("U+10FFFF"), Ok('\u{10ffff}'));
+    assert_eq!(parse::<char>("U+E000"), Ok('\u{e000}'));
+    assert_eq!(Utf16Reserved.error_range(), (0xd800, 0xdfff));
+    assert_eq!(TooHigh.error_range(), (0x110000, 0xffffffff));
+}
+
+#[test]
+fn ranges() {
+    let range: CodepointRange = "U+0041..U+005A".parse().unwrap();
+    assert_eq!(range, CodepointRange{ first: 'A',  last: 'Z' });
+    assert_eq!(range.into_iter().collect::<String>(), "ABCDEFGHIJKLMNOPQRSTUVWXYZ");
+    assert!(range.contains('A')  &&  range.contains('Z'));
+    assert!(!range.contains('@')  &&  !range.contains('['));
+    assert_eq!("U+00E6".parse(), Ok(CodepointRange{ first: 'æ',  last: 'æ' }));
+    assert_eq!("\\N{U+0041}..u+005a".parse(), Ok(range));
+    assert_eq!("U+005A..U+0041".parse::<CodepointRange>(), Err(Reversed));
+    assert_eq!("U+0041..U+D800".parse::<CodepointRange>(), Err(Codepoint(Utf16Reserved)));
+    for s in &["U+0041..", "U+0041...U+005A", "U+0041-U+005A", "U+0041..U+005A..U+0060", "..U+005A"] {
+        assert_eq!(s.parse::<CodepointRange>(), Err(Malformed), "{:?}", s);
+    }
+}
+
+#[test]
+fn iterating_skips_surrogates() {
+    let range = CodepointRange{ first: '\u{d7fe}',  last: '\u{e001}' };
+    let mut iter = range.into_iter();
+    assert_eq!(iter.len(), 4);
+    assert_eq!(iter.by_ref().collect::<Vec<_>>(), ['\u{d7fe}', '\u{d7ff}', '\u{e000}', '\u{e001}']);
+    assert_eq!(iter.len(), 0);
+    let all = CodepointRange{ first: '\0',  last: '\u{10ffff}' }.into_iter();
+    assert_eq!(all.len(), 0x11_00_00 - 0x800);
+    assert_eq!(all.count(), 0x11_00_00 - 0x800);
+    let reversed = CodepointRange{ first: 'b',  last: 'a' }.into_iter();
+    assert_eq!(reversed.len(), 0);
+    assert_eq!(reversed.count(), 0);
+}
@@ -207,6 +207,26 @@ fn test(c: char) {
     assert_eq!(u16c.is_ascii(), c.is_ascii());
     assert_eq!(u16c.to_ascii_lowercase().to_char(), c.to_ascii_lowercase());
     assert_eq!(u16c.to_ascii_uppercase().to_char(), c.to_ascii_uppercase());
+
+    // UCS-2
+    if len == 1 {
+        let ucs2 = Ucs2Char::from_char(c).unwrap();
+        assert_eq!(Ucs2Char::from_unit(reference[0]), Ok(ucs2));
+        assert_eq!(Ucs2Char::from_utf16char(u16c), Ok(ucs2));
+        assert_eq!(Ucs2Char::from_utf8char(u8c), Ok(ucs2));
+        assert_eq!(ucs2.to_unit(), reference[0]);
+        assert_eq!(ucs2.to_char(), c);
+        assert_eq!(Utf16Char::from(ucs2), u16c);
+        assert_eq!(Utf8Char::from(ucs2), u8c);
+        assert_eq!(ucs2.len_utf8(), utf8_len);
+        assert_eq!(ucs2.is_ascii(), c.is_ascii());
+        assert_eq!(&Vec::<u16>::from_iter(Some(ucs2))[..], reference);
+        assert_eq!(format!("{:?}", ucs2), format!("{:?}", c));
+        assert_eq!(format!("{}", ucs2), format!("{}", c));
+        let mut buf = [0; 2];
+        assert_eq!(Ucs2Char::encode_str(&as_str, &mut buf, Ucs2Substitution::Reject), Ok((2*utf8_len, 2)));
+        assert_eq!(buf, [reference[0], reference[0]]);
+    }
 }
 
 
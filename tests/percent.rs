@@ -0,0 +1,100 @@
+/* Copyright 2018 Torbjørn Birch Moltu
+ *
+ * Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+ * http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+ * http://opensource.org/licenses/MIT>, at your option. This file may not be
+ * copied, modified, or distributed except according to those terms.
+ */
+
+//! Tests for percent-encoding.
+
+#![cfg(feature="std")]
+
+extern crate encode_unicode;
+use encode_unicode::percent::{encode, encode_iri, decode, decode_lossy, PercentSet};
+use encode_unicode::percent::PercentSet::*;
+use encode_unicode::error::InvalidPercentEncoding::*;
+use encode_unicode::error::{InvalidUtf8Slice, InvalidUtf8, InvalidUtf8FirstByte, InvalidCodepoint};
+
+static SETS: [PercentSet; 4] = [Path, Query, Userinfo, Form];
+
+#[test]
+fn roundtrips() {
+    let all: String = (0..0x11_00_00).filter_map(std::char::from_u32)
+        .filter(|&c| (c as u32) % 59 < 2  ||  (c as u32) < 0x100 )
+        .collect();
+    for &set in &SETS {
+        let encoded = encode(&all, set);
+        assert!(encoded.bytes().all(|b| b > b' '  &&  b < 0x7f ));
+        assert_eq!(decode(&encoded, set).unwrap(), all);
+        assert_eq!(decode_lossy(&encoded, set), all);
+        let iri = encode_iri(&all, set);
+        assert_eq!(iri.chars().filter(|&c| c >= '\u{80}' ).count(), all.chars().filter(|&c| c >= '\u{80}' ).count());
+        assert_eq!(decode(&iri, set).unwrap(), all);
+    }
+}
+
+#[test]
+fn sets() {
+    let ascii = " !\"#$%&'()*+,-./09:;<=>?@AZ[\\]^_`az{|}~";
+    // Python: quote(ascii, safe="/:@!$&'()*+,;=")
+    assert_eq!(encode(ascii, Path),
+               "%20!%22%23$%25&'()*+,-./09:;%3C=%3E%3F@AZ%5B%5C%5D%5E_%60az%7B%7C%7D~");
+    assert_eq!(encode(ascii, Query),
+               "%20!%22%23$%25%26'()*%2B,-./09:;%3C%3D%3E?@AZ%5B%5C%5D%5E_%60az%7B%7C%7D~");
+    assert_eq!(encode(ascii, Userinfo),
+               "%20!%22%23$%25&'()*+,-.%2F09%3A;%3C=%3E%3F%40AZ%5B%5C%5D%5E_%60az%7B%7C%7D~");
+    // Python: quote_plus(ascii, safe="*~")
+    assert_eq!(encode(ascii, Form),
+               "+%21%22%23%24%25%26%27%28%29*%2B%2C-.%2F09%3A%3B%3C%3D%3E%3F%40AZ%5B%5C%5D%5E_%60az%7B%7C%7D~");
+    assert_eq!(encode("\u{0}\u{7f}", Path), "%00%7F");
+}
+
+#[test]
+fn plus() {
+    assert_eq!(decode("a+b%2B", Form).unwrap(), "a b+");
+    for &set in &[Path, Query, Userinfo] {
+        assert_eq!(decode("a+b%2B", set).unwrap(), "a+b+");
+    }
+    assert_eq!(decode_lossy("+%", Form), " %");
+}
+
+#[test]
+fn malformed() {
+    for s in &["%", "%4", "%4g", "%g4", "% 41", "%+41", "%-1"] {
+        assert_eq!(decode(s, Path), Err(Malformed(0)), "{:?}", s);
+        assert_eq!(decode_lossy(s, Path), *s);
+    }
+    assert_eq!(decode("%%41", Path), Err(Malformed(0)));
+    assert_eq!(decode_lossy("%%41", Path), "%A");
+    assert_eq!(decode("%41%4", Path), Err(Malformed(3)));
+    assert_eq!(decode_lossy("%41%4", Path), "A%4");
+    assert_eq!(decode("æ%", Path), Err(Malformed(2)));
+    assert_eq!(Malformed(2).position(), 2);
+}
+
+#[test]
+fn invalid_utf8() {
+    use encode_unicode::error::InvalidUtf8Slice::{Codepoint, TooShort};
+    assert_eq!(decode("%C0%AE%C0%AE/", Path), Err(Utf8(0, InvalidUtf8Slice::Utf8(InvalidUtf8::OverLong))));
+    assert_eq!(decode("..%2F%E0%80%AF", Path), Err(Utf8(5, InvalidUtf8Slice::Utf8(InvalidUtf8::OverLong))));
+    assert_eq!(decode("%C3%A6%80", Path),
+               Err(Utf8(6, InvalidUtf8Slice::Utf8(InvalidUtf8::FirstByte(InvalidUtf8FirstByte::ContinuationByte)))));
+    assert_eq!(decode("%F4%90%80%80", Path), Err(Utf8(0, Codepoint(InvalidCodepoint::TooHigh))));
+    assert_eq!(decode("%E2%82x%AC", Path), Err(Utf8(0, TooShort(3))));
+    assert_eq!(decode("%E2%82%41", Path), Err(Utf8(0, InvalidUtf8Slice::Utf8(InvalidUtf8::NotAContinuationByte(2)))));
+    assert_eq!(Utf8(6, TooShort(2)).position(), 6);
+}
+
+#[test]
+fn lossy() {
+    // the same as WHATWG's decoder, such as Python's bytes.decode('utf-8', 'replace')
+    assert_eq!(decode_lossy("%C0%AEx", Path), "\u{fffd}\u{fffd}x");
+    assert_eq!(decode_lossy("%E0%80%AF", Path), "\u{fffd}\u{fffd}\u{fffd}");
+    assert_eq!(decode_lossy("%ED%A0%80", Path), "\u{fffd}\u{fffd}\u{fffd}");
+    assert_eq!(decode_lossy("%E2%82%41%E2%82", Path), "\u{fffd}A\u{fffd}");
+    assert_eq!(decode_lossy("%F0%9F%92%E2%82%AC", Path), "\u{fffd}€");
+    assert_eq!(decode_lossy("%FF%F5%80", Path), "\u{fffd}\u{fffd}\u{fffd}");
+    assert_eq!(decode_lossy("%F4%90%80", Path), "\u{fffd}\u{fffd}\u{fffd}");
+    assert_eq!(decode_lossy("%F4%8F%80%F4%8F%BF%BF", Path), "\u{fffd}\u{10ffff}");
+}
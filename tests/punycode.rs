@@ -0,0 +1,68 @@
+/* Copyright 2018 Torbjørn Birch Moltu
+ *
+ * Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+ * http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+ * http://opensource.org/licenses/MIT>, at your option. This file may not be
+ * copied, modified, or distributed except according to those terms.
+ */
+
+//! Tests for Punycode and `xn--` labels.
+
+#![cfg(feature="std")]
+
+extern crate encode_unicode;
+use encode_unicode::punycode;
+use encode_unicode::error::InvalidPunycode::*;
+
+const SAMPLES: &[(&str, &str)] = &[
+    ("bücher", "bcher-kva"),
+    ("münchen", "mnchen-3ya"),
+    ("ü", "tda"),
+    ("💣", "fs8h"),
+    ("3年B組金八先生", "3B-ww4c5e180e575a65lsy2b"),
+    ("安室奈美恵-with-SUPER-MONKEYS", "-with-SUPER-MONKEYS-pc58ag80a8qai00g7n9n"),
+    ("ليهمابتكلموشعربي؟", "egbpdaj6bu4bxfgehfvwxn"),
+    ("他们为什么不说中文", "ihqwcrb4cv8a8dqg056pqjye"),
+    ("Pročprostěnemluvíčesky", "Proprostnemluvesky-uyb24dma41a"),
+    ("a-b-", "a-b--"),
+    ("-", "--"),
+    ("", ""),
+];
+
+#[test]
+fn encode_and_decode() {
+    for &(decoded, encoded) in SAMPLES {
+        assert_eq!(punycode::encode(decoded).as_ref().map(|s| &s[..] ), Ok(encoded));
+        assert_eq!(punycode::decode(encoded).as_ref().map(|s| &s[..] ), Ok(decoded));
+    }
+}
+
+#[test]
+fn uppercase_digits() {
+    assert_eq!(punycode::decode("bcher-KVA").unwrap(), "bücher");
+    assert_eq!(punycode::decode("FS8H").unwrap(), "💣");
+}
+
+#[test]
+fn invalid() {
+    assert_eq!(punycode::decode("æ"), Err(NonBasic(0)));
+    assert_eq!(punycode::decode("a-!"), Err(InvalidDigit(2)));
+    assert_eq!(punycode::decode("bcher-kv"), Err(Truncated));
+    assert_eq!(punycode::decode("99999999999"), Err(Overflow));
+    assert_eq!(punycode::decode("a").unwrap(), "\u{80}");
+    assert!(punycode::decode("zzzzzzzzz").is_err());
+}
+
+#[test]
+fn labels() {
+    assert_eq!(punycode::to_ascii_label("example").unwrap(), "example");
+    assert_eq!(punycode::to_ascii_label("münchen").unwrap(), "xn--mnchen-3ya");
+    assert_eq!(punycode::from_ascii_label("example").unwrap(), "example");
+    assert_eq!(punycode::from_ascii_label("xn--mnchen-3ya").unwrap(), "münchen");
+    assert_eq!(punycode::from_ascii_label("XN--mnchen-3ya").unwrap(), "münchen");
+    assert_eq!(punycode::from_ascii_label("xn-").unwrap(), "xn-");
+    assert_eq!(punycode::from_ascii_label("xn\r\rmnchen-3ya").unwrap(), "xn\r\rmnchen-3ya");
+    assert_eq!(punycode::from_ascii_label("XN\r-mnchen-3ya").unwrap(), "XN\r-mnchen-3ya");
+    assert_eq!(punycode::from_ascii_label("xn--a-!"), Err(InvalidDigit(6)));
+    assert_eq!(punycode::from_ascii_label("xn--æ"), Err(NonBasic(4)));
+}
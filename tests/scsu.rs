@@ -0,0 +1,146 @@
+/* Copyright 2018 Torbjørn Birch Moltu
+ *
+ * Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+ * http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+ * http://opensource.org/licenses/MIT>, at your option. This file may not be
+ * copied, modified, or distributed except according to those terms.
+ */
+
+//! Tests for the Standard Compression Scheme for Unicode.
+
+#![cfg(feature="std")]
+
+extern crate encode_unicode;
+use encode_unicode::{scsu, Utf16Char, StrExt};
+use encode_unicode::error::InvalidScsu::*;
+use std::io::Read;
+
+const SAMPLES: &[&str] = &[
+    "", "plain ascii", "\0\t\r\n\u{1}\u{1f}\u{7f}", "Öl fließt", "Москва",
+    "Pročprostěnemluvíčesky", "Ελληνικά και English", "“quoted” — dashed…",
+    "日本語のテキスト, with ASCII", "3年B組金八先生", "한국어 문장", "\u{e000}\u{f2ff}\u{ffff}",
+    "💣", "💣 bomb 💣", "𝔘𝔫𝔦𝔠𝔬𝔡𝔢 and 😀😃😄", "a💣日本💣b", "\u{10ffff}\u{10000}",
+    "αβγ абв אבג ابج कखग ㄱㄴㄷ ｱｲｳ あいう アイウ",
+];
+
+fn decode_to_string(bytes: &[u8]) -> Result<String,encode_unicode::error::InvalidScsu> {
+    scsu::decode(bytes.iter().cloned()).map(|r| r.map(|u16c| u16c.to_char() ) ).collect()
+}
+
+#[test]
+fn roundtrips() {
+    for s in SAMPLES {
+        let encoded: Vec<u8> = scsu::encode(s.chars()).collect();
+        assert_eq!(decode_to_string(&encoded).as_ref().map(|d| &d[..] ), Ok(*s), "{:?}", encoded);
+        let from_utf16: Vec<u8> = scsu::encode(s.utf16chars()).collect();
+        assert_eq!(from_utf16, encoded);
+    }
+}
+
+#[test]
+fn uts_6_examples() {
+    let german = [0xd6, 0x6c, 0x20, 0x66, 0x6c, 0x69, 0x65, 0xdf, 0x74];
+    assert_eq!(decode_to_string(&german).unwrap(), "Öl fließt");
+    assert_eq!(scsu::encode("Öl fließt".chars()).collect::<Vec<u8>>(), german);
+    let russian = [0x12, 0x9c, 0xbe, 0xc1, 0xba, 0xb2, 0xb0];
+    assert_eq!(decode_to_string(&russian).unwrap(), "Москва");
+    assert_eq!(scsu::encode("Москва".chars()).collect::<Vec<u8>>(), russian);
+}
+
+#[test]
+fn is_smaller() {
+    for &s in &["Всё хорошо, спасибо", "Ελληνικά", "ひらがな"] {
+        let encoded = scsu::encode(s.chars()).count();
+        assert!(encoded <= s.chars().count() + 2, "{:?} became {} bytes", s, encoded);
+    }
+    // switches between two windows
+    let czech = "Příliš žluťoučký kůň";
+    assert!(scsu::encode(czech.chars()).count() < czech.len());
+    let cjk = "中文文本中文文本";
+    assert_eq!(scsu::encode(cjk.chars()).count(), 1 + 2*cjk.chars().count());
+}
+
+#[test]
+fn windows() {
+    // SD3 with offset 0x0370, then quoted from the static Latin-1 window
+    assert_eq!(decode_to_string(&[0x1b, 0xfb, 0xc1, 0x02, 0x69, 0xc2]).unwrap(), "αéβ");
+    // SDX to window 7 at U+1F600 and back to window 0 with SC0
+    assert_eq!(decode_to_string(&[0x0b, 0xe1, 0xec, 0x80, 0x10, 0xe5]).unwrap(), "😀å");
+    // UD1 leaves Unicode mode
+    assert_eq!(decode_to_string(&[0x0f, 0x4e, 0x2d, 0xe9, 0x08, 0x80, 0x20]).unwrap(), "中Ѐ ");
+    // UQU quotes units that would otherwise be tags
+    assert_eq!(decode_to_string(&[0x0f, 0xf0, 0xe0, 0x00, 0xe0, b'a']).unwrap(), "\u{e000}a");
+}
+
+#[test]
+fn invalid() {
+    assert_eq!(decode_to_string(&[b'a', 0x0c]), Err(ReservedTag(1)));
+    assert_eq!(decode_to_string(&[0x0f, 0x00, 0x61, 0xf2]), Err(ReservedTag(3)));
+    assert_eq!(decode_to_string(&[0x18, 0x00]), Err(ReservedWindowOffset(0)));
+    assert_eq!(decode_to_string(&[b'a', 0x0f, 0xe8, 0xa8]), Err(ReservedWindowOffset(2)));
+    assert_eq!(decode_to_string(&[0x0b, 0x00]), Err(Truncated(0)));
+    assert_eq!(decode_to_string(&[0x0f, 0x00, 0x61, 0x00]), Err(Truncated(3)));
+    assert_eq!(decode_to_string(&[b'x', 0x01]), Err(Truncated(1)));
+}
+
+#[test]
+fn surrogates() {
+    assert_eq!(decode_to_string(&[0x0e, 0xd8, 0x3d, 0x0e, 0xdc, 0xa3]).unwrap(), "💣");
+    assert_eq!(decode_to_string(&[0x0f, 0xd8, 0x3d, 0xdc, 0xa3]).unwrap(), "💣");
+    let mut decoder = scsu::decode(vec![0x0e, 0xd8, 0x3d, b'a', 0x0e, 0xdc, 0xa3]);
+    assert_eq!(decoder.next(), Some(Err(UnpairedSurrogate(0))));
+    assert_eq!(decoder.next(), Some(Ok(Utf16Char::from('a'))));
+    assert_eq!(decoder.next(), Some(Err(UnpairedSurrogate(4))));
+    assert_eq!(decoder.next(), None);
+    let mut decoder = scsu::decode(vec![0x0f, 0xd8, 0x3d, 0xd8, 0x3d]);
+    assert_eq!(decoder.next(), Some(Err(UnpairedSurrogate(1))));
+    assert_eq!(decoder.next(), Some(Err(UnpairedSurrogate(3))));
+    assert_eq!(decoder.next(), None);
+}
+
+#[test]
+fn read_and_next_can_be_mixed() {
+    let text = "Ελληνικά 日本語 💣";
+    let expected: Vec<u8> = scsu::encode(text.chars()).collect();
+    let mut encoder = scsu::encode(text.chars());
+    let mut encoded = Vec::new();
+    let mut buf = [0; 3];
+    while let Some(b) = encoder.next() {
+        encoded.push(b);
+        let read = encoder.read(&mut buf).unwrap();
+        encoded.extend_from_slice(&buf[..read]);
+    }
+    assert_eq!(encoded, expected);
+    assert_eq!(encoder.read(&mut buf).unwrap(), 0);
+}
+
+#[test]
+fn size_hint_is_correct() {
+    for s in SAMPLES {
+        let mut encoder = scsu::encode(s.chars());
+        loop {
+            let (min, max) = encoder.size_hint();
+            let remaining = encoder.clone().count();
+            assert!(min <= remaining  &&  remaining <= max.unwrap(),
+                    "{} <= {} <= {:?} for {:?}", min, remaining, max, s);
+            if encoder.next().is_none() {
+                break;
+            }
+        }
+    }
+}
+
+#[test]
+fn size_hint_does_not_overflow() {
+    let encoder = scsu::encode((0..usize::MAX).map(|_| 'a' ));
+    assert_eq!(encoder.size_hint(), (usize::MAX, None));
+    let encoder = scsu::encode((0..usize::MAX/4 + 1).map(|_| 'a' ));
+    assert_eq!(encoder.size_hint(), (usize::MAX/4 + 1, None));
+    // an unpaired leading surrogate in unicode mode queues the following 'A'
+    let bytes = [0x0f, 0xd8, 0x00, 0x00, 0x41].iter().cloned()
+        .chain((0..usize::MAX).map(|_| 0x41 ));
+    let mut decoder = scsu::decode(bytes);
+    assert_eq!(decoder.size_hint(), (0, None));
+    assert_eq!(decoder.next(), Some(Err(UnpairedSurrogate(1))));
+    assert_eq!(decoder.size_hint(), (1, None));
+}
@@ -0,0 +1,104 @@
+/* Copyright 2018 Torbjørn Birch Moltu
+ *
+ * Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+ * http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+ * http://opensource.org/licenses/MIT>, at your option. This file may not be
+ * copied, modified, or distributed except according to those terms.
+ */
+
+//! Tests for the GSM 03.38 alphabet and splitting of concatenated SMS.
+
+#![cfg(feature="std")]
+
+extern crate encode_unicode;
+use encode_unicode::sms::*;
+use encode_unicode::sms::SmsEncoding::*;
+
+#[test]
+fn alphabet_roundtrips() {
+    for septet in 0..128 {
+        match from_gsm7(septet) {
+            Some(c) => assert_eq!(to_gsm7(c), Some((septet, None)), "{:?}", c),
+            None => assert_eq!(septet, 0x1b),
+        }
+    }
+    for septet in 0..128 {
+        if let Some(c) = from_gsm7_extension(septet) {
+            assert_eq!(to_gsm7(c), Some((0x1b, Some(septet))), "{:?}", c);
+            assert_eq!(Gsm7.char_len(c), Some(2));
+        }
+    }
+    assert_eq!(from_gsm7(0x80), None);
+    assert_eq!(from_gsm7_extension(0x41), None);
+}
+
+#[test]
+fn not_in_alphabet() {
+    for &c in &['`', '\u{1b}', 'ç', '\u{ff}', 'ő', '😀'] {
+        assert_eq!(to_gsm7(c), None, "{:?}", c);
+        assert_eq!(Gsm7.char_len(c), None);
+    }
+    assert_eq!(SmsEncoding::for_text("a`b"), Ucs2);
+    assert_eq!(Gsm7.text_len("a`b"), None);
+}
+
+#[test]
+fn lengths() {
+    assert_eq!(Gsm7.text_len("[Δ]"), Some(5));
+    assert_eq!(Ucs2.text_len("[Δ]"), Some(3));
+    assert_eq!(Ucs2.text_len("a😀"), Some(3));
+}
+
+#[test]
+fn single_message_limits() {
+    let fits = "a".repeat(160);
+    assert_eq!(segments(&fits).collect::<Vec<_>>(), [&fits[..]]);
+    let too_long = "a".repeat(161);
+    let parts: Vec<&str> = segments(&too_long).collect();
+    assert_eq!(parts, [&too_long[..153], &too_long[153..]]);
+
+    let fits = "å😀".repeat(23) + "æ";
+    assert_eq!(Ucs2.text_len(&fits), Some(70));
+    assert_eq!(segments(&fits).count(), 1);
+    let too_long = fits + "ø";
+    assert_eq!(segments(&too_long).count(), 2);
+}
+
+#[test]
+fn empty_is_one_part() {
+    let mut parts = segments("");
+    assert_eq!(parts.size_hint(), (1, Some(1)));
+    assert_eq!(parts.next(), Some(""));
+    assert_eq!(parts.size_hint(), (0, Some(0)));
+    assert_eq!(parts.next(), None);
+}
+
+#[test]
+fn doesnt_split_escapes() {
+    // 152 septets followed by a two-septet character
+    let text = "a".repeat(152) + "€" + &"b".repeat(10);
+    let parts: Vec<&str> = segments(&text).collect();
+    assert_eq!(parts.len(), 2);
+    assert_eq!(parts[0], &text[..152]);
+    assert!(parts[1].starts_with('€'));
+    assert_eq!(parts.concat(), text);
+}
+
+#[test]
+fn doesnt_split_surrogate_pairs() {
+    let text = "x".repeat(66) + "😀" + "yyy";
+    let parts: Vec<&str> = segments(&text).collect();
+    assert_eq!(parts, [&text[..66], &text[66..]]);
+    for part in parts {
+        assert!(Ucs2.text_len(part).unwrap() <= 67);
+    }
+}
+
+#[test]
+fn size_hint_is_correct() {
+    for text in &["a".repeat(1000), "{".repeat(1000), "😀".repeat(300), "ø".repeat(500)] {
+        let (min, max) = segments(text).size_hint();
+        let count = segments(text).count();
+        assert!(min <= count  &&  count <= max.unwrap(), "{} <= {} <= {:?}", min, count, max);
+    }
+}
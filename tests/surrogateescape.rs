@@ -0,0 +1,96 @@
+/* Copyright 2018 Torbjørn Birch Moltu
+ *
+ * Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+ * http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+ * http://opensource.org/licenses/MIT>, at your option. This file may not be
+ * copied, modified, or distributed except according to those terms.
+ */
+
+//! Tests for escaping invalid UTF-8 as lone surrogates, as Python does.
+
+#![cfg(feature="std")]
+
+extern crate encode_unicode;
+use encode_unicode::surrogateescape::*;
+use encode_unicode::error::InvalidSurrogateEscape::*;
+
+/// Produced by Python's `bytes.decode('utf-8', 'surrogateescape')`
+const PYTHON: &[(&[u8], &[u16], &[u8])] = &[
+    (b"\xe2\x82", &[0xdce2, 0xdc82], b"\xed\xb3\xa2\xed\xb2\x82"),
+    (b"\xe2\x82a", &[0xdce2, 0xdc82, 0x61], b"\xed\xb3\xa2\xed\xb2\x82a"),
+    (b"\xe2a\x82", &[0xdce2, 0x61, 0xdc82], b"\xed\xb3\xa2a\xed\xb2\x82"),
+    (b"\xc0\xaf", &[0xdcc0, 0xdcaf], b"\xed\xb3\x80\xed\xb2\xaf"),
+    (b"\xed\xa0\x80", &[0xdced, 0xdca0, 0xdc80], b"\xed\xb3\xad\xed\xb2\xa0\xed\xb2\x80"),
+    (b"\xf4\x90\x80\x80", &[0xdcf4, 0xdc90, 0xdc80, 0xdc80],
+     b"\xed\xb3\xb4\xed\xb2\x90\xed\xb2\x80\xed\xb2\x80"),
+    (b"\xf0\x9f\x92\xa3\xff", &[0xd83d, 0xdca3, 0xdcff], b"\xf0\x9f\x92\xa3\xed\xb3\xbf"),
+    (b"\x80\xbf\xc3", &[0xdc80, 0xdcbf, 0xdcc3], b"\xed\xb2\x80\xed\xb2\xbf\xed\xb3\x83"),
+    (b"\xf8\x88\x80\x80\x80", &[0xdcf8, 0xdc88, 0xdc80, 0xdc80, 0xdc80],
+     b"\xed\xb3\xb8\xed\xb2\x88\xed\xb2\x80\xed\xb2\x80\xed\xb2\x80"),
+];
+
+#[test]
+fn like_python() {
+    for &(bytes, utf16, wtf8) in PYTHON {
+        assert_eq!(decode_utf16(bytes).collect::<Vec<u16>>(), utf16, "{:?}", bytes);
+        assert_eq!(decode_wtf8(bytes).collect::<Vec<u8>>(), wtf8, "{:?}", bytes);
+        assert_eq!(encode_utf16(utf16.iter().cloned()).collect::<Result<Vec<u8>,_>>().unwrap(), bytes);
+        assert_eq!(encode_wtf8(wtf8).collect::<Result<Vec<u8>,_>>().unwrap(), bytes);
+    }
+}
+
+#[test]
+fn valid_utf8_is_unchanged() {
+    let text = "ASCII, Blåbærsyltetøy, 日本語 and 💣\u{0}\u{7f}\u{80}\u{ffff}\u{10ffff}";
+    let utf16: Vec<u16> = decode_utf16(text.as_bytes()).collect();
+    assert_eq!(utf16, text.encode_utf16().collect::<Vec<u16>>());
+    assert_eq!(decode_wtf8(text.as_bytes()).collect::<Vec<u8>>(), text.as_bytes());
+}
+
+#[test]
+fn all_byte_pairs_roundtrip() {
+    for a in 0..256u32 {
+        for b in 0..256u32 {
+            let bytes = [a as u8, b as u8, 0xe5, a as u8, 0x80, b as u8];
+            let utf16: Vec<u16> = decode_utf16(&bytes).collect();
+            let wtf8: Vec<u8> = decode_wtf8(&bytes).collect();
+            assert_eq!(encode_utf16(utf16).collect::<Result<Vec<u8>,_>>(), Ok(bytes.to_vec()));
+            assert_eq!(encode_wtf8(&wtf8).collect::<Result<Vec<u8>,_>>(), Ok(bytes.to_vec()));
+        }
+    }
+}
+
+#[test]
+fn size_hints() {
+    for &(bytes, utf16, wtf8) in PYTHON {
+        let mut decoder = decode_utf16(bytes);
+        for len in (0..utf16.len()+1).rev() {
+            let (min, max) = decoder.size_hint();
+            assert!(min <= len  &&  max.unwrap() >= len, "{:?}", decoder);
+            decoder.next();
+        }
+        let mut decoder = decode_wtf8(bytes);
+        for len in (0..wtf8.len()+1).rev() {
+            let (min, max) = decoder.size_hint();
+            assert!(min <= len  &&  max.unwrap() >= len, "{:?}", decoder);
+            decoder.next();
+        }
+    }
+}
+
+#[test]
+fn unescapable_utf16() {
+    let encoded: Vec<_> = encode_utf16(vec![0xdc7f, b'a' as u16, 0xd800, b'b' as u16, 0xdbff]).collect();
+    assert_eq!(encoded, [Err(UnpairedSurrogate(0)), Ok(b'a'), Err(UnpairedSurrogate(2)), Ok(b'b'),
+                         Err(UnpairedSurrogate(4))]);
+    // a pair is never two escapes
+    let encoded: Vec<_> = encode_utf16(vec![0xd800, 0xdc80]).collect();
+    assert_eq!(encoded, [Ok(0xf0), Ok(0x90), Ok(0x82), Ok(0x80)]);
+}
+
+#[test]
+fn unescapable_wtf8() {
+    let encoded: Vec<_> = encode_wtf8(b"\xed\xb1\xbfa\xed\xafb\xc3").collect();
+    assert_eq!(encoded, [Err(UnpairedSurrogate(0)), Ok(b'a'), Err(InvalidWtf8(4)), Err(InvalidWtf8(5)),
+                         Ok(b'b'), Err(InvalidWtf8(7))]);
+}
@@ -0,0 +1,83 @@
+/* Copyright 2018 Torbjørn Birch Moltu
+ *
+ * Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+ * http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+ * http://opensource.org/licenses/MIT>, at your option. This file may not be
+ * copied, modified, or distributed except according to those terms.
+ */
+
+//! Tests for UTF-7 and IMAP modified UTF-7.
+
+#![cfg(feature="std")]
+
+extern crate encode_unicode;
+use encode_unicode::utf7;
+use encode_unicode::error::InvalidUtf7::*;
+
+const SAMPLES: &[&str] = &[
+    "", "plain ascii", "+", "&", "+-&-", "a+b&c", "~\\", "\t\r\n\u{0}\u{7f}",
+    "æøå", "A\u{2262}\u{391}.", "日本語", "💣 bomb 💣", "\u{ffff}\u{10000}\u{10ffff}",
+    "-💣-", "💣-", "💣a", "💣/", "💣,",
+];
+
+#[test]
+fn rfc_2152_examples() {
+    assert_eq!(utf7::encode("A\u{2262}\u{391}."), "A+ImIDkQ.");
+    assert_eq!(utf7::decode("A+ImIDkQ.").unwrap(), "A\u{2262}\u{391}.");
+    assert_eq!(utf7::decode("Hi Mom -+Jjo--!").unwrap(), "Hi Mom -\u{263a}-!");
+    assert_eq!(utf7::decode("+ZeVnLIqe-").unwrap(), "日本語");
+    assert_eq!(utf7::encode("日本語"), "+ZeVnLIqe");
+    assert_eq!(utf7::encode("1 + 1"), "1 +- 1");
+}
+
+#[test]
+fn rfc_3501_example() {
+    let decoded = "~peter/mail/\u{53f0}\u{5317}/\u{65e5}\u{672c}\u{8a9e}";
+    assert_eq!(utf7::encode_imap(decoded), "~peter/mail/&U,BTFw-/&ZeVnLIqe-");
+    assert_eq!(utf7::decode_imap("~peter/mail/&U,BTFw-/&ZeVnLIqe-").unwrap(), decoded);
+}
+
+#[test]
+fn roundtrips() {
+    for s in SAMPLES {
+        let encoded = utf7::encode(s);
+        assert!(encoded.is_ascii(), "{:?} -> {:?}", s, encoded);
+        assert_eq!(utf7::decode(&encoded).as_ref().map(|s| &s[..] ), Ok(*s), "{:?}", encoded);
+        let encoded = utf7::encode_imap(s);
+        assert!(encoded.bytes().all(|b| (0x20..0x7f).contains(&b) ), "{:?} -> {:?}", s, encoded);
+        assert_eq!(utf7::decode_imap(&encoded).as_ref().map(|s| &s[..] ), Ok(*s), "{:?}", encoded);
+    }
+}
+
+#[test]
+fn surrogate_pairs() {
+    assert_eq!(utf7::encode("💣"), "+2D3cow");
+    assert_eq!(utf7::decode("+2D3cow-").unwrap(), "💣");
+    assert_eq!(utf7::decode("+2D0-"), Err(UnpairedSurrogate(0)));
+    assert_eq!(utf7::decode("a+3KM-"), Err(UnpairedSurrogate(1)));
+    assert_eq!(utf7::decode_imap("&2D0AYQ-"), Err(UnpairedSurrogate(0)));
+}
+
+#[test]
+fn malformed_shifts() {
+    assert_eq!(utf7::decode("+"), Err(EmptyShift(0)));
+    assert_eq!(utf7::decode("a+!"), Err(EmptyShift(1)));
+    assert_eq!(utf7::decode("+AG"), Err(EmptyShift(0)));
+    assert_eq!(utf7::decode("+AGF-"), Err(InvalidPadding(0)));
+    assert_eq!(utf7::decode("+AGEA-"), Err(InvalidPadding(0)));
+    assert_eq!(utf7::decode("+AGE/-"), Err(InvalidPadding(0)));
+    assert_eq!(utf7::decode_imap("&AOU,-"), Err(InvalidPadding(0)));
+    assert_eq!(utf7::decode_imap("&AOU"), Err(UnterminatedShift(4)));
+    assert_eq!(utf7::decode_imap("&AOU."), Err(UnterminatedShift(4)));
+    assert_eq!(utf7::decode_imap("&ACY-"), Err(DirectlyEncodable(0)));
+    assert_eq!(utf7::decode("+AGE-").unwrap(), "a");
+}
+
+#[test]
+fn unexpected_characters() {
+    assert_eq!(utf7::decode("æ"), Err(UnexpectedCharacter(0)));
+    assert_eq!(utf7::decode("+AOU-ø"), Err(UnexpectedCharacter(5)));
+    assert_eq!(utf7::decode_imap("a\tb"), Err(UnexpectedCharacter(1)));
+    assert_eq!(utf7::decode_imap("\u{7f}"), Err(UnexpectedCharacter(0)));
+    assert_eq!(utf7::decode("a\tb").unwrap(), "a\tb");
+}
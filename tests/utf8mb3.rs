@@ -0,0 +1,68 @@
+/* Copyright 2018 Torbjørn Birch Moltu
+ *
+ * Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+ * http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+ * http://opensource.org/licenses/MIT>, at your option. This file may not be
+ * copied, modified, or distributed except according to those terms.
+ */
+
+//! Tests for replacing characters that MySQL's legacy `utf8` can't store.
+
+#![cfg(feature="std")]
+
+use std::borrow::Cow;
+extern crate encode_unicode;
+use encode_unicode::{StrExt, IterExt, Utf8Char, Utf8mb3Replacement};
+use encode_unicode::Utf8mb3Replacement::*;
+
+#[test]
+fn find_first() {
+    assert_eq!("".find_utf8mb4(), None);
+    assert_eq!("\u{ffff}\u{7ff}\u{7f}".find_utf8mb4(), None);
+    assert_eq!("\u{10000}".find_utf8mb4(), Some((0, Utf8Char::from('\u{10000}'))));
+    assert_eq!("é\u{10ffff}\u{10000}".find_utf8mb4(), Some((2, Utf8Char::from('\u{10ffff}'))));
+}
+
+#[test]
+fn borrows_when_unchanged() {
+    for &replacement in &[ReplacementCharacter, Escape, Remove] {
+        match "‽ æøå".to_utf8mb3(replacement) {
+            Cow::Borrowed(s) => assert_eq!(s, "‽ æøå"),
+            Cow::Owned(_) => panic!("allocated for {:?}", replacement),
+        }
+    }
+}
+
+#[test]
+fn replaces_all() {
+    let s = "🂠a\u{10000}\u{10ffff}";
+    assert_eq!(s.to_utf8mb3(ReplacementCharacter), "\u{fffd}a\u{fffd}\u{fffd}");
+    assert_eq!(s.to_utf8mb3(Escape), "\\u{1f0a0}a\\u{10000}\\u{10ffff}");
+    assert_eq!(s.to_utf8mb3(Remove), "a");
+}
+
+#[test]
+fn streaming() {
+    fn bytes(s: &str,  replacement: Utf8mb3Replacement) -> Vec<u8> {
+        let chars: Vec<Utf8Char> = s.utf8chars().collect();
+        chars.iter().to_utf8mb3(replacement).to_bytes().collect()
+    }
+    let s = "💣 bomb 💣";
+    for &replacement in &[ReplacementCharacter, Escape, Remove] {
+        assert_eq!(bytes(s, replacement), s.to_utf8mb3(replacement).as_bytes());
+    }
+    for u8c in "💣\u{10000}".utf8chars().to_utf8mb3(Escape) {
+        assert!(u8c.is_ascii());
+    }
+}
+
+#[test]
+fn size_hint() {
+    let mut iter = "💣x".utf8chars().to_utf8mb3(Escape);
+    let (min, max) = iter.size_hint();
+    assert!(min <= 10  &&  max.unwrap() >= 10);
+    assert_eq!(iter.next(), Some(Utf8Char::from('\\')));
+    let remaining = iter.clone().count();
+    let (min, max) = iter.size_hint();
+    assert!(min <= remaining  &&  max.unwrap() >= remaining);
+}